@@ -170,7 +170,7 @@ fn new_chain() -> (
     let shared = SharedBuilder::<CacheDB<RocksDB>>::default()
         .db(&DBConfig {
             path: db_dir.path().to_owned(),
-            options: None,
+            ..Default::default()
         })
         .consensus(consensus)
         .build();
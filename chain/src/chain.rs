@@ -6,28 +6,36 @@ use ckb_core::service::{Request, DEFAULT_CHANNEL_SIZE, SIGNAL_CHANNEL_SIZE};
 use ckb_core::transaction::ProposalShortId;
 use ckb_core::{header::Header, BlockNumber};
 use ckb_notify::NotifyController;
-use ckb_shared::cell_set::CellSetDiff;
+use ckb_shared::cell_set::{CellSetChanges, CellSetDiff};
 use ckb_shared::chain_state::ChainState;
 use ckb_shared::error::SharedError;
 use ckb_shared::shared::Shared;
-use ckb_shared::store::{ChainStore, StoreBatch};
+use ckb_shared::store::{ChainStore, PrunedTransaction, StoreBatch};
 use ckb_traits::{BlockMedianTimeContext, ChainProvider};
+use ckb_util::Mutex;
 use ckb_verification::{BlockVerifier, TransactionsVerifier, Verifier};
 use crossbeam_channel::{self, select, Receiver, Sender};
 use failure::Error as FailureError;
 use faketime::unix_time_as_millis;
-use fnv::FnvHashSet;
+use fnv::{FnvHashMap, FnvHashSet};
 use log::{self, debug, error, log_enabled};
 use numext_fixed_hash::H256;
 use numext_fixed_uint::U256;
 use std::cmp;
 use std::sync::Arc;
 use std::thread;
+use std::time::{Duration, Instant};
 use stop_handler::{SignalSender, StopHandler};
 
+/// The maximum number of block bodies pruned when a new tip is inserted
+const MAX_PRUNED_BLOCKS_PER_TIP: BlockNumber = 100;
+/// The fee estimator statistics are persisted in the batch of every this many tips
+const FEE_STATS_PERSIST_INTERVAL: BlockNumber = 100;
+
 #[derive(Clone)]
 pub struct ChainController {
     process_block_sender: Sender<Request<Arc<Block>, Result<(), FailureError>>>,
+    metrics: Arc<ChainMetrics>,
     stop: StopHandler<()>,
 }
 
@@ -41,6 +49,29 @@ impl ChainController {
     pub fn process_block(&self, block: Arc<Block>) -> Result<(), FailureError> {
         Request::call(&self.process_block_sender, block).expect("process_block() failed")
     }
+
+    pub fn metrics(&self) -> Arc<ChainMetrics> {
+        Arc::clone(&self.metrics)
+    }
+}
+
+/// The blocks processed by the chain service
+#[derive(Debug, Default)]
+pub struct ChainMetrics {
+    // the number of processed blocks and the total time of verifying and inserting them
+    processed_blocks: Mutex<(u64, Duration)>,
+}
+
+impl ChainMetrics {
+    fn record(&self, elapsed: Duration) {
+        let mut processed_blocks = self.processed_blocks.lock();
+        processed_blocks.0 += 1;
+        processed_blocks.1 += elapsed;
+    }
+
+    pub fn processed_blocks(&self) -> (u64, Duration) {
+        *self.processed_blocks.lock()
+    }
 }
 
 struct ChainReceivers {
@@ -161,6 +192,8 @@ impl<CS: ChainStore + 'static> ChainService<CS> {
         let receivers = ChainReceivers {
             process_block_receiver,
         };
+        let metrics = Arc::new(ChainMetrics::default());
+        let thread_metrics = Arc::clone(&metrics);
         let thread = thread_builder
             .spawn(move || loop {
                 select! {
@@ -169,7 +202,10 @@ impl<CS: ChainStore + 'static> ChainService<CS> {
                     },
                     recv(receivers.process_block_receiver) -> msg => match msg {
                         Ok(Request { responder, arguments: block }) => {
-                            let _ = responder.send(self.process_block(block));
+                            let start = Instant::now();
+                            let result = self.process_block(block);
+                            thread_metrics.record(start.elapsed());
+                            let _ = responder.send(result);
                         },
                         _ => {
                             error!(target: "chain", "process_block_receiver closed");
@@ -183,6 +219,7 @@ impl<CS: ChainStore + 'static> ChainService<CS> {
 
         ChainController {
             process_block_sender,
+            metrics,
             stop,
         }
     }
@@ -207,7 +244,7 @@ impl<CS: ChainStore + 'static> ChainService<CS> {
         let mut new_best_block = false;
         let mut total_difficulty = U256::zero();
 
-        let mut cell_set_diff = CellSetDiff::default();
+        let mut cell_set_changes = CellSetChanges::default();
         let mut fork = ForkChanges::default();
         let mut chain_state = self.shared.chain_state().lock();
         let tip_number = chain_state.tip_number();
@@ -251,11 +288,21 @@ impl<CS: ChainStore + 'static> ChainService<CS> {
                 &cannon_total_difficulty - &current_total_difficulty
             );
 
-            self.find_fork(&mut fork, tip_number, &block, ext);
-            cell_set_diff = self.reconcile_main_chain(&mut batch, &mut fork, &mut chain_state)?;
+            self.find_fork(&mut fork, tip_number, &block, ext)?;
+            let cell_set_diff =
+                self.reconcile_main_chain(&mut batch, &mut fork, &mut chain_state)?;
+            cell_set_changes = chain_state
+                .cell_set()
+                .new_overlay(&cell_set_diff)
+                .into_changes();
+            batch.update_cell_set(&cell_set_changes)?;
             self.update_index(&mut batch, &fork.detached_blocks, &fork.attached_blocks)?;
             self.update_proposal_ids(&mut chain_state, &fork);
             batch.insert_tip_header(&block.header())?;
+            // the statistics change slowly, losing the last blocks of them on a crash is harmless
+            if block.header().number() % FEE_STATS_PERSIST_INTERVAL == 0 {
+                batch.insert_fee_stats(&chain_state.fee_stats())?;
+            }
             new_best_block = true;
 
             total_difficulty = cannon_total_difficulty;
@@ -270,13 +317,14 @@ impl<CS: ChainStore + 'static> ChainService<CS> {
             // then, update tx_pool
             let detached_proposal_id = chain_state.proposal_ids_finalize(tip_header.number());
             fork.detached_proposal_id = detached_proposal_id;
-            chain_state.update_tip(tip_header, total_difficulty, cell_set_diff);
+            chain_state.update_tip(tip_header, total_difficulty, cell_set_changes);
             chain_state.update_tx_pool_for_reorg(
                 fork.detached_blocks().iter(),
                 fork.attached_blocks().iter(),
                 fork.detached_proposal_id().iter(),
                 self.shared.consensus().max_block_cycles(),
             );
+            self.prune_blocks(&chain_state)?;
             if log_enabled!(target: "chain", log::Level::Debug) {
                 self.print_chain(&chain_state, 10);
             }
@@ -287,6 +335,72 @@ impl<CS: ChainStore + 'static> ChainService<CS> {
         Ok(())
     }
 
+    // Discard the bodies of the main chain blocks deeper than `prune_depth`, a few blocks per
+    // new tip so that a node enabling the option catches up gradually. Their transactions are
+    // kept until all the outputs are spent by pruned blocks, those spends can't be reverted.
+    pub(crate) fn prune_blocks(&self, chain_state: &ChainState<CS>) -> Result<(), FailureError> {
+        let prune_depth = match self.shared.prune_depth() {
+            Some(depth) => depth,
+            None => return Ok(()),
+        };
+        let target = match chain_state.tip_number().checked_sub(prune_depth) {
+            Some(number) => number,
+            None => return Ok(()),
+        };
+        let store = self.shared.store();
+        // genesis is never pruned
+        let start = store.get_pruned_number().unwrap_or(0) + 1;
+        if start > target {
+            return Ok(());
+        }
+        let end = cmp::min(target, start + MAX_PRUNED_BLOCKS_PER_TIP - 1);
+
+        let mut pruned_txs: FnvHashMap<H256, PrunedTransaction> = FnvHashMap::default();
+        let mut batch = store.new_batch()?;
+        for number in start..=end {
+            let hash = store
+                .get_block_hash(number)
+                .expect("main chain block hash stored");
+            let transactions = match store.get_block_body(&hash) {
+                Some(transactions) => transactions,
+                None => continue,
+            };
+            for tx in transactions {
+                for out_point in tx.input_pts() {
+                    if out_point.is_null() {
+                        continue;
+                    }
+                    let tx_hash = out_point.tx_hash;
+                    // the genesis transactions are never in the pruned column
+                    if !pruned_txs.contains_key(&tx_hash) {
+                        match store.get_pruned_transaction(&tx_hash) {
+                            Some(pruned_tx) => {
+                                pruned_txs.insert(tx_hash.clone(), pruned_tx);
+                            }
+                            None => continue,
+                        }
+                    }
+                    if let Some(pruned_tx) = pruned_txs.get_mut(&tx_hash) {
+                        pruned_tx.spent += 1;
+                    }
+                }
+                pruned_txs.insert(tx.hash(), PrunedTransaction::new(tx));
+            }
+            batch.delete_block_body(&hash)?;
+        }
+        for (tx_hash, pruned_tx) in &pruned_txs {
+            if pruned_tx.is_all_spent() {
+                batch.delete_pruned_transaction(tx_hash)?;
+            } else {
+                batch.insert_pruned_transaction(tx_hash, pruned_tx)?;
+            }
+        }
+        batch.insert_pruned_number(end)?;
+        batch.commit()?;
+        debug!(target: "chain", "pruned block bodies {} to {}", start, end);
+        Ok(())
+    }
+
     pub(crate) fn update_proposal_ids(&self, chain_state: &mut ChainState<CS>, fork: &ForkChanges) {
         for blk in fork.detached_blocks() {
             chain_state.remove_proposal_ids(&blk);
@@ -312,23 +426,32 @@ impl<CS: ChainStore + 'static> ChainService<CS> {
         Ok(())
     }
 
+    // The body of a block on either side of the fork, a reorg can't detach the main chain blocks
+    // whose bodies have been pruned
+    fn fork_block(&self, hash: &H256, number: BlockNumber) -> Result<Block, FailureError> {
+        match self.shared.block(hash) {
+            Some(block) => Ok(block),
+            None if self.shared.store().is_pruned(hash) => {
+                Err(SharedError::PrunedFork(number).into())
+            }
+            None => Err(SharedError::MissingBlockBody(hash.clone()).into()),
+        }
+    }
+
     fn alignment_fork(
         &self,
         fork: &mut ForkChanges,
         index: &mut GlobalIndex,
         new_tip_number: BlockNumber,
         current_tip_number: BlockNumber,
-    ) {
+    ) -> Result<(), FailureError> {
         if new_tip_number <= current_tip_number {
             for bn in new_tip_number..=current_tip_number {
                 let hash = self
                     .shared
                     .block_hash(bn)
                     .expect("block hash stored before alignment_fork");
-                let old_block = self
-                    .shared
-                    .block(&hash)
-                    .expect("block data stored before alignment_fork");
+                let old_block = self.fork_block(&hash, bn)?;
                 fork.detached_blocks.push(old_block);
             }
         } else {
@@ -344,17 +467,19 @@ impl<CS: ChainStore + 'static> ChainService<CS> {
                         index.unseen = false;
                     }
                 }
-                let new_block = self
-                    .shared
-                    .block(&index.hash)
-                    .expect("block data stored before alignment_fork");
+                let new_block = self.fork_block(&index.hash, index.number)?;
                 index.forward(new_block.header().parent_hash().clone());
                 fork.attached_blocks.push(new_block);
             }
         }
+        Ok(())
     }
 
-    fn find_fork_until_latest_common(&self, fork: &mut ForkChanges, index: &mut GlobalIndex) {
+    fn find_fork_until_latest_common(
+        &self,
+        fork: &mut ForkChanges,
+        index: &mut GlobalIndex,
+    ) -> Result<(), FailureError> {
         loop {
             if index.number == 0 {
                 break;
@@ -366,10 +491,7 @@ impl<CS: ChainStore + 'static> ChainService<CS> {
             if detached_hash == index.hash {
                 break;
             }
-            let detached_blocks = self
-                .shared
-                .block(&detached_hash)
-                .expect("detached block stored before find_fork_until_latest_common");
+            let detached_blocks = self.fork_block(&detached_hash, index.number)?;
             fork.detached_blocks.push(detached_blocks);
 
             if index.unseen {
@@ -384,13 +506,11 @@ impl<CS: ChainStore + 'static> ChainService<CS> {
                 }
             }
 
-            let attached_block = self
-                .shared
-                .block(&index.hash)
-                .expect("attached block stored before find_fork_until_latest_common");
+            let attached_block = self.fork_block(&index.hash, index.number)?;
             index.forward(attached_block.header().parent_hash().clone());
             fork.attached_blocks.push(attached_block);
         }
+        Ok(())
     }
 
     pub(crate) fn find_fork(
@@ -399,7 +519,7 @@ impl<CS: ChainStore + 'static> ChainService<CS> {
         current_tip_number: BlockNumber,
         new_tip_block: &Block,
         new_tip_ext: BlockExt,
-    ) {
+    ) -> Result<(), FailureError> {
         let new_tip_number = new_tip_block.header().number();
         fork.dirty_exts.push(new_tip_ext);
 
@@ -417,10 +537,34 @@ impl<CS: ChainStore + 'static> ChainService<CS> {
         // then detached_blocks.extend(chain[new_tip_number .. =current_tip_number])
         // if new_tip_number > current_tip_number
         // then attached_blocks.extend(forks[current_tip_number + 1 .. =new_tip_number])
-        self.alignment_fork(fork, &mut index, new_tip_number, current_tip_number);
+        self.alignment_fork(fork, &mut index, new_tip_number, current_tip_number)?;
 
         // find latest common ancestor
-        self.find_fork_until_latest_common(fork, &mut index);
+        self.find_fork_until_latest_common(fork, &mut index)?;
+
+        self.checkpoint_check(fork)
+    }
+
+    // refuse any reorg which detaches blocks at or below the latest checkpoint,
+    // or attaches a block conflicting with a checkpoint
+    fn checkpoint_check(&self, fork: &ForkChanges) -> Result<(), FailureError> {
+        let consensus = self.shared.consensus();
+        if let Some(checkpoint) = consensus.last_checkpoint() {
+            if fork
+                .detached_blocks()
+                .iter()
+                .any(|b| b.header().number() <= checkpoint.number)
+            {
+                Err(SharedError::CheckpointConflict(checkpoint.number))?;
+            }
+        }
+        for b in fork.attached_blocks() {
+            let header = b.header();
+            if consensus.is_checkpoint_conflict(header.number(), &header.hash()) {
+                Err(SharedError::CheckpointConflict(header.number()))?;
+            }
+        }
+        Ok(())
     }
 
     // we found new best_block total_difficulty > old_chain.total_difficulty
@@ -471,6 +615,19 @@ impl<CS: ChainStore + 'static> ChainService<CS> {
 
                     let cellbase_maturity = { self.shared.consensus().cellbase_maturity() };
 
+                    let cache_hits = {
+                        let txs_verify_cache = chain_state.mut_txs_verify_cache();
+                        resolved
+                            .iter()
+                            .skip(1)
+                            .filter(|rtx| txs_verify_cache.contains_key(&rtx.transaction.hash()))
+                            .count()
+                    };
+                    chain_state.record_txs_verify_cache(
+                        cache_hits as u64,
+                        (resolved.len() - 1 - cache_hits) as u64,
+                    );
+
                     match txs_verifier.verify(
                         chain_state.mut_txs_verify_cache(),
                         &resolved,
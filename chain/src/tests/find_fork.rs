@@ -1,10 +1,11 @@
 use crate::chain::{ChainBuilder, ForkChanges};
 use crate::tests::util::gen_block;
-use ckb_chain_spec::consensus::Consensus;
+use ckb_chain_spec::consensus::{Checkpoint, Consensus};
 use ckb_core::block::Block;
 use ckb_core::extras::BlockExt;
 use ckb_db::memorydb::MemoryKeyValueDB;
 use ckb_notify::NotifyService;
+use ckb_shared::error::SharedError;
 use ckb_shared::shared::SharedBuilder;
 use ckb_traits::ChainProvider;
 use faketime::unix_time_as_millis;
@@ -71,7 +72,9 @@ fn test_find_fork_case1() {
 
     let mut fork = ForkChanges::default();
 
-    chain_service.find_fork(&mut fork, tip_number, &new_block, ext);
+    chain_service
+        .find_fork(&mut fork, tip_number, &new_block, ext)
+        .unwrap();
 
     let detached_blocks: HashSet<Block> = HashSet::from_iter(fork1.into_iter());
     let attached_blocks: HashSet<Block> = HashSet::from_iter(fork2.into_iter());
@@ -149,7 +152,9 @@ fn test_find_fork_case2() {
 
     let mut fork = ForkChanges::default();
 
-    chain_service.find_fork(&mut fork, tip_number, &new_block, ext);
+    chain_service
+        .find_fork(&mut fork, tip_number, &new_block, ext)
+        .unwrap();
 
     let detached_blocks: HashSet<Block> = HashSet::from_iter(fork1[1..].iter().cloned());
     let attached_blocks: HashSet<Block> = HashSet::from_iter(fork2.into_iter());
@@ -221,7 +226,9 @@ fn test_find_fork_case3() {
     };
     let mut fork = ForkChanges::default();
 
-    chain_service.find_fork(&mut fork, tip_number, &new_block, ext);
+    chain_service
+        .find_fork(&mut fork, tip_number, &new_block, ext)
+        .unwrap();
 
     let detached_blocks: HashSet<Block> = HashSet::from_iter(fork1.into_iter());
     let attached_blocks: HashSet<Block> = HashSet::from_iter(fork2.into_iter());
@@ -294,7 +301,9 @@ fn test_find_fork_case4() {
 
     let mut fork = ForkChanges::default();
 
-    chain_service.find_fork(&mut fork, tip_number, &new_block, ext);
+    chain_service
+        .find_fork(&mut fork, tip_number, &new_block, ext)
+        .unwrap();
 
     let detached_blocks: HashSet<Block> = HashSet::from_iter(fork1.into_iter());
     let attached_blocks: HashSet<Block> = HashSet::from_iter(fork2.into_iter());
@@ -307,3 +316,65 @@ fn test_find_fork_case4() {
         HashSet::from_iter(fork.attached_blocks.iter().cloned())
     );
 }
+
+// 0--1--2--3--4
+// \     |
+//  \    checkpoint
+//   1--2--3--4--5
+#[test]
+fn test_find_fork_below_checkpoint() {
+    let consensus = Consensus::default();
+    let genesis = consensus.genesis_block().header().clone();
+
+    let mut fork1: Vec<Block> = Vec::new();
+    let mut fork2: Vec<Block> = Vec::new();
+
+    let mut parent = genesis.clone();
+    for _ in 0..4 {
+        let new_block = gen_block(&parent, U256::from(100u64), vec![], vec![], vec![]);
+        fork1.push(new_block.clone());
+        parent = new_block.header().clone();
+    }
+
+    let mut parent = genesis.clone();
+    for _ in 0..5 {
+        let new_block = gen_block(&parent, U256::from(90u64), vec![], vec![], vec![]);
+        fork2.push(new_block.clone());
+        parent = new_block.header().clone();
+    }
+
+    let checkpoint = Checkpoint {
+        number: 2,
+        hash: fork1[1].header().hash(),
+    };
+    let builder = SharedBuilder::<MemoryKeyValueDB>::new();
+    let shared = builder
+        .consensus(consensus.set_checkpoints(vec![checkpoint]))
+        .build();
+    let notify = NotifyService::default().start::<&str>(None);
+    let mut chain_service = ChainBuilder::new(shared.clone(), notify)
+        .verification(false)
+        .build();
+
+    // fork1 total_difficulty 400
+    for blk in &fork1 {
+        chain_service.process_block(Arc::new(blk.clone())).unwrap();
+    }
+
+    // fork2 total_difficulty 360
+    for blk in &fork2[..4] {
+        chain_service.process_block(Arc::new(blk.clone())).unwrap();
+    }
+
+    // fork2 total_difficulty 450, but it forks below the checkpoint
+    let error = chain_service
+        .process_block(Arc::new(fork2[4].clone()))
+        .unwrap_err()
+        .downcast::<SharedError>()
+        .unwrap();
+    assert_eq!(error, SharedError::CheckpointConflict(2));
+    assert_eq!(
+        shared.chain_state().lock().tip_hash(),
+        fork1[3].header().hash()
+    );
+}
@@ -1,4 +1,5 @@
 mod basic;
 mod delay_verify;
 mod find_fork;
+mod prune;
 mod util;
@@ -0,0 +1,118 @@
+use crate::chain::ChainBuilder;
+use crate::tests::util::{create_transaction, gen_block};
+use ckb_chain_spec::consensus::Consensus;
+use ckb_core::block::BlockBuilder;
+use ckb_core::header::HeaderBuilder;
+use ckb_core::script::Script;
+use ckb_core::transaction::{CellInput, CellOutput, OutPoint, TransactionBuilder};
+use ckb_core::{capacity_bytes, Capacity};
+use ckb_db::memorydb::MemoryKeyValueDB;
+use ckb_notify::NotifyService;
+use ckb_shared::error::SharedError;
+use ckb_shared::shared::SharedBuilder;
+use ckb_shared::store::ChainStore;
+use ckb_traits::ChainProvider;
+use numext_fixed_uint::U256;
+use std::sync::Arc;
+
+#[test]
+fn test_prune_block_bodies() {
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new(OutPoint::null(), 0, Default::default()))
+        .output(CellOutput::new(
+            capacity_bytes!(100_000_000),
+            vec![],
+            Script::default(),
+            None,
+        ))
+        .build();
+    let mut root_hash = tx.hash().clone();
+    let genesis_block = BlockBuilder::default()
+        .transaction(tx)
+        .with_header_builder(HeaderBuilder::default().difficulty(U256::from(1000u64)));
+    let consensus = Consensus::default()
+        .set_cellbase_maturity(0)
+        .set_genesis_block(genesis_block);
+
+    let shared = SharedBuilder::<MemoryKeyValueDB>::new()
+        .consensus(consensus)
+        .prune_depth(Some(5))
+        .build();
+    let notify = NotifyService::default().start::<&str>(None);
+    let chain_controller = ChainBuilder::new(shared.clone(), notify)
+        .verification(false)
+        .build()
+        .start::<&str>(None);
+
+    // every block spends the output of the previous block transaction
+    let mut blocks = vec![];
+    let mut parent = shared.block_header(&shared.block_hash(0).unwrap()).unwrap();
+    for i in 1..=20 {
+        let difficulty = parent.difficulty().clone();
+        let tx = create_transaction(root_hash, i as u8);
+        root_hash = tx.hash().clone();
+        let block = gen_block(
+            &parent,
+            difficulty + U256::from(1u64),
+            vec![tx],
+            vec![],
+            vec![],
+        );
+        chain_controller
+            .process_block(Arc::new(block.clone()))
+            .expect("process block ok");
+        parent = block.header().clone();
+        blocks.push(block);
+    }
+
+    let store = shared.store();
+    assert_eq!(store.get_pruned_number(), Some(15));
+    for block in &blocks[..15] {
+        let hash = block.header().hash();
+        assert!(store.is_pruned(&hash));
+        assert!(shared.block(&hash).is_none());
+        assert!(shared.block_header(&hash).is_some());
+        assert!(shared.block_ext(&hash).is_some());
+    }
+    for block in &blocks[15..] {
+        assert!(!store.is_pruned(&block.header().hash()));
+        assert!(shared.block(&block.header().hash()).is_some());
+    }
+    assert!(shared.block(&shared.genesis_hash()).is_some());
+
+    // the cellbase cells are live
+    let cellbase = &blocks[0].transactions()[0];
+    assert_eq!(
+        shared.get_transaction(&cellbase.hash()).as_ref(),
+        Some(cellbase)
+    );
+    // spent by a pruned block
+    let spent = &blocks[0].transactions()[1];
+    assert!(shared.get_transaction(&spent.hash()).is_none());
+    assert!(store.get_transaction_address(&spent.hash()).is_some());
+    // spent by a block which can still be reverted
+    let last_pruned = &blocks[14].transactions()[1];
+    assert_eq!(
+        shared.get_transaction(&last_pruned.hash()).as_ref(),
+        Some(last_pruned)
+    );
+    // a fork can't detach the pruned blocks
+    let fork = gen_block(
+        blocks[9].header(),
+        U256::from(1_000_000u64),
+        vec![],
+        vec![],
+        vec![],
+    );
+    let err = chain_controller
+        .process_block(Arc::new(fork))
+        .expect_err("fork detaching pruned blocks");
+    assert_eq!(
+        err.downcast_ref::<SharedError>(),
+        Some(&SharedError::PrunedFork(11))
+    );
+    assert_eq!(
+        shared.chain_state().lock().tip_hash(),
+        blocks[19].header().hash()
+    );
+}
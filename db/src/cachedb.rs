@@ -1,8 +1,9 @@
-use crate::{Col, KeyValueDB, Result};
+use crate::{Col, ColumnStatistics, DBIterator, DBSnapshot, IteratorMode, KeyValueDB, Result};
 use ckb_util::RwLock;
 use fnv::FnvHashMap;
 use lru_cache::LruCache;
 use std::ops::Range;
+use std::path::Path;
 
 type CacheTable = FnvHashMap<Col, LruCache<Vec<u8>, Vec<u8>>>;
 pub type CacheCols = (u32, usize);
@@ -60,4 +61,20 @@ where
     fn batch(&self) -> Result<Self::Batch> {
         self.db.batch()
     }
+
+    fn iter<'a>(&'a self, col: Col, mode: IteratorMode) -> Result<DBIterator<'a>> {
+        self.db.iter(col, mode)
+    }
+
+    fn snapshot<'a>(&'a self) -> Result<Box<dyn DBSnapshot + 'a>> {
+        self.db.snapshot()
+    }
+
+    fn checkpoint(&self, path: &Path) -> Result<()> {
+        self.db.checkpoint(path)
+    }
+
+    fn column_statistics(&self, col: Col) -> Result<ColumnStatistics> {
+        self.db.column_statistics(col)
+    }
 }
@@ -7,4 +7,43 @@ pub struct DBConfig {
     #[serde(default)]
     pub path: PathBuf,
     pub options: Option<HashMap<String, String>>,
+    /// Discard the bodies of the main chain blocks deeper than this below the tip,
+    /// blocks are kept forever when it is not set.
+    #[serde(default)]
+    pub prune_depth: Option<u64>,
+    /// Tuning of the columns by name, overriding the defaults of each column.
+    #[serde(default)]
+    pub columns: HashMap<String, ColumnConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize, Default, PartialEq, Eq)]
+pub struct ColumnConfig {
+    /// Size in bytes of the LRU cache of uncompressed table blocks
+    pub block_cache_size: Option<usize>,
+    /// Bits per key of the bloom filter, there is no filter when it is not set
+    pub bloom_filter_bits: Option<i32>,
+    pub compression: Option<Compression>,
+    /// Size in bytes of a memtable before it is flushed
+    pub write_buffer_size: Option<usize>,
+}
+
+impl ColumnConfig {
+    /// The settings of `self`, falling back to `defaults` for those not set
+    pub fn or(&self, defaults: &ColumnConfig) -> ColumnConfig {
+        ColumnConfig {
+            block_cache_size: self.block_cache_size.or(defaults.block_cache_size),
+            bloom_filter_bits: self.bloom_filter_bits.or(defaults.bloom_filter_bits),
+            compression: self.compression.or(defaults.compression),
+            write_buffer_size: self.write_buffer_size.or(defaults.write_buffer_size),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    None,
+    Snappy,
+    Lz4,
+    Zstd,
 }
@@ -5,15 +5,18 @@
 
 use failure::Fail;
 use std::ops::Range;
+use std::path::Path;
 use std::result;
 
 pub mod cachedb;
 pub mod config;
 pub mod memorydb;
 pub mod rocksdb;
+#[cfg(test)]
+mod tests;
 
 pub use crate::cachedb::CacheDB;
-pub use crate::config::DBConfig;
+pub use crate::config::{ColumnConfig, Compression, DBConfig};
 pub use crate::memorydb::MemoryKeyValueDB;
 pub use crate::rocksdb::RocksDB;
 
@@ -26,11 +29,112 @@ pub enum Error {
     DBError(String),
 }
 
+pub type DBIterator<'a> = Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a>;
+
+/// Statistics of a column, `None` for those not reported by the database
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ColumnStatistics {
+    pub estimate_num_keys: Option<u64>,
+    pub total_sst_files_size: Option<u64>,
+    pub mem_tables_size: Option<u64>,
+    pub table_readers_mem: Option<u64>,
+    pub block_cache_usage: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Forward,
+    Reverse,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IteratorMode<'a> {
+    Start,
+    End,
+    /// Starts from the key, or the nearest one in the direction if the key does not exist
+    From(&'a [u8], Direction),
+}
+
 pub trait KeyValueDB: Sync + Send {
     type Batch: DbBatch;
     fn read(&self, col: Col, key: &[u8]) -> Result<Option<Vec<u8>>>;
     fn partial_read(&self, col: Col, key: &[u8], range: &Range<usize>) -> Result<Option<Vec<u8>>>;
     fn batch(&self) -> Result<Self::Batch>;
+
+    fn iter<'a>(&'a self, col: Col, mode: IteratorMode) -> Result<DBIterator<'a>>;
+
+    /// Iterates the keys starting with `prefix`
+    fn prefix_iter<'a>(
+        &'a self,
+        col: Col,
+        prefix: &[u8],
+        direction: Direction,
+    ) -> Result<DBIterator<'a>> {
+        prefix_iter(prefix, direction, |mode| self.iter(col, mode))
+    }
+
+    /// A consistent view of the database at the time it is taken
+    fn snapshot<'a>(&'a self) -> Result<Box<dyn DBSnapshot + 'a>>;
+
+    /// Write a consistent copy of the database to `path`, which must not exist
+    fn checkpoint(&self, path: &Path) -> Result<()>;
+
+    fn column_statistics(&self, col: Col) -> Result<ColumnStatistics>;
+}
+
+pub trait DBSnapshot {
+    fn read(&self, col: Col, key: &[u8]) -> Result<Option<Vec<u8>>>;
+
+    fn iter<'a>(&'a self, col: Col, mode: IteratorMode) -> Result<DBIterator<'a>>;
+
+    fn prefix_iter<'a>(
+        &'a self,
+        col: Col,
+        prefix: &[u8],
+        direction: Direction,
+    ) -> Result<DBIterator<'a>> {
+        prefix_iter(prefix, direction, |mode| self.iter(col, mode))
+    }
+}
+
+fn prefix_iter<'a, F>(prefix: &[u8], direction: Direction, iter: F) -> Result<DBIterator<'a>>
+where
+    F: FnOnce(IteratorMode) -> Result<DBIterator<'a>>,
+{
+    let matched = prefix.to_vec();
+    match direction {
+        Direction::Forward => {
+            let iter = iter(IteratorMode::From(prefix, Direction::Forward))?;
+            Ok(Box::new(
+                iter.take_while(move |(key, _)| key.starts_with(&matched)),
+            ))
+        }
+        Direction::Reverse => {
+            // seek back from the first key after all the keys with the prefix
+            let upper_bound = prefix_upper_bound(prefix);
+            let iter = match upper_bound {
+                Some(ref upper_bound) => iter(IteratorMode::From(upper_bound, Direction::Reverse))?,
+                None => iter(IteratorMode::End)?,
+            };
+            Ok(Box::new(
+                iter.skip_while(move |(key, _)| Some(key) == upper_bound.as_ref())
+                    .take_while(move |(key, _)| key.starts_with(&matched)),
+            ))
+        }
+    }
+}
+
+/// The smallest key greater than all the keys starting with `prefix`,
+/// `None` if there is no such key
+pub(crate) fn prefix_upper_bound(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut upper_bound = prefix.to_vec();
+    while let Some(last) = upper_bound.pop() {
+        if last < u8::max_value() {
+            upper_bound.push(last + 1);
+            return Some(upper_bound);
+        }
+    }
+    None
 }
 
 pub trait DbBatch {
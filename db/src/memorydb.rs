@@ -1,13 +1,19 @@
 // for unit test
-use crate::{Col, DbBatch, Error, KeyValueDB, Result};
+use crate::{
+    prefix_upper_bound, Col, ColumnStatistics, DBIterator, DBSnapshot, DbBatch, Direction, Error,
+    IteratorMode, KeyValueDB, Result,
+};
 use ckb_util::RwLock;
 use fnv::FnvHashMap;
+use std::collections::BTreeMap;
+use std::ops::Bound::{Excluded, Included, Unbounded};
 use std::ops::Range;
+use std::path::Path;
 use std::sync::Arc;
 
 pub type MemoryKey = Vec<u8>;
 pub type MemoryValue = Vec<u8>;
-pub type MemoryTable = FnvHashMap<Col, FnvHashMap<MemoryKey, MemoryValue>>;
+pub type MemoryTable = FnvHashMap<Col, BTreeMap<MemoryKey, MemoryValue>>;
 
 #[derive(Default, Debug)]
 pub struct MemoryKeyValueDB {
@@ -18,7 +24,7 @@ impl MemoryKeyValueDB {
     pub fn open(cols: usize) -> MemoryKeyValueDB {
         let mut table = FnvHashMap::with_capacity_and_hasher(cols, Default::default());
         for idx in 0..cols {
-            table.insert(idx as u32, FnvHashMap::default());
+            table.insert(idx as u32, BTreeMap::new());
         }
         MemoryKeyValueDB {
             db: Arc::new(RwLock::new(table)),
@@ -56,6 +62,122 @@ impl KeyValueDB for MemoryKeyValueDB {
             db: Arc::clone(&self.db),
         })
     }
+
+    fn iter<'a>(&'a self, col: Col, mode: IteratorMode) -> Result<DBIterator<'a>> {
+        let db = self.db.read();
+        iter_column(&db, col, mode)
+    }
+
+    fn prefix_iter<'a>(
+        &'a self,
+        col: Col,
+        prefix: &[u8],
+        direction: Direction,
+    ) -> Result<DBIterator<'a>> {
+        let db = self.db.read();
+        prefix_iter_column(&db, col, prefix, direction)
+    }
+
+    fn snapshot<'a>(&'a self) -> Result<Box<dyn DBSnapshot + 'a>> {
+        Ok(Box::new(MemorySnapshot {
+            table: self.db.read().clone(),
+        }))
+    }
+
+    fn checkpoint(&self, _path: &Path) -> Result<()> {
+        Err(Error::DBError(
+            "checkpoint is not supported by the memory database".to_owned(),
+        ))
+    }
+
+    fn column_statistics(&self, col: Col) -> Result<ColumnStatistics> {
+        let db = self.db.read();
+        match db.get(&col) {
+            None => Err(Error::DBError(format!("column {} not found ", col))),
+            Some(map) => Ok(ColumnStatistics {
+                estimate_num_keys: Some(map.len() as u64),
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+/// Copies the iterated entries, the lock can not be held by the iterator
+fn iter_column<'a>(table: &MemoryTable, col: Col, mode: IteratorMode) -> Result<DBIterator<'a>> {
+    let map = column(table, col)?;
+    Ok(match mode {
+        IteratorMode::Start => copy_entries(map.iter()),
+        IteratorMode::End => copy_entries(map.iter().rev()),
+        IteratorMode::From(key, Direction::Forward) => {
+            copy_entries(map.range::<[u8], _>((Included(key), Unbounded)))
+        }
+        IteratorMode::From(key, Direction::Reverse) => {
+            copy_entries(map.range::<[u8], _>((Unbounded, Included(key))).rev())
+        }
+    })
+}
+
+/// Copies only the entries with the prefix
+fn prefix_iter_column<'a>(
+    table: &MemoryTable,
+    col: Col,
+    prefix: &[u8],
+    direction: Direction,
+) -> Result<DBIterator<'a>> {
+    let map = column(table, col)?;
+    let upper_bound = prefix_upper_bound(prefix);
+    let range = map.range::<[u8], _>((
+        Included(prefix),
+        upper_bound
+            .as_ref()
+            .map(|upper_bound| Excluded(upper_bound.as_slice()))
+            .unwrap_or(Unbounded),
+    ));
+    Ok(match direction {
+        Direction::Forward => copy_entries(range),
+        Direction::Reverse => copy_entries(range.rev()),
+    })
+}
+
+fn column(table: &MemoryTable, col: Col) -> Result<&BTreeMap<MemoryKey, MemoryValue>> {
+    table
+        .get(&col)
+        .ok_or_else(|| Error::DBError(format!("column {} not found ", col)))
+}
+
+fn copy_entries<'a, 'b, I>(entries: I) -> DBIterator<'a>
+where
+    I: Iterator<Item = (&'b MemoryKey, &'b MemoryValue)>,
+{
+    let entries: Vec<(MemoryKey, MemoryValue)> =
+        entries.map(|(k, v)| (k.clone(), v.clone())).collect();
+    Box::new(entries.into_iter())
+}
+
+pub struct MemorySnapshot {
+    table: MemoryTable,
+}
+
+impl DBSnapshot for MemorySnapshot {
+    fn read(&self, col: Col, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        match self.table.get(&col) {
+            None => Err(Error::DBError(format!("column {} not found ", col))),
+            Some(map) => Ok(map.get(key).cloned()),
+        }
+    }
+
+    fn iter<'a>(&'a self, col: Col, mode: IteratorMode) -> Result<DBIterator<'a>> {
+        iter_column(&self.table, col, mode)
+    }
+
+    fn prefix_iter<'a>(
+        &'a self,
+        col: Col,
+        prefix: &[u8],
+        direction: Direction,
+    ) -> Result<DBIterator<'a>> {
+        prefix_iter_column(&self.table, col, prefix, direction)
+    }
 }
 
 pub struct MemoryDbBatch {
@@ -153,4 +275,16 @@ mod tests {
             db.partial_read(0, &[0, 0], &(1..4)).unwrap()
         );
     }
+
+    #[test]
+    fn iterate() {
+        let db = MemoryKeyValueDB::open(1);
+        crate::tests::iterate(&db);
+    }
+
+    #[test]
+    fn read_from_snapshot() {
+        let db = MemoryKeyValueDB::open(1);
+        crate::tests::read_from_snapshot(&db);
+    }
 }
@@ -1,7 +1,16 @@
-use crate::{Col, DBConfig, DbBatch, Error, KeyValueDB, Result};
+use crate::{
+    Col, ColumnConfig, ColumnStatistics, Compression, DBConfig, DBIterator, DBSnapshot, DbBatch,
+    Direction, Error, IteratorMode, KeyValueDB, Result,
+};
 use log::warn;
-use rocksdb::{ColumnFamily, Error as RdbError, Options, WriteBatch, DB};
+use rocksdb::checkpoint::Checkpoint;
+use rocksdb::{
+    BlockBasedOptions, ColumnFamily, ColumnFamilyDescriptor, DBCompressionType,
+    Direction as RdbDirection, Error as RdbError, IteratorMode as RdbIteratorMode, Options,
+    Snapshot, WriteBatch, DB,
+};
 use std::ops::Range;
+use std::path::Path;
 use std::sync::Arc;
 
 pub struct RocksDB {
@@ -10,27 +19,43 @@ pub struct RocksDB {
 
 impl RocksDB {
     pub fn open(config: &DBConfig, columns: u32) -> Self {
+        let columns = vec![ColumnConfig::default(); columns as usize];
+        Self::open_with_columns(config, &columns)
+    }
+
+    /// Open the database with a column family per entry of `columns`, tuned by its settings
+    pub fn open_with_columns(config: &DBConfig, columns: &[ColumnConfig]) -> Self {
         let mut opts = Options::default();
         opts.create_if_missing(true);
         opts.create_missing_column_families(true);
 
-        let cfnames: Vec<_> = (0..columns).map(|c| c.to_string()).collect();
-        let cf_options: Vec<&str> = cfnames.iter().map(|n| n as &str).collect();
-        let db = DB::open_cf(&opts, &config.path, &cf_options).unwrap_or_else(|err| {
-            if err.as_ref().starts_with("Corruption:") {
-                warn!("Try repairing the rocksdb since {} ...", err);
-                let mut repair_opts = Options::default();
-                repair_opts.create_if_missing(false);
-                repair_opts.create_missing_column_families(false);
-                DB::repair(repair_opts, &config.path)
-                    .unwrap_or_else(|err| panic!("Failed to repair the rocksdb: {}", err));
-                warn!("Try opening the repaired rocksdb ...");
-                DB::open_cf(&opts, &config.path, &cf_options)
-                    .unwrap_or_else(|err| panic!("Failed to open the repaired rocksdb: {}", err))
-            } else {
-                panic!("Failed to open rocksdb: {}", err);
-            }
-        });
+        // the descriptors are consumed when the database is opened
+        let descriptors = || {
+            columns
+                .iter()
+                .enumerate()
+                .map(|(col, column)| {
+                    ColumnFamilyDescriptor::new(col.to_string(), cf_options(column))
+                })
+                .collect::<Vec<_>>()
+        };
+        let db =
+            DB::open_cf_descriptors(&opts, &config.path, descriptors()).unwrap_or_else(|err| {
+                if err.as_ref().starts_with("Corruption:") {
+                    warn!("Try repairing the rocksdb since {} ...", err);
+                    let mut repair_opts = Options::default();
+                    repair_opts.create_if_missing(false);
+                    repair_opts.create_missing_column_families(false);
+                    DB::repair(repair_opts, &config.path)
+                        .unwrap_or_else(|err| panic!("Failed to repair the rocksdb: {}", err));
+                    warn!("Try opening the repaired rocksdb ...");
+                    DB::open_cf_descriptors(&opts, &config.path, descriptors()).unwrap_or_else(
+                        |err| panic!("Failed to open the repaired rocksdb: {}", err),
+                    )
+                } else {
+                    panic!("Failed to open rocksdb: {}", err);
+                }
+            });
 
         if let Some(db_opt) = config.options.as_ref() {
             let rocksdb_options: Vec<(&str, &str)> = db_opt
@@ -47,6 +72,30 @@ impl RocksDB {
     }
 }
 
+fn cf_options(config: &ColumnConfig) -> Options {
+    let mut opts = Options::default();
+    let mut block_opts = BlockBasedOptions::default();
+    if let Some(size) = config.block_cache_size {
+        block_opts.set_lru_cache(size);
+    }
+    if let Some(bits) = config.bloom_filter_bits {
+        block_opts.set_bloom_filter(bits, false);
+    }
+    opts.set_block_based_table_factory(&block_opts);
+    if let Some(compression) = config.compression {
+        opts.set_compression_type(match compression {
+            Compression::None => DBCompressionType::None,
+            Compression::Snappy => DBCompressionType::Snappy,
+            Compression::Lz4 => DBCompressionType::Lz4,
+            Compression::Zstd => DBCompressionType::Zstd,
+        });
+    }
+    if let Some(size) = config.write_buffer_size {
+        opts.set_write_buffer_size(size);
+    }
+    opts
+}
+
 fn cf_handle(db: &DB, col: Col) -> Result<ColumnFamily> {
     db.cf_handle(&col.to_string())
         .ok_or_else(|| Error::DBError(format!("column {} not found", col)))
@@ -77,6 +126,81 @@ impl KeyValueDB for RocksDB {
             wb: WriteBatch::default(),
         })
     }
+
+    fn iter<'a>(&'a self, col: Col, mode: IteratorMode) -> Result<DBIterator<'a>> {
+        let cf = cf_handle(&self.inner, col)?;
+        let iter = self.inner.iterator_cf(cf, rdb_iterator_mode(mode))?;
+        Ok(Box::new(
+            iter.map(|(key, value)| (key.into_vec(), value.into_vec())),
+        ))
+    }
+
+    fn snapshot<'a>(&'a self) -> Result<Box<dyn DBSnapshot + 'a>> {
+        Ok(Box::new(RocksDBSnapshot {
+            db: &self.inner,
+            inner: self.inner.snapshot(),
+        }))
+    }
+
+    // the table files are hard linked when `path` is on the same filesystem
+    fn checkpoint(&self, path: &Path) -> Result<()> {
+        Checkpoint::new(&self.inner)?
+            .create_checkpoint(path)
+            .map_err(Into::into)
+    }
+
+    fn column_statistics(&self, col: Col) -> Result<ColumnStatistics> {
+        let cf = cf_handle(&self.inner, col)?;
+        let property = |name: &str| -> Result<Option<u64>> {
+            Ok(self
+                .inner
+                .property_value_cf(cf, name)?
+                .and_then(|value| value.parse().ok()))
+        };
+        Ok(ColumnStatistics {
+            estimate_num_keys: property("rocksdb.estimate-num-keys")?,
+            total_sst_files_size: property("rocksdb.total-sst-files-size")?,
+            mem_tables_size: property("rocksdb.cur-size-all-mem-tables")?,
+            table_readers_mem: property("rocksdb.estimate-table-readers-mem")?,
+            block_cache_usage: property("rocksdb.block-cache-usage")?,
+        })
+    }
+}
+
+fn rdb_iterator_mode(mode: IteratorMode) -> RdbIteratorMode {
+    match mode {
+        IteratorMode::Start => RdbIteratorMode::Start,
+        IteratorMode::End => RdbIteratorMode::End,
+        IteratorMode::From(key, Direction::Forward) => {
+            RdbIteratorMode::From(key, RdbDirection::Forward)
+        }
+        IteratorMode::From(key, Direction::Reverse) => {
+            RdbIteratorMode::From(key, RdbDirection::Reverse)
+        }
+    }
+}
+
+pub struct RocksDBSnapshot<'a> {
+    db: &'a DB,
+    inner: Snapshot<'a>,
+}
+
+impl<'a> DBSnapshot for RocksDBSnapshot<'a> {
+    fn read(&self, col: Col, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let cf = cf_handle(self.db, col)?;
+        self.inner
+            .get_cf(cf, key)
+            .map(|v| v.map(|vi| vi.to_vec()))
+            .map_err(Into::into)
+    }
+
+    fn iter<'b>(&'b self, col: Col, mode: IteratorMode) -> Result<DBIterator<'b>> {
+        let cf = cf_handle(self.db, col)?;
+        let iter = self.inner.iterator_cf(cf, rdb_iterator_mode(mode))?;
+        Ok(Box::new(
+            iter.map(|(key, value)| (key.into_vec(), value.into_vec())),
+        ))
+    }
 }
 
 pub struct RocksdbBatch {
@@ -138,6 +262,7 @@ mod tests {
                 opts.insert("disable_auto_compactions".to_owned(), "true".to_owned());
                 opts
             }),
+            ..Default::default()
         };
         RocksDB::open(&config, 2); // no panic
     }
@@ -156,6 +281,7 @@ mod tests {
                 opts.insert("letsrock".to_owned(), "true".to_owned());
                 opts
             }),
+            ..Default::default()
         };
         RocksDB::open(&config, 2); // panic
     }
@@ -200,4 +326,82 @@ mod tests {
             db.partial_read(0, &[0, 0], &(1..4)).unwrap()
         );
     }
+
+    #[test]
+    fn iterate() {
+        let db = setup_db("iterate", 1);
+        crate::tests::iterate(&db);
+    }
+
+    #[test]
+    fn read_from_snapshot() {
+        let db = setup_db("read_from_snapshot", 1);
+        crate::tests::read_from_snapshot(&db);
+    }
+
+    #[test]
+    fn write_checkpoint() {
+        let db = setup_db("write_checkpoint", 2);
+        let mut batch = db.batch().unwrap();
+        batch.insert(0, &[0], &[0]).unwrap();
+        batch.insert(1, &[1], &[1]).unwrap();
+        batch.commit().unwrap();
+
+        let tmp_dir = tempfile::Builder::new()
+            .prefix("write_checkpoint_target")
+            .tempdir()
+            .unwrap();
+        let path = tmp_dir.path().join("checkpoint");
+        db.checkpoint(&path).unwrap();
+
+        let mut batch = db.batch().unwrap();
+        batch.insert(0, &[0], &[1]).unwrap();
+        batch.commit().unwrap();
+
+        let config = DBConfig {
+            path,
+            ..Default::default()
+        };
+        let checkpoint = RocksDB::open(&config, 2);
+        assert_eq!(Some(vec![0]), checkpoint.read(0, &[0]).unwrap());
+        assert_eq!(Some(vec![1]), checkpoint.read(1, &[1]).unwrap());
+        // the target must not exist
+        assert!(db.checkpoint(tmp_dir.path()).is_err());
+    }
+
+    #[test]
+    fn open_with_tuned_columns() {
+        let tmp_dir = tempfile::Builder::new()
+            .prefix("open_with_tuned_columns")
+            .tempdir()
+            .unwrap();
+        let config = DBConfig {
+            path: tmp_dir.as_ref().to_path_buf(),
+            ..Default::default()
+        };
+        let columns = [
+            ColumnConfig {
+                block_cache_size: Some(1 << 20),
+                bloom_filter_bits: Some(10),
+                ..Default::default()
+            },
+            ColumnConfig {
+                compression: Some(Compression::Zstd),
+                write_buffer_size: Some(1 << 20),
+                ..Default::default()
+            },
+        ];
+        let db = RocksDB::open_with_columns(&config, &columns);
+
+        let mut batch = db.batch().unwrap();
+        batch.insert(0, &[0], &[0]).unwrap();
+        batch.insert(1, &[1], &[1]).unwrap();
+        batch.insert(1, &[2], &[2]).unwrap();
+        batch.commit().unwrap();
+        assert_eq!(Some(vec![1]), db.read(1, &[1]).unwrap());
+
+        assert_eq!(db.column_statistics(0).unwrap().estimate_num_keys, Some(1));
+        assert_eq!(db.column_statistics(1).unwrap().estimate_num_keys, Some(2));
+        assert!(db.column_statistics(2).is_err());
+    }
 }
@@ -0,0 +1,106 @@
+//! Checks shared by the `KeyValueDB` implementations, which must behave the same
+use crate::{DBIterator, Direction, IteratorMode, KeyValueDB};
+
+fn keys(iter: DBIterator) -> Vec<Vec<u8>> {
+    iter.map(|(key, _)| key).collect()
+}
+
+/// Requires a database with one empty column
+pub(crate) fn iterate<DB: KeyValueDB>(db: &DB) {
+    let mut batch = db.batch().unwrap();
+    for key in &[
+        vec![1],
+        vec![1, 1],
+        vec![1, 255],
+        vec![2],
+        vec![2, 0],
+        vec![255],
+    ] {
+        batch.insert(0, key, key).unwrap();
+    }
+    batch.commit().unwrap();
+
+    assert_eq!(
+        keys(db.iter(0, IteratorMode::Start).unwrap()),
+        vec![
+            vec![1],
+            vec![1, 1],
+            vec![1, 255],
+            vec![2],
+            vec![2, 0],
+            vec![255]
+        ]
+    );
+    assert_eq!(
+        keys(db.iter(0, IteratorMode::End).unwrap()),
+        vec![
+            vec![255],
+            vec![2, 0],
+            vec![2],
+            vec![1, 255],
+            vec![1, 1],
+            vec![1]
+        ]
+    );
+    assert_eq!(
+        keys(
+            db.iter(0, IteratorMode::From(&[1, 100], Direction::Forward))
+                .unwrap()
+        ),
+        vec![vec![1, 255], vec![2], vec![2, 0], vec![255]]
+    );
+    assert_eq!(
+        keys(
+            db.iter(0, IteratorMode::From(&[1, 100], Direction::Reverse))
+                .unwrap()
+        ),
+        vec![vec![1, 1], vec![1]]
+    );
+
+    assert_eq!(
+        keys(db.prefix_iter(0, &[1], Direction::Forward).unwrap()),
+        vec![vec![1], vec![1, 1], vec![1, 255]]
+    );
+    assert_eq!(
+        keys(db.prefix_iter(0, &[1], Direction::Reverse).unwrap()),
+        vec![vec![1, 255], vec![1, 1], vec![1]]
+    );
+    assert_eq!(
+        keys(db.prefix_iter(0, &[1, 255], Direction::Reverse).unwrap()),
+        vec![vec![1, 255]]
+    );
+    assert_eq!(
+        keys(db.prefix_iter(0, &[2], Direction::Forward).unwrap()),
+        vec![vec![2], vec![2, 0]]
+    );
+    assert_eq!(
+        keys(db.prefix_iter(0, &[255], Direction::Reverse).unwrap()),
+        vec![vec![255]]
+    );
+    assert!(keys(db.prefix_iter(0, &[3], Direction::Forward).unwrap()).is_empty());
+}
+
+/// Requires a database with one empty column
+pub(crate) fn read_from_snapshot<DB: KeyValueDB>(db: &DB) {
+    let mut batch = db.batch().unwrap();
+    batch.insert(0, &[0], &[0]).unwrap();
+    batch.commit().unwrap();
+
+    let snapshot = db.snapshot().unwrap();
+    let mut batch = db.batch().unwrap();
+    batch.insert(0, &[0], &[1]).unwrap();
+    batch.insert(0, &[1], &[1]).unwrap();
+    batch.commit().unwrap();
+
+    assert_eq!(Some(vec![1]), db.read(0, &[0]).unwrap());
+    assert_eq!(Some(vec![0]), snapshot.read(0, &[0]).unwrap());
+    assert_eq!(None, snapshot.read(0, &[1]).unwrap());
+    assert_eq!(
+        keys(snapshot.iter(0, IteratorMode::Start).unwrap()),
+        vec![vec![0]]
+    );
+    assert_eq!(
+        keys(snapshot.prefix_iter(0, &[0], Direction::Forward).unwrap()),
+        vec![vec![0]]
+    );
+}
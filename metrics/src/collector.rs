@@ -0,0 +1,244 @@
+use crate::encoder::Encoder;
+use ckb_chain::chain::ChainController;
+use ckb_network::NetworkController;
+use ckb_rpc::{RpcMetrics, LATENCY_BUCKETS};
+use ckb_shared::shared::Shared;
+use ckb_shared::store::ChainStore;
+use ckb_sync::Synchronizer;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The sources of the node metrics
+pub struct NodeMetrics<CS: ChainStore> {
+    pub shared: Shared<CS>,
+    pub chain: ChainController,
+    pub network: NetworkController,
+    pub synchronizer: Synchronizer<CS>,
+    pub rpc: Option<Arc<RpcMetrics>>,
+}
+
+fn seconds(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) * 1e-9
+}
+
+impl<CS: ChainStore> NodeMetrics<CS> {
+    pub fn collect(&self) -> String {
+        let mut encoder = Encoder::default();
+        self.collect_chain(&mut encoder);
+        self.collect_network(&mut encoder);
+        self.collect_sync(&mut encoder);
+        if let Some(ref rpc) = self.rpc {
+            collect_rpc(rpc, &mut encoder);
+        }
+        encoder.finish()
+    }
+
+    fn collect_chain(&self, encoder: &mut Encoder) {
+        // copy out and release the chain state lock before encoding
+        let (tip_number, total_difficulty, pool_sizes, (hits, misses)) = {
+            let chain_state = self.shared.chain_state().lock();
+            let tx_pool = chain_state.tx_pool();
+            (
+                chain_state.tip_number(),
+                chain_state.total_difficulty().clone(),
+                [
+                    ("pending", tx_pool.pending_size()),
+                    ("staging", tx_pool.staging_size()),
+                    ("orphan", tx_pool.orphan_size()),
+                ],
+                chain_state.txs_verify_cache_stats(),
+            )
+        };
+        encoder.metric(
+            "ckb_chain_tip_number",
+            "gauge",
+            "The number of the tip block",
+            tip_number,
+        );
+        encoder.metric(
+            "ckb_chain_total_difficulty",
+            "gauge",
+            "The total difficulty of the main chain",
+            total_difficulty,
+        );
+
+        encoder.header(
+            "ckb_tx_pool_transactions",
+            "gauge",
+            "The number of transactions in the pool",
+        );
+        for (state, size) in pool_sizes.iter() {
+            encoder.sample("ckb_tx_pool_transactions", &[("state", *state)], size);
+        }
+
+        encoder.metric(
+            "ckb_txs_verify_cache_hits_total",
+            "counter",
+            "The transaction verifications served by the cache",
+            hits,
+        );
+        encoder.metric(
+            "ckb_txs_verify_cache_misses_total",
+            "counter",
+            "The transaction verifications not found in the cache",
+            misses,
+        );
+
+        let (blocks, elapsed) = self.chain.metrics().processed_blocks();
+        encoder.header(
+            "ckb_chain_process_block_seconds",
+            "summary",
+            "The time of verifying and inserting a block",
+        );
+        encoder.sample("ckb_chain_process_block_seconds_sum", &[], seconds(elapsed));
+        encoder.sample("ckb_chain_process_block_seconds_count", &[], blocks);
+    }
+
+    fn collect_network(&self, encoder: &mut Encoder) {
+        let (inbound, outbound) = self.network.peers_count();
+        encoder.header(
+            "ckb_network_peers",
+            "gauge",
+            "The number of connected peers",
+        );
+        encoder.sample("ckb_network_peers", &[("direction", "inbound")], inbound);
+        encoder.sample("ckb_network_peers", &[("direction", "outbound")], outbound);
+
+        let traffic = self.network.traffic();
+        encoder.header(
+            "ckb_network_received_bytes_total",
+            "counter",
+            "The bytes of the received messages",
+        );
+        for (proto_id, traffic) in &traffic {
+            let protocol = proto_id.to_string();
+            encoder.sample(
+                "ckb_network_received_bytes_total",
+                &[("protocol", protocol.as_str())],
+                traffic.received_bytes,
+            );
+        }
+        encoder.header(
+            "ckb_network_sent_bytes_total",
+            "counter",
+            "The bytes of the sent messages",
+        );
+        for (proto_id, traffic) in &traffic {
+            let protocol = proto_id.to_string();
+            encoder.sample(
+                "ckb_network_sent_bytes_total",
+                &[("protocol", protocol.as_str())],
+                traffic.sent_bytes,
+            );
+        }
+    }
+
+    fn collect_sync(&self, encoder: &mut Encoder) {
+        let synchronizer = &self.synchronizer;
+        encoder.metric(
+            "ckb_sync_best_known_header_number",
+            "gauge",
+            "The number of the best known header",
+            synchronizer.best_known_header.read().number(),
+        );
+        encoder.metric(
+            "ckb_sync_started_peers",
+            "gauge",
+            "The number of peers the headers are synchronized from",
+            synchronizer.n_sync.load(Ordering::Acquire),
+        );
+        let inflight_blocks: usize = synchronizer
+            .peers
+            .blocks_inflight
+            .read()
+            .values()
+            .map(|inflight| inflight.len())
+            .sum();
+        encoder.metric(
+            "ckb_sync_inflight_blocks",
+            "gauge",
+            "The number of requested blocks not received yet",
+            inflight_blocks,
+        );
+        encoder.metric(
+            "ckb_sync_orphan_blocks",
+            "gauge",
+            "The number of received blocks whose parent is unknown",
+            synchronizer.orphan_block_pool.len(),
+        );
+    }
+}
+
+fn collect_rpc(rpc: &RpcMetrics, encoder: &mut Encoder) {
+    let methods = rpc.methods();
+    encoder.header(
+        "ckb_rpc_calls_total",
+        "counter",
+        "The number of calls of a RPC method",
+    );
+    for (method, metrics) in &methods {
+        encoder.sample(
+            "ckb_rpc_calls_total",
+            &[("method", method.as_str())],
+            metrics.calls,
+        );
+    }
+    encoder.header(
+        "ckb_rpc_timeouts_total",
+        "counter",
+        "The number of calls longer than the method timeout",
+    );
+    for (method, metrics) in &methods {
+        encoder.sample(
+            "ckb_rpc_timeouts_total",
+            &[("method", method.as_str())],
+            metrics.timeouts,
+        );
+    }
+
+    encoder.header(
+        "ckb_rpc_latency_seconds",
+        "histogram",
+        "The latency of the calls of a RPC method",
+    );
+    for (method, metrics) in &methods {
+        let mut cumulative = 0;
+        for (bound, count) in LATENCY_BUCKETS
+            .iter()
+            .map(|millis| (*millis as f64 / 1_000f64).to_string())
+            .chain(Some("+Inf".to_owned()))
+            .zip(metrics.latency_buckets.iter())
+        {
+            cumulative += count;
+            encoder.sample(
+                "ckb_rpc_latency_seconds_bucket",
+                &[("method", method.as_str()), ("le", bound.as_str())],
+                cumulative,
+            );
+        }
+        encoder.sample(
+            "ckb_rpc_latency_seconds_sum",
+            &[("method", method.as_str())],
+            seconds(metrics.latency_sum),
+        );
+        encoder.sample(
+            "ckb_rpc_latency_seconds_count",
+            &[("method", method.as_str())],
+            metrics.calls,
+        );
+    }
+
+    encoder.metric(
+        "ckb_rpc_rate_limited_total",
+        "counter",
+        "The number of requests rejected by the rate limit",
+        rpc.rate_limited(),
+    );
+    encoder.metric(
+        "ckb_rpc_batch_rejected_total",
+        "counter",
+        "The number of batches rejected by the max batch size",
+        rpc.batch_rejected(),
+    );
+}
@@ -0,0 +1,67 @@
+use std::fmt::{Display, Write};
+
+/// Writes metrics in the Prometheus text exposition format
+#[derive(Default)]
+pub(crate) struct Encoder {
+    out: String,
+}
+
+impl Encoder {
+    pub(crate) fn header(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.out, "# HELP {} {}", name, help);
+        let _ = writeln!(self.out, "# TYPE {} {}", name, kind);
+    }
+
+    pub(crate) fn sample<V: Display>(&mut self, name: &str, labels: &[(&str, &str)], value: V) {
+        self.out.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(key, value)| format!("{}=\"{}\"", key, escape(value)))
+                .collect();
+            let _ = write!(self.out, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.out, " {}", value);
+    }
+
+    /// A metric with a single sample without labels
+    pub(crate) fn metric<V: Display>(&mut self, name: &str, kind: &str, help: &str, value: V) {
+        self.header(name, kind, help);
+        self.sample(name, &[], value);
+    }
+
+    pub(crate) fn finish(self) -> String {
+        self.out
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_samples() {
+        let mut encoder = Encoder::default();
+        encoder.metric("ckb_tip_number", "gauge", "The tip block number", 10);
+        encoder.header("ckb_peers", "gauge", "The connected peers");
+        encoder.sample("ckb_peers", &[("direction", "inbound")], 2);
+        encoder.sample("ckb_peers", &[("direction", "out\"bound")], 3);
+        assert_eq!(
+            encoder.finish(),
+            "# HELP ckb_tip_number The tip block number\n\
+             # TYPE ckb_tip_number gauge\n\
+             ckb_tip_number 10\n\
+             # HELP ckb_peers The connected peers\n\
+             # TYPE ckb_peers gauge\n\
+             ckb_peers{direction=\"inbound\"} 2\n\
+             ckb_peers{direction=\"out\\\"bound\"} 3\n"
+        );
+    }
+}
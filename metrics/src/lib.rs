@@ -0,0 +1,67 @@
+//! Export the node internals in the Prometheus text format on `GET /metrics`.
+
+mod collector;
+mod encoder;
+
+pub use crate::collector::NodeMetrics;
+
+use ckb_shared::store::ChainStore;
+use log::{debug, info};
+use serde_derive::Deserialize;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+// a scraper which stops reading would otherwise block the serving thread forever
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct Config {
+    /// The metrics endpoint is disabled when not set
+    pub listen_address: Option<String>,
+}
+
+/// Binds `listen_address` and serves the metrics in a background thread, the requests are
+/// served one by one.
+pub fn start<CS: ChainStore + 'static>(
+    listen_address: &str,
+    metrics: NodeMetrics<CS>,
+) -> io::Result<()> {
+    let listener = TcpListener::bind(listen_address)?;
+    info!(target: "metrics", "metrics listening on {}", listen_address);
+    thread::Builder::new()
+        .name("MetricsServer".to_owned())
+        .spawn(move || {
+            for stream in listener.incoming() {
+                if let Err(err) = stream.and_then(|stream| serve(stream, &metrics)) {
+                    debug!(target: "metrics", "serve metrics error: {}", err);
+                }
+            }
+        })?;
+    Ok(())
+}
+
+fn serve<CS: ChainStore>(mut stream: TcpStream, metrics: &NodeMetrics<CS>) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let response = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => {
+            let body = metrics.collect();
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                CONTENT_TYPE,
+                body.len(),
+                body
+            )
+        }
+        _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_owned(),
+    };
+    stream.write_all(response.as_bytes())?;
+    stream.flush()
+}
@@ -15,10 +15,10 @@ pub use crate::{
     behaviour::Behaviour,
     config::NetworkConfig,
     errors::Error,
-    network::{NetworkController, NetworkService, NetworkState},
+    network::{NetworkController, NetworkService, NetworkState, ProtocolTraffic},
     peer::{Peer, PeerIdentifyInfo},
     peer_registry::PeerRegistry,
-    peer_store::Score,
+    peer_store::{BanTarget, BannedAddr, Score},
     protocols::{CKBProtocol, CKBProtocolContext, CKBProtocolHandler, PeerIndex},
 };
 pub use p2p::{
@@ -1,6 +1,6 @@
 use crate::errors::Error;
 use crate::peer_registry::{ConnectionStatus, PeerRegistry};
-use crate::peer_store::{sqlite::SqlitePeerStore, PeerStore, Status};
+use crate::peer_store::{sqlite::SqlitePeerStore, BanTarget, BannedAddr, PeerStore, Score, Status};
 use crate::protocols::feeler::Feeler;
 use crate::protocols::{
     discovery::{DiscoveryProtocol, DiscoveryService},
@@ -56,6 +56,13 @@ const FAILED_DIAL_CACHE_SIZE: usize = 100;
 
 type MultiaddrList = Vec<(Multiaddr, u8)>;
 
+/// The bytes of the messages received and sent by a protocol
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProtocolTraffic {
+    pub received_bytes: u64,
+    pub sent_bytes: u64,
+}
+
 #[derive(Debug, Clone)]
 pub struct SessionInfo {
     pub peer: Peer,
@@ -76,6 +83,7 @@ pub struct NetworkState {
     local_private_key: secio::SecioKeyPair,
     local_peer_id: PeerId,
     config: NetworkConfig,
+    traffic: Mutex<FnvHashMap<ProtocolId, ProtocolTraffic>>,
 }
 
 impl NetworkState {
@@ -122,6 +130,7 @@ impl NetworkState {
             local_private_key: local_private_key.clone(),
             local_peer_id: local_private_key.to_public_key().peer_id(),
             protocol_ids: RwLock::new(FnvHashSet::default()),
+            traffic: Mutex::new(FnvHashMap::default()),
         })
     }
 
@@ -140,6 +149,23 @@ impl NetworkState {
         }
     }
 
+    // The useful peers are protected from the inbound eviction, see `PeerRegistry`
+    pub(crate) fn new_block_received(&self, session_id: SessionId) {
+        self.with_peer_registry_mut(|reg| {
+            if let Some(peer) = reg.get_peer_mut(session_id) {
+                peer.last_block_received = Some(Instant::now());
+            }
+        });
+    }
+
+    pub(crate) fn new_tx_received(&self, session_id: SessionId) {
+        self.with_peer_registry_mut(|reg| {
+            if let Some(peer) = reg.get_peer_mut(session_id) {
+                peer.last_tx_received = Some(Instant::now());
+            }
+        });
+    }
+
     pub(crate) fn report_peer(
         &self,
         p2p_control: &ServiceControl,
@@ -203,6 +229,32 @@ impl NetworkState {
         }
     }
 
+    pub(crate) fn ban_network(
+        &self,
+        p2p_control: &ServiceControl,
+        target: BanTarget,
+        timeout: Duration,
+        reason: String,
+    ) {
+        debug!(target: "network", "ban {} with {:?}, reason: {}", target, timeout, reason);
+        // disconnect the connected peers in the banned network, reserved peers are never banned
+        self.with_peer_registry_mut(|reg| {
+            let session_ids = reg
+                .peers()
+                .values()
+                .filter(|peer| !peer.is_reserved && target.contains(&peer.address))
+                .map(|peer| peer.session_id)
+                .collect::<Vec<_>>();
+            for session_id in session_ids {
+                reg.remove_peer(session_id);
+                if let Err(err) = p2p_control.disconnect(session_id) {
+                    error!(target: "network", "send message to p2p service error: {:?}", err);
+                }
+            }
+        });
+        self.peer_store.lock().ban_network(target, timeout, reason);
+    }
+
     pub(crate) fn query_session_id(&self, peer_id: &PeerId) -> Option<SessionId> {
         let mut target_session_id = None;
         // Create a scope for avoid dead lock
@@ -303,6 +355,19 @@ impl NetworkState {
         self.peer_registry.read().connection_status()
     }
 
+    pub(crate) fn record_received(&self, proto_id: ProtocolId, bytes: usize) {
+        self.traffic
+            .lock()
+            .entry(proto_id)
+            .or_default()
+            .received_bytes += bytes as u64;
+    }
+
+    /// Records a message of `bytes` sent to `peers` peers
+    pub(crate) fn record_sent(&self, proto_id: ProtocolId, bytes: usize, peers: usize) {
+        self.traffic.lock().entry(proto_id).or_default().sent_bytes += (bytes * peers) as u64;
+    }
+
     pub fn external_urls(&self, max_urls: usize) -> Vec<(String, u8)> {
         let original_listened_addresses = self.original_listened_addresses.read();
         self.listened_addresses(max_urls.saturating_sub(original_listened_addresses.len()))
@@ -854,6 +919,21 @@ impl NetworkController {
             .add_node(&self.p2p_control, peer_id, address)
     }
 
+    pub fn ban_network(&self, target: BanTarget, timeout: Duration, reason: String) {
+        self.network_state
+            .ban_network(&self.p2p_control, target, timeout, reason)
+    }
+
+    pub fn banned_addrs(&self) -> Vec<BannedAddr> {
+        self.network_state
+            .with_peer_store(|peer_store| peer_store.banned_addrs())
+    }
+
+    pub fn clear_banned_addrs(&self) {
+        self.network_state
+            .with_peer_store_mut(|peer_store| peer_store.clear_ban_list())
+    }
+
     pub fn connected_peers(&self) -> Vec<(PeerId, Peer, MultiaddrList)> {
         let peers = self
             .network_state
@@ -878,19 +958,55 @@ impl NetworkController {
         })
     }
 
+    /// The score of a peer in the peer store
+    pub fn peer_score(&self, peer_id: &PeerId) -> Option<Score> {
+        self.network_state
+            .with_peer_store(|peer_store| peer_store.peer_score(peer_id))
+    }
+
+    /// The numbers of the inbound and outbound peers
+    pub fn peers_count(&self) -> (usize, usize) {
+        self.network_state.with_peer_registry(|reg| {
+            let inbound = reg
+                .peers()
+                .values()
+                .filter(|peer| peer.is_inbound())
+                .count();
+            (inbound, reg.peers().len() - inbound)
+        })
+    }
+
+    pub fn traffic(&self) -> Vec<(ProtocolId, ProtocolTraffic)> {
+        let mut traffic: Vec<_> = self
+            .network_state
+            .traffic
+            .lock()
+            .iter()
+            .map(|(proto_id, traffic)| (*proto_id, *traffic))
+            .collect();
+        traffic.sort_by_key(|(proto_id, _)| *proto_id);
+        traffic
+    }
+
     pub fn broadcast(&self, proto_id: ProtocolId, data: Vec<u8>) {
         let session_ids = self.network_state.peer_registry.read().connected_peers();
+        let (bytes, peers) = (data.len(), session_ids.len());
         if let Err(err) =
             self.p2p_control
                 .filter_broadcast(TargetSession::Multi(session_ids), proto_id, data)
         {
             warn!(target: "network", "broadcast message to {} failed: {:?}", proto_id, err);
+        } else {
+            self.network_state.record_sent(proto_id, bytes, peers);
         }
     }
 
     pub fn send_message_to(&self, session_id: SessionId, proto_id: ProtocolId, data: Vec<u8>) {
+        let bytes = data.len();
         if let Err(err) = self.p2p_control.send_message_to(session_id, proto_id, data) {
             warn!(target: "network", "send message to {} {} failed: {:?}", session_id, proto_id, err);
+        } else {
+            self.network_state.record_sent(proto_id, bytes, 1);
         }
     }
 }
@@ -1,7 +1,9 @@
 use p2p::multiaddr::{Multiaddr, Protocol};
-use std::net::IpAddr;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
 
-#[derive(Hash, Eq, PartialEq, Debug)]
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy)]
 pub enum Group {
     NoGroup,
     LocalNetwork,
@@ -67,3 +69,72 @@ impl NetworkGroup for Multiaddr {
         Group::NoGroup
     }
 }
+
+/// Either a single ip or a whole network group, the units we can ban
+#[derive(Hash, Eq, PartialEq, Debug, Clone)]
+pub enum BanTarget {
+    Ip(IpAddr),
+    Subnet(Group),
+}
+
+impl BanTarget {
+    pub fn contains(&self, addr: &Multiaddr) -> bool {
+        match self {
+            BanTarget::Ip(ip) => addr.extract_ip_addr().as_ref() == Some(ip),
+            BanTarget::Subnet(group) => addr.network_group() == *group,
+        }
+    }
+}
+
+impl FromStr for BanTarget {
+    type Err = String;
+
+    /// Accepts an ip like "192.168.0.1", or a subnet which is exactly a network group,
+    /// i.e. "192.168.0.0/16" for IPv4 and "2001:db8::/32" for IPv6.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, '/');
+        let ip = parts
+            .next()
+            .unwrap_or("")
+            .parse::<IpAddr>()
+            .map_err(|err| format!("invalid address {}: {}", s, err))?;
+        match (ip, parts.next()) {
+            (ip, None) => Ok(BanTarget::Ip(ip)),
+            (IpAddr::V4(ipv4), Some("16")) => {
+                let bits = ipv4.octets();
+                Ok(BanTarget::Subnet(Group::IP4([bits[0], bits[1]])))
+            }
+            (IpAddr::V6(ipv6), Some("32")) => {
+                let bits = ipv6.octets();
+                Ok(BanTarget::Subnet(Group::IP6([
+                    bits[0], bits[1], bits[2], bits[3],
+                ])))
+            }
+            _ => Err(format!(
+                "invalid subnet {}, only /16 for IPv4 and /32 for IPv6 are supported",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for BanTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BanTarget::Ip(ip) => write!(f, "{}", ip),
+            BanTarget::Subnet(Group::IP4(bits)) => {
+                write!(f, "{}/16", Ipv4Addr::new(bits[0], bits[1], 0, 0))
+            }
+            BanTarget::Subnet(Group::IP6(bits)) => {
+                let segment0 = u16::from(bits[0]) << 8 | u16::from(bits[1]);
+                let segment1 = u16::from(bits[2]) << 8 | u16::from(bits[3]);
+                write!(
+                    f,
+                    "{}/32",
+                    Ipv6Addr::new(segment0, segment1, 0, 0, 0, 0, 0, 0)
+                )
+            }
+            BanTarget::Subnet(group) => write!(f, "{:?}", group),
+        }
+    }
+}
@@ -20,6 +20,10 @@ pub struct Peer {
     pub identify_info: Option<PeerIdentifyInfo>,
     pub last_ping_time: Option<Instant>,
     pub last_message_time: Option<Instant>,
+    // Last time the peer sent us a new valid block
+    pub last_block_received: Option<Instant>,
+    // Last time the peer sent us a new valid transaction
+    pub last_tx_received: Option<Instant>,
     pub ping: Option<Duration>,
     pub is_feeler: bool,
     pub connected_time: Instant,
@@ -43,6 +47,8 @@ impl Peer {
             ping: None,
             last_ping_time: None,
             last_message_time: None,
+            last_block_received: None,
+            last_tx_received: None,
             connected_time: Instant::now(),
             is_feeler: false,
             peer_id,
@@ -1,12 +1,15 @@
 use crate::peer_store::PeerStore;
 use crate::{errors::PeerError, Peer, PeerId, SessionType};
-use fnv::{FnvHashMap, FnvHashSet};
+use fnv::{FnvHashMap, FnvHashSet, FnvHasher};
 use log::debug;
 use p2p::{multiaddr::Multiaddr, SessionId};
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
 
 pub(crate) const EVICTION_PROTECT_PEERS: usize = 8;
+pub(crate) const EVICTION_PROTECT_GROUPS: usize = 4;
 
 pub struct PeerRegistry {
     peers: FnvHashMap<SessionId, Peer>,
@@ -18,6 +21,9 @@ pub struct PeerRegistry {
     reserved_only: bool,
     reserved_peers: FnvHashSet<PeerId>,
     feeler_peers: FnvHashSet<PeerId>,
+    // Secret key to order network groups when protecting peers from eviction,
+    // so an attacker can not predict which groups are protected
+    eviction_key: u64,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -39,6 +45,30 @@ where
     }
 }
 
+// Same as `sort_then_drop` ordered by the time of an event, the more recently it happened the
+// more likely the peer is protected, the peers without the event are never protected
+fn sort_then_drop_by_recent<F>(list: &mut Vec<&Peer>, n: usize, time: F)
+where
+    F: Fn(&Peer) -> Option<Instant>,
+{
+    let elapsed = |peer: &Peer| {
+        time(peer)
+            .map(|t| t.elapsed())
+            .unwrap_or_else(|| Duration::from_secs(std::u64::MAX))
+    };
+    list.sort_by(|peer1, peer2| elapsed(peer2).cmp(&elapsed(peer1)));
+    if list.len() > n {
+        let protected = list
+            .iter()
+            .rev()
+            .take(n)
+            .take_while(|peer| time(peer).is_some())
+            .count();
+        let len = list.len() - protected;
+        list.truncate(len);
+    }
+}
+
 impl PeerRegistry {
     pub fn new(
         max_inbound: u32,
@@ -58,6 +88,7 @@ impl PeerRegistry {
             max_inbound,
             max_outbound,
             reserved_only,
+            eviction_key: thread_rng().gen(),
         }
     }
 
@@ -103,13 +134,14 @@ impl PeerRegistry {
             }
         }
         peer_store.add_connected_peer(&peer_id, remote_addr.clone(), session_type);
-        let peer = Peer::new(session_id, session_type, peer_id, remote_addr, is_reserved);
+        let is_feeler = self.is_feeler(&peer_id);
+        let mut peer = Peer::new(session_id, session_type, peer_id, remote_addr, is_reserved);
+        peer.is_feeler = is_feeler;
         self.peers.insert(session_id, peer);
         Ok(evicted_peer)
     }
 
     // When have inbound connection, we try evict a inbound peer
-    // TODO: revisit this after find out what's bitcoin way
     fn try_evict_inbound_peer(&self, peer_store: &PeerStore) -> Option<SessionId> {
         let mut candidate_peers = {
             self.peers
@@ -118,6 +150,9 @@ impl PeerRegistry {
                 .collect::<Vec<_>>()
         };
         // Protect peers based on characteristics that an attacker hard to simulate or manipulate
+        // Protect the longest connected peer in each of several network groups
+        self.protect_network_groups(&mut candidate_peers);
+
         // Protect peers which has the highest score
         sort_then_drop(
             &mut candidate_peers,
@@ -162,6 +197,17 @@ impl PeerRegistry {
                 peer2_last_message.cmp(&peer1_last_message)
             },
         );
+
+        // Protect peers which most recently sent us new blocks
+        sort_then_drop_by_recent(&mut candidate_peers, EVICTION_PROTECT_PEERS / 2, |peer| {
+            peer.last_block_received
+        });
+
+        // Protect peers which most recently sent us new transactions
+        sort_then_drop_by_recent(&mut candidate_peers, EVICTION_PROTECT_PEERS / 2, |peer| {
+            peer.last_tx_received
+        });
+
         // Protect half peers which have the longest connection time
         let protect_peers = candidate_peers.len() / 2;
         sort_then_drop(&mut candidate_peers, protect_peers, |peer1, peer2| {
@@ -196,6 +242,31 @@ impl PeerRegistry {
             })
     }
 
+    // Keep at most one peer per network group for `EVICTION_PROTECT_GROUPS` groups,
+    // groups are ordered by a secret keyed hash. Nothing is protected unless there are more
+    // groups, otherwise the group of an attacker would always be protected as well.
+    fn protect_network_groups(&self, candidate_peers: &mut Vec<&Peer>) {
+        let group_key = |peer: &Peer| {
+            let mut hasher = FnvHasher::with_key(self.eviction_key);
+            peer.network_group().hash(&mut hasher);
+            hasher.finish()
+        };
+        let groups = candidate_peers
+            .iter()
+            .map(|peer| group_key(*peer))
+            .collect::<FnvHashSet<_>>();
+        if groups.len() <= EVICTION_PROTECT_GROUPS {
+            return;
+        }
+        candidate_peers.sort_by_key(|peer| (group_key(*peer), peer.connected_time));
+        let mut protected_groups = FnvHashSet::default();
+        // the first peer of each group is the longest connected one
+        candidate_peers.retain(|peer| {
+            protected_groups.len() >= EVICTION_PROTECT_GROUPS
+                || !protected_groups.insert(group_key(*peer))
+        });
+    }
+
     pub fn add_feeler(&mut self, peer_id: PeerId) {
         self.feeler_peers.insert(peer_id);
     }
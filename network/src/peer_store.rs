@@ -1,5 +1,6 @@
 pub mod sqlite;
 
+pub use crate::network_group::BanTarget;
 pub use crate::{peer_store::sqlite::SqlitePeerStore, SessionType};
 pub(crate) use crate::{Behaviour, PeerId};
 use p2p::multiaddr::Multiaddr;
@@ -55,12 +56,29 @@ pub trait PeerStore: Send {
     fn random_peers(&self, count: u32) -> Vec<(PeerId, Multiaddr)>;
     /// Ban a peer
     fn ban_addr(&mut self, addr: &Multiaddr, timeout: Duration);
+    /// Ban an ip or a whole subnet
+    fn ban_network(&mut self, target: BanTarget, timeout: Duration, reason: String);
     /// Check peer ban status
     fn is_banned(&self, addr: &Multiaddr) -> bool;
+    /// Get ips and subnets which are still banned
+    fn banned_addrs(&self) -> Vec<BannedAddr>;
+    /// Unban all ips and subnets
+    fn clear_ban_list(&mut self);
     /// peer score config
     fn peer_score_config(&self) -> PeerScoreConfig;
 }
 
+/// A ban record
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BannedAddr {
+    pub address: BanTarget,
+    /// Unix time when the ban expires
+    pub ban_until: Duration,
+    pub ban_reason: String,
+    /// Unix time when the ban was created
+    pub created_at: Duration,
+}
+
 /// Peer Status
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Status {
@@ -1,11 +1,13 @@
-use crate::network_group::{Group, NetworkGroup};
+use crate::network_group::{BanTarget, Group, NetworkGroup};
 use crate::peer_store::sqlite::DBError;
-use crate::peer_store::{Multiaddr, PeerId, Score, Status};
+use crate::peer_store::{BannedAddr, Multiaddr, PeerId, Score, Status};
 use crate::SessionType;
+use faketime::unix_time;
+use log::warn;
 use rusqlite::types::ToSql;
 use rusqlite::OptionalExtension;
 use rusqlite::{Connection, NO_PARAMS};
-use std::iter::FromIterator;
+use std::net::IpAddr;
 use std::time::Duration;
 
 type DBResult<T> = Result<T, DBError>;
@@ -36,13 +38,66 @@ pub fn create_tables(conn: &Connection) -> DBResult<()> {
     "#;
     conn.execute_batch(sql)?;
     let sql = r#"
-    CREATE TABLE IF NOT EXISTS ban_list (
+    CREATE TABLE IF NOT EXISTS banned_addr (
     id INTEGER PRIMARY KEY NOT NULL,
-    ip BINARY UNIQUE NOT NULL,
-    ban_time INTEGER NOT NULL
+    address TEXT UNIQUE NOT NULL,
+    ban_time INTEGER NOT NULL,
+    ban_reason TEXT NOT NULL,
+    created_at INTEGER NOT NULL
     );
     "#;
-    conn.execute_batch(sql).map_err(Into::into)
+    conn.execute_batch(sql)?;
+    migrate_ban_list(conn)
+}
+
+// ban_list only stored raw ips, it is replaced by banned_addr which also stores subnets
+fn migrate_ban_list(conn: &Connection) -> DBResult<()> {
+    let exists = conn
+        .query_row(
+            "SELECT name FROM sqlite_master WHERE type='table' AND name='ban_list'",
+            NO_PARAMS,
+            |row| row.get::<_, String>(0),
+        )
+        .optional()?
+        .is_some();
+    if !exists {
+        return Ok(());
+    }
+    let records = {
+        let mut stmt = conn.prepare("SELECT ip, ban_time FROM ban_list")?;
+        let rows = stmt.query_map(NO_PARAMS, |row| {
+            (row.get::<_, Vec<u8>>(0), secs_to_duration(row.get(1)))
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()?
+    };
+    let now = unix_time();
+    for (ip, ban_until) in records {
+        let ip = match ip.len() {
+            4 => {
+                let mut octets = [0u8; 4];
+                octets.copy_from_slice(&ip);
+                IpAddr::from(octets)
+            }
+            16 => {
+                let mut octets = [0u8; 16];
+                octets.copy_from_slice(&ip);
+                IpAddr::from(octets)
+            }
+            _ => {
+                warn!(target: "network", "skip the banned ip {:?} of an invalid length", ip);
+                continue;
+            }
+        };
+        let banned_addr = BannedAddr {
+            address: BanTarget::Ip(ip),
+            ban_until,
+            ban_reason: String::new(),
+            created_at: now,
+        };
+        insert_ban_record(conn, &banned_addr)?;
+    }
+    conn.execute_batch("DROP TABLE ban_list;")
+        .map_err(Into::into)
 }
 
 #[derive(Debug)]
@@ -329,29 +384,62 @@ pub fn get_peers_to_feeler(
     rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
 }
 
-pub fn insert_ban_record(conn: &Connection, ip: &[u8], ban_time: Duration) -> DBResult<usize> {
-    let mut stmt =
-        conn.prepare("INSERT OR REPLACE INTO ban_list (ip, ban_time) VALUES(:ip, :ban_time);")?;
-    stmt.execute_named(&[(":ip", &ip), (":ban_time", &duration_to_secs(ban_time))])
-        .map_err(Into::into)
+pub fn insert_ban_record(conn: &Connection, banned_addr: &BannedAddr) -> DBResult<usize> {
+    let mut stmt = conn.prepare(
+        "INSERT OR REPLACE INTO banned_addr (address, ban_time, ban_reason, created_at)
+                    VALUES(:address, :ban_time, :ban_reason, :created_at);",
+    )?;
+    stmt.execute_named(&[
+        (":address", &banned_addr.address.to_string()),
+        (":ban_time", &duration_to_secs(banned_addr.ban_until)),
+        (":ban_reason", &banned_addr.ban_reason),
+        (":created_at", &duration_to_secs(banned_addr.created_at)),
+    ])
+    .map_err(Into::into)
 }
 
-pub fn get_ban_records(conn: &Connection, now: Duration) -> DBResult<Vec<(Vec<u8>, Duration)>> {
-    let mut stmt = conn.prepare("SELECT ip, ban_time FROM ban_list WHERE ban_time > :now")?;
+pub fn get_ban_records(conn: &Connection, now: Duration) -> DBResult<Vec<BannedAddr>> {
+    let mut stmt = conn.prepare(
+        "SELECT address, ban_time, ban_reason, created_at FROM banned_addr WHERE ban_time > :now",
+    )?;
     let rows = stmt.query_map_named(&[(":now", &duration_to_secs(now))], |row| {
-        (row.get::<_, Vec<u8>>(0), secs_to_duration(row.get(1)))
+        parse_ban_target(row.get(0)).map(|address| BannedAddr {
+            address,
+            ban_until: secs_to_duration(row.get(1)),
+            ban_reason: row.get(2),
+            created_at: secs_to_duration(row.get(3)),
+        })
     })?;
-    Result::from_iter(rows).map_err(Into::into)
+    let records = rows.collect::<Result<Vec<_>, _>>()?;
+    Ok(records.into_iter().filter_map(|record| record).collect())
 }
 
-pub fn clear_expires_banned_ip(conn: &Connection, now: Duration) -> DBResult<Vec<Vec<u8>>> {
-    let mut stmt = conn.prepare("SELECT ip FROM ban_list WHERE ban_time < :now")?;
+pub fn clear_expires_banned_addr(conn: &Connection, now: Duration) -> DBResult<Vec<BanTarget>> {
+    let mut stmt = conn.prepare("SELECT address FROM banned_addr WHERE ban_time < :now")?;
     let rows = stmt.query_map_named(&[(":now", &duration_to_secs(now))], |row| {
-        row.get::<_, Vec<u8>>(0)
+        parse_ban_target(row.get(0))
     })?;
-    let mut stmt = conn.prepare("DELETE FROM ban_list WHERE ban_time < :now")?;
+    let addrs = rows.collect::<Result<Vec<_>, _>>()?;
+    let addrs = addrs.into_iter().filter_map(|addr| addr).collect();
+    let mut stmt = conn.prepare("DELETE FROM banned_addr WHERE ban_time < :now")?;
     stmt.execute_named(&[(":now", &duration_to_secs(now))])?;
-    Result::from_iter(rows).map_err(Into::into)
+    Ok(addrs)
+}
+
+pub fn clear_ban_records(conn: &Connection) -> DBResult<usize> {
+    conn.execute("DELETE FROM banned_addr", NO_PARAMS)
+        .map_err(Into::into)
+}
+
+// a row which doesn't parse is logged and skipped
+fn parse_ban_target(address: String) -> Option<BanTarget> {
+    match address.parse() {
+        Ok(target) => Some(target),
+        Err(err) => {
+            warn!(target: "network", "skip the invalid banned address: {}", err);
+            None
+        }
+    }
 }
 
 fn status_to_u8(status: Status) -> u8 {
@@ -362,12 +450,13 @@ fn u8_to_status(i: u8) -> Status {
     Status::from(i)
 }
 
-fn secs_to_duration(secs: u32) -> Duration {
-    Duration::from_secs(secs.into())
+// The times are stored as i64, the INTEGER of sqlite, far times such as a long ban are clamped
+fn secs_to_duration(secs: i64) -> Duration {
+    Duration::from_secs(secs.max(0) as u64)
 }
 
-fn duration_to_secs(duration: Duration) -> u32 {
-    duration.as_secs() as u32
+fn duration_to_secs(duration: Duration) -> i64 {
+    duration.as_secs().min(i64::max_value() as u64) as i64
 }
 
 fn endpoint_to_bool(endpoint: SessionType) -> bool {
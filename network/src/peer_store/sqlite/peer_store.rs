@@ -1,4 +1,4 @@
-use crate::network_group::MultiaddrExt;
+use crate::network_group::{BanTarget, MultiaddrExt, NetworkGroup};
 use crate::peer_store::sqlite::{db, DBError};
 /// SqlitePeerStore
 /// Principles:
@@ -12,7 +12,8 @@ use crate::peer_store::sqlite::{db, DBError};
 ///    score.
 /// 4. Good peers can get higher score than bad peers.
 use crate::peer_store::{
-    Behaviour, Multiaddr, PeerId, PeerScoreConfig, PeerStore, ReportResult, Score, Status,
+    BannedAddr, Behaviour, Multiaddr, PeerId, PeerScoreConfig, PeerStore, ReportResult, Score,
+    Status,
 };
 use crate::SessionType;
 use faketime::unix_time;
@@ -31,7 +32,7 @@ const DEFAULT_ADDRS: u32 = 3;
 pub struct SqlitePeerStore {
     bootnodes: Vec<(PeerId, Multiaddr)>,
     peer_score_config: PeerScoreConfig,
-    ban_list: FnvHashMap<Vec<u8>, Duration>,
+    ban_list: FnvHashMap<BanTarget, Duration>,
     pub(crate) conn: Connection,
 }
 
@@ -77,47 +78,52 @@ impl SqlitePeerStore {
     }
 
     fn load_banlist(&mut self) -> Result<(), DBError> {
-        self.clear_expires_banned_ip()?;
+        self.clear_expires_banned_addr()?;
         let now = unix_time();
         let ban_records = db::get_ban_records(&self.conn, now)?;
-        for (ip, ban_time) in ban_records {
-            self.ban_list.insert(ip, ban_time);
+        for banned_addr in ban_records {
+            self.ban_list
+                .insert(banned_addr.address, banned_addr.ban_until);
         }
         Ok(())
     }
 
-    fn ban_ip(&mut self, addr: &Multiaddr, timeout: Duration) {
-        let ip = {
-            match addr.extract_ip_addr_binary() {
-                Some(binary) => binary,
-                None => return,
-            }
+    fn ban_target(&mut self, target: BanTarget, timeout: Duration, ban_reason: String) {
+        let now = unix_time();
+        let banned_addr = BannedAddr {
+            address: target,
+            ban_until: now + timeout,
+            ban_reason,
+            created_at: now,
         };
-        let ban_time = unix_time() + timeout;
-        db::insert_ban_record(&self.conn, &ip, ban_time).expect("ban ip");
-        self.ban_list.insert(ip, ban_time);
+        db::insert_ban_record(&self.conn, &banned_addr).expect("ban addr");
+        self.ban_list
+            .insert(banned_addr.address, banned_addr.ban_until);
         if self.ban_list.len() > BAN_LIST_CLEAR_EXPIRES_SIZE {
-            self.clear_expires_banned_ip().expect("clear ban list");
+            self.clear_expires_banned_addr().expect("clear ban list");
         }
     }
 
     fn is_addr_banned(&self, addr: &Multiaddr) -> bool {
-        let ip = match addr.extract_ip_addr_binary() {
+        let ip = match addr.extract_ip_addr() {
             Some(ip) => ip,
             None => return false,
         };
         let now = unix_time();
-        match self.ban_list.get(&ip) {
-            Some(ban_time) => *ban_time > now,
-            None => false,
-        }
+        // the ip itself or its whole network group may be banned
+        [BanTarget::Ip(ip), BanTarget::Subnet(addr.network_group())]
+            .iter()
+            .any(|target| match self.ban_list.get(target) {
+                Some(ban_time) => *ban_time > now,
+                None => false,
+            })
     }
 
-    fn clear_expires_banned_ip(&mut self) -> Result<(), DBError> {
+    fn clear_expires_banned_addr(&mut self) -> Result<(), DBError> {
         let now = unix_time();
-        let ips = db::clear_expires_banned_ip(&self.conn, now)?;
-        for ip in ips {
-            self.ban_list.remove(&ip);
+        let addrs = db::clear_expires_banned_addr(&self.conn, now)?;
+        for addr in addrs {
+            self.ban_list.remove(&addr);
         }
         Ok(())
     }
@@ -312,9 +318,15 @@ impl PeerStore for SqlitePeerStore {
         .expect("get random peers")
     }
 
-    #[inline]
     fn ban_addr(&mut self, addr: &Multiaddr, timeout: Duration) {
-        self.ban_ip(addr, timeout)
+        if let Some(ip) = addr.extract_ip_addr() {
+            self.ban_target(BanTarget::Ip(ip), timeout, "misbehavior".to_string())
+        }
+    }
+
+    #[inline]
+    fn ban_network(&mut self, target: BanTarget, timeout: Duration, reason: String) {
+        self.ban_target(target, timeout, reason)
     }
 
     #[inline]
@@ -322,6 +334,15 @@ impl PeerStore for SqlitePeerStore {
         self.is_addr_banned(&addr)
     }
 
+    fn banned_addrs(&self) -> Vec<BannedAddr> {
+        db::get_ban_records(&self.conn, unix_time()).expect("get ban records")
+    }
+
+    fn clear_ban_list(&mut self) {
+        db::clear_ban_records(&self.conn).expect("clear ban records");
+        self.ban_list.clear();
+    }
+
     fn peer_score_config(&self) -> PeerScoreConfig {
         self.peer_score_config
     }
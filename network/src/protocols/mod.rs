@@ -31,6 +31,10 @@ pub trait CKBProtocolContext: Send {
     fn connected_peers(&self) -> Vec<PeerIndex>;
     fn report_peer(&self, peer_index: PeerIndex, behaviour: Behaviour);
     fn ban_peer(&self, peer_index: PeerIndex, timeout: Duration);
+    /// The peer sent us a new valid block
+    fn new_block_received(&self, peer_index: PeerIndex);
+    /// The peer sent us a new valid transaction
+    fn new_tx_received(&self, peer_index: PeerIndex);
     // Other methods
     fn protocol_id(&self) -> ProtocolId;
 }
@@ -173,6 +177,8 @@ impl ServiceProtocol for CKBHandler {
 
     fn received(&mut self, context: ProtocolContextMutRef, data: bytes::Bytes) {
         trace!(target: "network", "[received message]: {}, {}, length={}", self.proto_id, context.session.id, data.len());
+        self.network_state
+            .record_received(self.proto_id, data.len());
         let nc = DefaultCKBProtocolContext {
             proto_id: self.proto_id,
             network_state: Arc::clone(&self.network_state),
@@ -222,19 +228,32 @@ impl CKBProtocolContext for DefaultCKBProtocolContext {
     }
     fn send_message_to(&self, peer_index: PeerIndex, data: Vec<u8>) {
         trace!(target: "network", "[send message]: {}, to={}, length={}", self.proto_id, peer_index, data.len());
+        let bytes = data.len();
         if let Err(err) = self
             .p2p_control
             .send_message_to(peer_index, self.proto_id, data)
         {
             error!(target: "network", "send message to p2p service error: {:?}", err);
+        } else {
+            self.network_state.record_sent(self.proto_id, bytes, 1);
         }
     }
     fn filter_broadcast(&self, target: TargetSession, data: Vec<u8>) {
+        let bytes = data.len();
+        let peers = match target {
+            TargetSession::All => self
+                .network_state
+                .with_peer_registry(|reg| reg.peers().len()),
+            TargetSession::Single(_) => 1,
+            TargetSession::Multi(ref session_ids) => session_ids.len(),
+        };
         if let Err(err) = self
             .p2p_control
             .filter_broadcast(target, self.proto_id, data)
         {
             error!(target: "network", "send message to p2p service error: {:?}", err);
+        } else {
+            self.network_state.record_sent(self.proto_id, bytes, peers);
         }
     }
     fn disconnect(&self, peer_index: PeerIndex) {
@@ -259,6 +278,12 @@ impl CKBProtocolContext for DefaultCKBProtocolContext {
         self.network_state
             .ban_session(&self.p2p_control, peer_index, timeout);
     }
+    fn new_block_received(&self, peer_index: PeerIndex) {
+        self.network_state.new_block_received(peer_index);
+    }
+    fn new_tx_received(&self, peer_index: PeerIndex) {
+        self.network_state.new_tx_received(peer_index);
+    }
 
     fn protocol_id(&self) -> ProtocolId {
         self.proto_id
@@ -1,4 +1,6 @@
+use crate::network_group::{Group, NetworkGroup};
 use crate::NetworkState;
+use fnv::FnvHashSet;
 use futures::{try_ready, Async, Stream};
 use log::{debug, trace, warn};
 use p2p::service::ServiceControl;
@@ -29,11 +31,21 @@ impl OutboundPeerService {
     }
 
     fn attempt_dial_peers(&mut self, count: u32) {
+        // fetch more peers than needed, since peers in the same network group are skipped
         let attempt_peers = self
             .network_state
-            .with_peer_store(|peer_store| peer_store.peers_to_attempt(count + 5));
+            .with_peer_store(|peer_store| peer_store.peers_to_attempt(count * 3 + 5));
         let p2p_control = self.p2p_control.clone();
         trace!(target: "network", "count={}, attempt_peers: {:?}", count, attempt_peers);
+        // Only one outbound connection per network group, so that a single
+        // hosting provider can not occupy all our outbound connections
+        let mut outbound_groups = self.network_state.with_peer_registry(|reg| {
+            reg.peers()
+                .values()
+                .filter(|peer| peer.is_outbound() && !peer.is_reserved)
+                .map(|peer| peer.network_group())
+                .collect::<FnvHashSet<_>>()
+        });
         for (peer_id, addr) in attempt_peers
             .into_iter()
             .filter(|(peer_id, _addr)| {
@@ -52,6 +64,13 @@ impl OutboundPeerService {
                         })
                         .unwrap_or(true)
             })
+            .filter(|(_peer_id, addr)| {
+                let group = addr.network_group();
+                // local and ungrouped addresses are not limited
+                group == Group::LocalNetwork
+                    || group == Group::NoGroup
+                    || outbound_groups.insert(group)
+            })
             .take(count as usize)
         {
             debug!(target: "network", "dial attempt peer: {:?}", addr);
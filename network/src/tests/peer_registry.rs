@@ -1,9 +1,9 @@
 use crate::{
     errors::PeerError,
     multiaddr::ToMultiaddr,
-    peer_registry::{PeerRegistry, EVICTION_PROTECT_PEERS},
+    peer_registry::{PeerRegistry, EVICTION_PROTECT_GROUPS, EVICTION_PROTECT_PEERS},
     peer_store::{PeerStore, SqlitePeerStore},
-    Behaviour, PeerId, SessionType,
+    Behaviour, Peer, PeerId, SessionType,
 };
 use std::time::{Duration, Instant};
 
@@ -287,3 +287,143 @@ fn test_accept_inbound_peer_eviction() {
         .expect("accept");
     assert!(peers_registry.get_key_by_peer_id(&evict_target).is_none());
 }
+
+fn update_peer<F>(peers_registry: &mut PeerRegistry, peer_id: &PeerId, update: F)
+where
+    F: FnOnce(&mut Peer),
+{
+    let session_id = peers_registry
+        .get_key_by_peer_id(peer_id)
+        .expect("get_key_by_peer_id failed");
+    if let Some(peer) = peers_registry.get_peer_mut(session_id) {
+        update(peer);
+    };
+}
+
+#[test]
+fn test_accept_inbound_peer_eviction_protect_network_groups() {
+    // the longest connected peer of a network group is protected
+    // when there are more than EVICTION_PROTECT_GROUPS groups
+    let mut peer_store = new_peer_store();
+    let groups = EVICTION_PROTECT_GROUPS + 1;
+    let mut peers_registry = PeerRegistry::new((groups * 2) as u32, 3, false, vec![]);
+    // to prevent time error, we set now to 10ago.
+    let now = Instant::now() - Duration::from_secs(10);
+    let mut longest_connected_peers = Vec::new();
+    let mut new_peers = Vec::new();
+    for group in 0..groups {
+        let addr = format!("/ip4/10.{}.0.1", group)
+            .as_str()
+            .to_multiaddr()
+            .unwrap();
+        let longest_connected_peer = PeerId::random();
+        let new_peer = PeerId::random();
+        for (offset, peer_id) in [&longest_connected_peer, &new_peer].iter().enumerate() {
+            peers_registry
+                .accept_peer(
+                    (*peer_id).clone(),
+                    addr.clone(),
+                    (group * 2 + offset).into(),
+                    SessionType::Inbound,
+                    peer_store.as_mut(),
+                )
+                .expect("accept");
+        }
+        // the longest connected peers have the lowest score,
+        // they would be evicted if they were not protected by their network group
+        peer_store.report(&longest_connected_peer, Behaviour::FailedToPing);
+        update_peer(&mut peers_registry, &longest_connected_peer, |peer| {
+            peer.connected_time = now - Duration::from_secs(10);
+        });
+        update_peer(&mut peers_registry, &new_peer, |peer| {
+            peer.connected_time = now + Duration::from_secs(group as u64 + 1);
+        });
+        longest_connected_peers.push(longest_connected_peer);
+        new_peers.push(new_peer);
+    }
+
+    peers_registry
+        .accept_peer(
+            PeerId::random(),
+            "/ip4/10.0.0.2".to_multiaddr().unwrap(),
+            1000.into(),
+            SessionType::Inbound,
+            peer_store.as_mut(),
+        )
+        .expect("accept");
+    for peer_id in &longest_connected_peers {
+        assert!(peers_registry.get_key_by_peer_id(peer_id).is_some());
+    }
+    assert_eq!(
+        new_peers
+            .iter()
+            .filter(|peer_id| peers_registry.get_key_by_peer_id(peer_id).is_none())
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn test_accept_inbound_peer_eviction_protect_new_blocks_and_transactions() {
+    // peers which most recently sent us new blocks or new transactions are protected
+    let mut peer_store = new_peer_store();
+    let addr = "/ip4/10.0.0.1".to_multiaddr().unwrap();
+    let peers_count = EVICTION_PROTECT_PEERS;
+    let mut peers_registry = PeerRegistry::new(peers_count as u32, 3, false, vec![]);
+    let peer_ids = (0..peers_count)
+        .map(|_| PeerId::random())
+        .collect::<Vec<_>>();
+    for (session_id, peer_id) in peer_ids.iter().enumerate() {
+        peers_registry
+            .accept_peer(
+                peer_id.clone(),
+                addr.clone(),
+                session_id.into(),
+                SessionType::Inbound,
+                peer_store.as_mut(),
+            )
+            .expect("accept");
+    }
+
+    // to prevent time error, we set now to 10ago.
+    let now = Instant::now() - Duration::from_secs(10);
+    let (useful_peers, other_peers) = peer_ids.split_at(EVICTION_PROTECT_PEERS / 2);
+    for (i, peer_id) in useful_peers.iter().enumerate() {
+        // the useful peers are the newest connected ones and have the lowest score,
+        // they would be evicted if they were not protected by their new blocks and transactions
+        peer_store.report(peer_id, Behaviour::FailedToPing);
+        update_peer(&mut peers_registry, peer_id, |peer| {
+            peer.connected_time = now + Duration::from_secs(10);
+            if i % 2 == 0 {
+                peer.last_block_received = Some(now + Duration::from_secs(10));
+            } else {
+                peer.last_tx_received = Some(now + Duration::from_secs(10));
+            }
+        });
+    }
+    for peer_id in other_peers {
+        update_peer(&mut peers_registry, peer_id, |peer| {
+            peer.connected_time = now;
+        });
+    }
+
+    peers_registry
+        .accept_peer(
+            PeerId::random(),
+            addr.clone(),
+            1000.into(),
+            SessionType::Inbound,
+            peer_store.as_mut(),
+        )
+        .expect("accept");
+    for peer_id in useful_peers {
+        assert!(peers_registry.get_key_by_peer_id(peer_id).is_some());
+    }
+    assert_eq!(
+        other_peers
+            .iter()
+            .filter(|peer_id| peers_registry.get_key_by_peer_id(peer_id).is_none())
+            .count(),
+        1
+    );
+}
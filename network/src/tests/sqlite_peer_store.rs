@@ -3,7 +3,7 @@ use crate::{
     peer_store::{
         sqlite::db,
         sqlite::peer_store::{LAST_CONNECTED_TIMEOUT_SECS, PEER_STORE_LIMIT},
-        PeerStore, SqlitePeerStore, Status,
+        BanTarget, PeerScoreConfig, PeerStore, SqlitePeerStore, Status,
     },
     Behaviour, PeerId, SessionType,
 };
@@ -66,6 +66,80 @@ fn test_ban_peer() {
     assert!(peer_store.is_banned(&addr));
 }
 
+#[test]
+fn test_ban_subnet() {
+    let mut peer_store: Box<dyn PeerStore> = Box::new(new_peer_store());
+    let addr1 = "/ip4/192.168.0.1".to_multiaddr().unwrap();
+    let addr2 = "/ip4/192.168.3.4".to_multiaddr().unwrap();
+    let addr3 = "/ip4/192.169.0.1".to_multiaddr().unwrap();
+    let subnet = "192.168.0.0/16".parse::<BanTarget>().unwrap();
+    peer_store.ban_network(subnet.clone(), Duration::from_secs(10), "test".to_string());
+    assert!(peer_store.is_banned(&addr1));
+    assert!(peer_store.is_banned(&addr2));
+    assert!(!peer_store.is_banned(&addr3));
+
+    let banned_addrs = peer_store.banned_addrs();
+    assert_eq!(banned_addrs.len(), 1);
+    assert_eq!(banned_addrs[0].address, subnet);
+    assert_eq!(banned_addrs[0].ban_reason, "test");
+
+    peer_store.clear_ban_list();
+    assert!(!peer_store.is_banned(&addr1));
+    assert!(peer_store.banned_addrs().is_empty());
+}
+
+#[test]
+fn test_ban_for_long() {
+    let mut peer_store: Box<dyn PeerStore> = Box::new(new_peer_store());
+    let addr = "/ip4/127.0.0.1".to_multiaddr().unwrap();
+    let target = "127.0.0.1".parse::<BanTarget>().unwrap();
+    // the seconds exceed u32
+    let ban_time = Duration::from_millis(std::u64::MAX);
+    peer_store.ban_network(target.clone(), ban_time, "test".to_string());
+    assert!(peer_store.is_banned(&addr));
+
+    // the banned addresses are read from the database
+    let banned_addrs = peer_store.banned_addrs();
+    assert_eq!(banned_addrs.len(), 1);
+    assert_eq!(banned_addrs[0].address, target);
+    assert!(banned_addrs[0].ban_until > ban_time);
+}
+
+#[test]
+fn test_parse_ban_target() {
+    for address in &["192.168.0.1", "192.168.0.0/16", "2001:db8::/32"] {
+        let target = address.parse::<BanTarget>().unwrap();
+        assert_eq!(target.to_string(), *address);
+    }
+    assert!("192.168.0.0/24".parse::<BanTarget>().is_err());
+    assert!("localhost".parse::<BanTarget>().is_err());
+}
+
+#[test]
+fn test_migrate_ban_list() {
+    let conn = rusqlite::Connection::open_in_memory().expect("memory");
+    let ban_until = (faketime::unix_time() + Duration::from_secs(100)).as_secs() as u32;
+    conn.execute_batch(&format!(
+        r#"
+        CREATE TABLE ban_list (
+        id INTEGER PRIMARY KEY NOT NULL,
+        ip BINARY UNIQUE NOT NULL,
+        ban_time INTEGER NOT NULL
+        );
+        INSERT INTO ban_list (ip, ban_time) VALUES (x'7f000001', {0});
+        INSERT INTO ban_list (ip, ban_time) VALUES (x'7f00', {0});
+        "#,
+        ban_until
+    ))
+    .expect("create ban_list");
+
+    let peer_store = SqlitePeerStore::new(conn, PeerScoreConfig::default());
+    assert!(peer_store.is_banned(&"/ip4/127.0.0.1".to_multiaddr().unwrap()));
+    let banned_addrs = peer_store.banned_addrs();
+    assert_eq!(banned_addrs.len(), 1);
+    assert_eq!(banned_addrs[0].address.to_string(), "127.0.0.1");
+}
+
 #[test]
 fn test_attepmt_ban() {
     let mut peer_store: Box<dyn PeerStore> = Box::new(new_peer_store());
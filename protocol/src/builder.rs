@@ -1,17 +1,19 @@
 use crate::protocol_generated::ckb::protocol::{
     Block as FbsBlock, BlockBuilder, BlockProposalBuilder, BlockTransactionsBuilder,
-    Bytes as FbsBytes, BytesBuilder, CellInput as FbsCellInput, CellInputBuilder,
-    CellOutput as FbsCellOutput, CellOutputBuilder, CompactBlock, CompactBlockBuilder,
-    FilteredBlock, FilteredBlockBuilder, GetBlockProposalBuilder, GetBlockTransactionsBuilder,
-    GetBlocks as FbsGetBlocks, GetBlocksBuilder, GetHeaders as FbsGetHeaders, GetHeadersBuilder,
+    BlocksNotFoundBuilder, Bytes as FbsBytes, BytesBuilder, CellInput as FbsCellInput,
+    CellInputBuilder, CellOutput as FbsCellOutput, CellOutputBuilder, CompactBlock,
+    CompactBlockBuilder, FilteredBlock, FilteredBlockBuilder, GetBlockProposalBuilder,
+    GetBlockTransactionsBuilder, GetBlocks as FbsGetBlocks, GetBlocksBuilder,
+    GetHeaders as FbsGetHeaders, GetHeadersBuilder, GetRelayTransactionsBuilder,
     Header as FbsHeader, HeaderBuilder, Headers as FbsHeaders, HeadersBuilder,
     IndexTransactionBuilder, MerkleProofBuilder, OutPoint as FbsOutPoint, OutPointBuilder,
     ProposalShortId as FbsProposalShortId, RelayMessage, RelayMessageBuilder, RelayPayload,
-    RelayTransaction as FbsRelayTransaction, RelayTransactionBuilder, Script as FbsScript,
-    ScriptBuilder, SyncMessage, SyncMessageBuilder, SyncPayload, Time as FbsTime, TimeBuilder,
-    TimeMessage, TimeMessageBuilder, Transaction as FbsTransaction, TransactionBuilder,
-    UncleBlock as FbsUncleBlock, UncleBlockBuilder, Witness as FbsWitness, WitnessBuilder,
-    H256 as FbsH256,
+    RelayTransaction as FbsRelayTransaction, RelayTransactionBuilder,
+    RelayTransactionHashesBuilder, RelayTransactionsBuilder, Script as FbsScript, ScriptBuilder,
+    SendCompactBlockBuilder, SyncMessage, SyncMessageBuilder, SyncPayload, Time as FbsTime,
+    TimeBuilder, TimeMessage, TimeMessageBuilder, Transaction as FbsTransaction,
+    TransactionBuilder, UncleBlock as FbsUncleBlock, UncleBlockBuilder, Witness as FbsWitness,
+    WitnessBuilder, H256 as FbsH256,
 };
 use crate::{short_transaction_id, short_transaction_id_keys};
 use ckb_core::block::Block;
@@ -365,6 +367,26 @@ impl<'a> SyncMessage<'a> {
         builder.finish()
     }
 
+    pub fn build_blocks_not_found<'b>(
+        fbb: &mut FlatBufferBuilder<'b>,
+        block_hashes: &[H256],
+    ) -> WIPOffset<SyncMessage<'b>> {
+        let blocks_not_found = {
+            let vec = block_hashes
+                .iter()
+                .map(Into::into)
+                .collect::<Vec<FbsH256>>();
+            let block_hashes = fbb.create_vector(&vec);
+            let mut builder = BlocksNotFoundBuilder::new(fbb);
+            builder.add_block_hashes(block_hashes);
+            builder.finish()
+        };
+        let mut builder = SyncMessageBuilder::new(fbb);
+        builder.add_payload_type(SyncPayload::BlocksNotFound);
+        builder.add_payload(blocks_not_found.as_union_value());
+        builder.finish()
+    }
+
     pub fn build_block<'b>(
         fbb: &mut FlatBufferBuilder<'b>,
         block: &Block,
@@ -528,6 +550,79 @@ impl<'a> RelayMessage<'a> {
         builder.finish()
     }
 
+    pub fn build_transactions<'b>(
+        fbb: &mut FlatBufferBuilder<'b>,
+        transactions: &[(Transaction, Cycle)],
+    ) -> WIPOffset<RelayMessage<'b>> {
+        let relay_transactions = {
+            let vec = transactions
+                .iter()
+                .map(|(transaction, cycles)| FbsRelayTransaction::build(fbb, transaction, *cycles))
+                .collect::<Vec<_>>();
+            let transactions = fbb.create_vector(&vec);
+            let mut builder = RelayTransactionsBuilder::new(fbb);
+            builder.add_transactions(transactions);
+            builder.finish()
+        };
+
+        let mut builder = RelayMessageBuilder::new(fbb);
+        builder.add_payload_type(RelayPayload::RelayTransactions);
+        builder.add_payload(relay_transactions.as_union_value());
+        builder.finish()
+    }
+
+    pub fn build_transaction_hashes<'b>(
+        fbb: &mut FlatBufferBuilder<'b>,
+        tx_hashes: &[H256],
+    ) -> WIPOffset<RelayMessage<'b>> {
+        let relay_transaction_hashes = {
+            let vec = tx_hashes.iter().map(Into::into).collect::<Vec<FbsH256>>();
+            let tx_hashes = fbb.create_vector(&vec);
+            let mut builder = RelayTransactionHashesBuilder::new(fbb);
+            builder.add_tx_hashes(tx_hashes);
+            builder.finish()
+        };
+
+        let mut builder = RelayMessageBuilder::new(fbb);
+        builder.add_payload_type(RelayPayload::RelayTransactionHashes);
+        builder.add_payload(relay_transaction_hashes.as_union_value());
+        builder.finish()
+    }
+
+    pub fn build_get_transactions<'b>(
+        fbb: &mut FlatBufferBuilder<'b>,
+        tx_hashes: &[H256],
+    ) -> WIPOffset<RelayMessage<'b>> {
+        let get_relay_transactions = {
+            let vec = tx_hashes.iter().map(Into::into).collect::<Vec<FbsH256>>();
+            let tx_hashes = fbb.create_vector(&vec);
+            let mut builder = GetRelayTransactionsBuilder::new(fbb);
+            builder.add_tx_hashes(tx_hashes);
+            builder.finish()
+        };
+
+        let mut builder = RelayMessageBuilder::new(fbb);
+        builder.add_payload_type(RelayPayload::GetRelayTransactions);
+        builder.add_payload(get_relay_transactions.as_union_value());
+        builder.finish()
+    }
+
+    pub fn build_send_compact_block<'b>(
+        fbb: &mut FlatBufferBuilder<'b>,
+        high_bandwidth: bool,
+    ) -> WIPOffset<RelayMessage<'b>> {
+        let send_compact_block = {
+            let mut builder = SendCompactBlockBuilder::new(fbb);
+            builder.add_high_bandwidth(high_bandwidth);
+            builder.finish()
+        };
+
+        let mut builder = RelayMessageBuilder::new(fbb);
+        builder.add_payload_type(RelayPayload::SendCompactBlock);
+        builder.add_payload(send_compact_block.as_union_value());
+        builder.finish()
+    }
+
     pub fn build_get_block_transactions<'b>(
         fbb: &mut FlatBufferBuilder<'b>,
         block_hash: &H256,
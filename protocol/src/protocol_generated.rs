@@ -37,11 +37,12 @@ pub enum SyncPayload {
   AddFilter = 6,
   ClearFilter = 7,
   FilteredBlock = 8,
+  BlocksNotFound = 9,
 
 }
 
 const ENUM_MIN_SYNC_PAYLOAD: u8 = 0;
-const ENUM_MAX_SYNC_PAYLOAD: u8 = 8;
+const ENUM_MAX_SYNC_PAYLOAD: u8 = 9;
 
 impl<'a> flatbuffers::Follow<'a> for SyncPayload {
   type Inner = Self;
@@ -75,7 +76,7 @@ impl flatbuffers::Push for SyncPayload {
 }
 
 #[allow(non_camel_case_types)]
-const ENUM_VALUES_SYNC_PAYLOAD:[SyncPayload; 9] = [
+const ENUM_VALUES_SYNC_PAYLOAD:[SyncPayload; 10] = [
   SyncPayload::NONE,
   SyncPayload::GetHeaders,
   SyncPayload::Headers,
@@ -84,11 +85,12 @@ const ENUM_VALUES_SYNC_PAYLOAD:[SyncPayload; 9] = [
   SyncPayload::SetFilter,
   SyncPayload::AddFilter,
   SyncPayload::ClearFilter,
-  SyncPayload::FilteredBlock
+  SyncPayload::FilteredBlock,
+  SyncPayload::BlocksNotFound
 ];
 
 #[allow(non_camel_case_types)]
-const ENUM_NAMES_SYNC_PAYLOAD:[&'static str; 9] = [
+const ENUM_NAMES_SYNC_PAYLOAD:[&'static str; 10] = [
     "NONE",
     "GetHeaders",
     "Headers",
@@ -97,7 +99,8 @@ const ENUM_NAMES_SYNC_PAYLOAD:[&'static str; 9] = [
     "SetFilter",
     "AddFilter",
     "ClearFilter",
-    "FilteredBlock"
+    "FilteredBlock",
+    "BlocksNotFound"
 ];
 
 pub fn enum_name_sync_payload(e: SyncPayload) -> &'static str {
@@ -117,11 +120,15 @@ pub enum RelayPayload {
   BlockTransactions = 4,
   GetBlockProposal = 5,
   BlockProposal = 6,
+  RelayTransactionHashes = 7,
+  GetRelayTransactions = 8,
+  SendCompactBlock = 9,
+  RelayTransactions = 10,
 
 }
 
 const ENUM_MIN_RELAY_PAYLOAD: u8 = 0;
-const ENUM_MAX_RELAY_PAYLOAD: u8 = 6;
+const ENUM_MAX_RELAY_PAYLOAD: u8 = 10;
 
 impl<'a> flatbuffers::Follow<'a> for RelayPayload {
   type Inner = Self;
@@ -155,25 +162,33 @@ impl flatbuffers::Push for RelayPayload {
 }
 
 #[allow(non_camel_case_types)]
-const ENUM_VALUES_RELAY_PAYLOAD:[RelayPayload; 7] = [
+const ENUM_VALUES_RELAY_PAYLOAD:[RelayPayload; 11] = [
   RelayPayload::NONE,
   RelayPayload::CompactBlock,
   RelayPayload::RelayTransaction,
   RelayPayload::GetBlockTransactions,
   RelayPayload::BlockTransactions,
   RelayPayload::GetBlockProposal,
-  RelayPayload::BlockProposal
+  RelayPayload::BlockProposal,
+  RelayPayload::RelayTransactionHashes,
+  RelayPayload::GetRelayTransactions,
+  RelayPayload::SendCompactBlock,
+  RelayPayload::RelayTransactions
 ];
 
 #[allow(non_camel_case_types)]
-const ENUM_NAMES_RELAY_PAYLOAD:[&'static str; 7] = [
+const ENUM_NAMES_RELAY_PAYLOAD:[&'static str; 11] = [
     "NONE",
     "CompactBlock",
     "RelayTransaction",
     "GetBlockTransactions",
     "BlockTransactions",
     "GetBlockProposal",
-    "BlockProposal"
+    "BlockProposal",
+    "RelayTransactionHashes",
+    "GetRelayTransactions",
+    "SendCompactBlock",
+    "RelayTransactions"
 ];
 
 pub fn enum_name_relay_payload(e: RelayPayload) -> &'static str {
@@ -619,6 +634,16 @@ impl<'a> SyncMessage<'a> {
     }
   }
 
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn payload_as_blocks_not_found(&'a self) -> Option<BlocksNotFound> {
+    if self.payload_type() == SyncPayload::BlocksNotFound {
+      self.payload().map(|u| BlocksNotFound::init_from_table(u))
+    } else {
+      None
+    }
+  }
+
 }
 
 pub struct SyncMessageArgs {
@@ -914,6 +939,82 @@ impl<'a: 'b, 'b> GetBlocksBuilder<'a, 'b> {
   }
 }
 
+pub enum BlocksNotFoundOffset {}
+#[derive(Copy, Clone, Debug, PartialEq)]
+
+pub struct BlocksNotFound<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for BlocksNotFound<'a> {
+    type Inner = BlocksNotFound<'a>;
+    #[inline]
+    fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+        Self {
+            _tab: flatbuffers::Table { buf: buf, loc: loc },
+        }
+    }
+}
+
+impl<'a> BlocksNotFound<'a> {
+    #[inline]
+    pub fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+        BlocksNotFound {
+            _tab: table,
+        }
+    }
+    #[allow(unused_mut)]
+    pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr>(
+        _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr>,
+        args: &'args BlocksNotFoundArgs<'args>) -> flatbuffers::WIPOffset<BlocksNotFound<'bldr>> {
+      let mut builder = BlocksNotFoundBuilder::new(_fbb);
+      if let Some(x) = args.block_hashes { builder.add_block_hashes(x); }
+      builder.finish()
+    }
+
+    pub const VT_BLOCK_HASHES: flatbuffers::VOffsetT = 4;
+
+  #[inline]
+  pub fn block_hashes(&self) -> Option<&'a [H256]> {
+    self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<H256>>>(BlocksNotFound::VT_BLOCK_HASHES, None).map(|v| v.safe_slice() )
+  }
+}
+
+pub struct BlocksNotFoundArgs<'a> {
+    pub block_hashes: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a , H256>>>,
+}
+impl<'a> Default for BlocksNotFoundArgs<'a> {
+    #[inline]
+    fn default() -> Self {
+        BlocksNotFoundArgs {
+            block_hashes: None,
+        }
+    }
+}
+pub struct BlocksNotFoundBuilder<'a: 'b, 'b> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b> BlocksNotFoundBuilder<'a, 'b> {
+  #[inline]
+  pub fn add_block_hashes(&mut self, block_hashes: flatbuffers::WIPOffset<flatbuffers::Vector<'b , H256>>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(BlocksNotFound::VT_BLOCK_HASHES, block_hashes);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> BlocksNotFoundBuilder<'a, 'b> {
+    let start = _fbb.start_table();
+    BlocksNotFoundBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<BlocksNotFound<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
 pub enum HeadersOffset {}
 #[derive(Copy, Clone, Debug, PartialEq)]
 
@@ -2103,6 +2204,46 @@ impl<'a> RelayMessage<'a> {
     }
   }
 
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn payload_as_relay_transaction_hashes(&'a self) -> Option<RelayTransactionHashes> {
+    if self.payload_type() == RelayPayload::RelayTransactionHashes {
+      self.payload().map(|u| RelayTransactionHashes::init_from_table(u))
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn payload_as_get_relay_transactions(&'a self) -> Option<GetRelayTransactions> {
+    if self.payload_type() == RelayPayload::GetRelayTransactions {
+      self.payload().map(|u| GetRelayTransactions::init_from_table(u))
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn payload_as_send_compact_block(&'a self) -> Option<SendCompactBlock> {
+    if self.payload_type() == RelayPayload::SendCompactBlock {
+      self.payload().map(|u| SendCompactBlock::init_from_table(u))
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn payload_as_relay_transactions(&'a self) -> Option<RelayTransactions> {
+    if self.payload_type() == RelayPayload::RelayTransactions {
+      self.payload().map(|u| RelayTransactions::init_from_table(u))
+    } else {
+      None
+    }
+  }
+
 }
 
 pub struct RelayMessageArgs {
@@ -2798,6 +2939,310 @@ impl<'a: 'b, 'b> BlockProposalBuilder<'a, 'b> {
   }
 }
 
+pub enum RelayTransactionHashesOffset {}
+#[derive(Copy, Clone, Debug, PartialEq)]
+
+pub struct RelayTransactionHashes<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for RelayTransactionHashes<'a> {
+    type Inner = RelayTransactionHashes<'a>;
+    #[inline]
+    fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+        Self {
+            _tab: flatbuffers::Table { buf: buf, loc: loc },
+        }
+    }
+}
+
+impl<'a> RelayTransactionHashes<'a> {
+    #[inline]
+    pub fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+        RelayTransactionHashes {
+            _tab: table,
+        }
+    }
+    #[allow(unused_mut)]
+    pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr>(
+        _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr>,
+        args: &'args RelayTransactionHashesArgs<'args>) -> flatbuffers::WIPOffset<RelayTransactionHashes<'bldr>> {
+      let mut builder = RelayTransactionHashesBuilder::new(_fbb);
+      if let Some(x) = args.tx_hashes { builder.add_tx_hashes(x); }
+      builder.finish()
+    }
+
+    pub const VT_TX_HASHES: flatbuffers::VOffsetT = 4;
+
+  #[inline]
+  pub fn tx_hashes(&self) -> Option<&'a [H256]> {
+    self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<H256>>>(RelayTransactionHashes::VT_TX_HASHES, None).map(|v| v.safe_slice() )
+  }
+}
+
+pub struct RelayTransactionHashesArgs<'a> {
+    pub tx_hashes: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a , H256>>>,
+}
+impl<'a> Default for RelayTransactionHashesArgs<'a> {
+    #[inline]
+    fn default() -> Self {
+        RelayTransactionHashesArgs {
+            tx_hashes: None,
+        }
+    }
+}
+pub struct RelayTransactionHashesBuilder<'a: 'b, 'b> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b> RelayTransactionHashesBuilder<'a, 'b> {
+  #[inline]
+  pub fn add_tx_hashes(&mut self, tx_hashes: flatbuffers::WIPOffset<flatbuffers::Vector<'b , H256>>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(RelayTransactionHashes::VT_TX_HASHES, tx_hashes);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> RelayTransactionHashesBuilder<'a, 'b> {
+    let start = _fbb.start_table();
+    RelayTransactionHashesBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<RelayTransactionHashes<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+pub enum GetRelayTransactionsOffset {}
+#[derive(Copy, Clone, Debug, PartialEq)]
+
+pub struct GetRelayTransactions<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for GetRelayTransactions<'a> {
+    type Inner = GetRelayTransactions<'a>;
+    #[inline]
+    fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+        Self {
+            _tab: flatbuffers::Table { buf: buf, loc: loc },
+        }
+    }
+}
+
+impl<'a> GetRelayTransactions<'a> {
+    #[inline]
+    pub fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+        GetRelayTransactions {
+            _tab: table,
+        }
+    }
+    #[allow(unused_mut)]
+    pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr>(
+        _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr>,
+        args: &'args GetRelayTransactionsArgs<'args>) -> flatbuffers::WIPOffset<GetRelayTransactions<'bldr>> {
+      let mut builder = GetRelayTransactionsBuilder::new(_fbb);
+      if let Some(x) = args.tx_hashes { builder.add_tx_hashes(x); }
+      builder.finish()
+    }
+
+    pub const VT_TX_HASHES: flatbuffers::VOffsetT = 4;
+
+  #[inline]
+  pub fn tx_hashes(&self) -> Option<&'a [H256]> {
+    self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<H256>>>(GetRelayTransactions::VT_TX_HASHES, None).map(|v| v.safe_slice() )
+  }
+}
+
+pub struct GetRelayTransactionsArgs<'a> {
+    pub tx_hashes: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a , H256>>>,
+}
+impl<'a> Default for GetRelayTransactionsArgs<'a> {
+    #[inline]
+    fn default() -> Self {
+        GetRelayTransactionsArgs {
+            tx_hashes: None,
+        }
+    }
+}
+pub struct GetRelayTransactionsBuilder<'a: 'b, 'b> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b> GetRelayTransactionsBuilder<'a, 'b> {
+  #[inline]
+  pub fn add_tx_hashes(&mut self, tx_hashes: flatbuffers::WIPOffset<flatbuffers::Vector<'b , H256>>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(GetRelayTransactions::VT_TX_HASHES, tx_hashes);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> GetRelayTransactionsBuilder<'a, 'b> {
+    let start = _fbb.start_table();
+    GetRelayTransactionsBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<GetRelayTransactions<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+pub enum SendCompactBlockOffset {}
+#[derive(Copy, Clone, Debug, PartialEq)]
+
+pub struct SendCompactBlock<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for SendCompactBlock<'a> {
+    type Inner = SendCompactBlock<'a>;
+    #[inline]
+    fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+        Self {
+            _tab: flatbuffers::Table { buf: buf, loc: loc },
+        }
+    }
+}
+
+impl<'a> SendCompactBlock<'a> {
+    #[inline]
+    pub fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+        SendCompactBlock {
+            _tab: table,
+        }
+    }
+    #[allow(unused_mut)]
+    pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr>(
+        _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr>,
+        args: &'args SendCompactBlockArgs) -> flatbuffers::WIPOffset<SendCompactBlock<'bldr>> {
+      let mut builder = SendCompactBlockBuilder::new(_fbb);
+      builder.add_high_bandwidth(args.high_bandwidth);
+      builder.finish()
+    }
+
+    pub const VT_HIGH_BANDWIDTH: flatbuffers::VOffsetT = 4;
+
+  #[inline]
+  pub fn high_bandwidth(&self) -> bool {
+    self._tab.get::<bool>(SendCompactBlock::VT_HIGH_BANDWIDTH, Some(false)).unwrap()
+  }
+}
+
+pub struct SendCompactBlockArgs {
+    pub high_bandwidth: bool,
+}
+impl<'a> Default for SendCompactBlockArgs {
+    #[inline]
+    fn default() -> Self {
+        SendCompactBlockArgs {
+            high_bandwidth: false,
+        }
+    }
+}
+pub struct SendCompactBlockBuilder<'a: 'b, 'b> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b> SendCompactBlockBuilder<'a, 'b> {
+  #[inline]
+  pub fn add_high_bandwidth(&mut self, high_bandwidth: bool) {
+    self.fbb_.push_slot::<bool>(SendCompactBlock::VT_HIGH_BANDWIDTH, high_bandwidth, false);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> SendCompactBlockBuilder<'a, 'b> {
+    let start = _fbb.start_table();
+    SendCompactBlockBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<SendCompactBlock<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+pub enum RelayTransactionsOffset {}
+#[derive(Copy, Clone, Debug, PartialEq)]
+
+pub struct RelayTransactions<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for RelayTransactions<'a> {
+    type Inner = RelayTransactions<'a>;
+    #[inline]
+    fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+        Self {
+            _tab: flatbuffers::Table { buf: buf, loc: loc },
+        }
+    }
+}
+
+impl<'a> RelayTransactions<'a> {
+    #[inline]
+    pub fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+        RelayTransactions {
+            _tab: table,
+        }
+    }
+    #[allow(unused_mut)]
+    pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr>(
+        _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr>,
+        args: &'args RelayTransactionsArgs<'args>) -> flatbuffers::WIPOffset<RelayTransactions<'bldr>> {
+      let mut builder = RelayTransactionsBuilder::new(_fbb);
+      if let Some(x) = args.transactions { builder.add_transactions(x); }
+      builder.finish()
+    }
+
+    pub const VT_TRANSACTIONS: flatbuffers::VOffsetT = 4;
+
+  #[inline]
+  pub fn transactions(&self) -> Option<flatbuffers::Vector<flatbuffers::ForwardsUOffset<RelayTransaction<'a>>>> {
+    self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<flatbuffers::ForwardsUOffset<RelayTransaction<'a>>>>>(RelayTransactions::VT_TRANSACTIONS, None)
+  }
+}
+
+pub struct RelayTransactionsArgs<'a> {
+    pub transactions: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a , flatbuffers::ForwardsUOffset<RelayTransaction<'a >>>>>,
+}
+impl<'a> Default for RelayTransactionsArgs<'a> {
+    #[inline]
+    fn default() -> Self {
+        RelayTransactionsArgs {
+            transactions: None,
+        }
+    }
+}
+pub struct RelayTransactionsBuilder<'a: 'b, 'b> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b> RelayTransactionsBuilder<'a, 'b> {
+  #[inline]
+  pub fn add_transactions(&mut self, transactions: flatbuffers::WIPOffset<flatbuffers::Vector<'b , flatbuffers::ForwardsUOffset<RelayTransaction<'b >>>>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(RelayTransactions::VT_TRANSACTIONS, transactions);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> RelayTransactionsBuilder<'a, 'b> {
+    let start = _fbb.start_table();
+    RelayTransactionsBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<RelayTransactions<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
 pub enum SetFilterOffset {}
 #[derive(Copy, Clone, Debug, PartialEq)]
 
@@ -0,0 +1,173 @@
+//! Access control of the RPC modules.
+//!
+//! A client authenticates with an HTTP `Authorization` header, either `Bearer <token>` or
+//! `Basic <base64 of username:password>`. The header is matched against the configured
+//! credentials, a request without the header is anonymous and can only call the public modules.
+//! When no credentials are configured, the access control is disabled.
+
+use crate::config::{Config, Module};
+use crate::error::RPCError;
+use crate::middleware::{methods, RpcMeta};
+use jsonrpc_core::{Error, Request};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug)]
+pub(crate) struct AccessControl {
+    enable: bool,
+    public_modules: Vec<Module>,
+    // the expected `Authorization` header of every credential
+    credentials: HashMap<String, Vec<Module>>,
+    methods: HashMap<String, Module>,
+}
+
+impl AccessControl {
+    /// `methods` maps the served method names to their modules
+    pub(crate) fn new(config: &Config, methods: HashMap<String, Module>) -> Self {
+        let mut credentials = HashMap::new();
+        for credential in &config.credentials {
+            if let Some(ref token) = credential.token {
+                credentials.insert(format!("Bearer {}", token), credential.modules.clone());
+            }
+            if let (Some(ref username), Some(ref password)) =
+                (&credential.username, &credential.password)
+            {
+                let encoded = base64::encode(&format!("{}:{}", username, password));
+                credentials.insert(format!("Basic {}", encoded), credential.modules.clone());
+            }
+        }
+        AccessControl {
+            enable: config.auth_enable(),
+            public_modules: config.public_modules.clone(),
+            credentials,
+            methods,
+        }
+    }
+
+    /// Whether the `Authorization` header matches a configured credential
+    pub(crate) fn is_credential(&self, authorization: &str) -> bool {
+        self.enable && self.credentials.contains_key(authorization)
+    }
+
+    /// Whether the method is served
+    pub(crate) fn is_served(&self, method: &str) -> bool {
+        self.methods.contains_key(method)
+    }
+
+    pub(crate) fn check(&self, request: &Request, meta: &RpcMeta) -> Result<(), Error> {
+        if !self.enable {
+            return Ok(());
+        }
+        let modules = match meta.authorization {
+            Some(ref authorization) => {
+                Some(self.credentials.get(authorization).ok_or_else(|| {
+                    RPCError::custom(RPCError::Unauthorized, "invalid credential".to_owned())
+                })?)
+            }
+            None => None,
+        };
+        for method in methods(request) {
+            // unknown methods are left to the handler
+            if let Some(module) = self.methods.get(method) {
+                let allowed = self.public_modules.contains(module)
+                    || modules.map_or(false, |modules| modules.contains(module));
+                if !allowed {
+                    return Err(RPCError::custom(
+                        RPCError::Unauthorized,
+                        format!("method {} is not allowed", method),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Credential;
+    use jsonrpc_core::{Call, Id, MethodCall, Params, Version};
+
+    fn config() -> Config {
+        Config {
+            listen_address: "127.0.0.1:8114".to_owned(),
+            threads: None,
+            modules: vec![Module::Chain, Module::Miner],
+            max_request_body_size: 1024,
+            admin_listen_address: None,
+            admin_modules: Vec::new(),
+            public_modules: vec![Module::Chain],
+            credentials: vec![Credential {
+                name: "miner".to_owned(),
+                token: Some("secret".to_owned()),
+                username: Some("miner".to_owned()),
+                password: Some("password".to_owned()),
+                modules: vec![Module::Miner],
+            }],
+            max_batch_size: None,
+            max_calls_per_second: None,
+            client_ip_header: None,
+            trusted_proxies: Vec::new(),
+            default_timeout: None,
+            method_timeouts: HashMap::new(),
+        }
+    }
+
+    fn access_control(config: &Config) -> AccessControl {
+        let mut methods = HashMap::new();
+        methods.insert("get_tip_header".to_owned(), Module::Chain);
+        methods.insert("submit_block".to_owned(), Module::Miner);
+        AccessControl::new(config, methods)
+    }
+
+    fn request(method: &str) -> Request {
+        Request::Single(Call::MethodCall(MethodCall {
+            jsonrpc: Some(Version::V2),
+            method: method.to_owned(),
+            params: Params::None,
+            id: Id::Num(1),
+        }))
+    }
+
+    fn meta(authorization: Option<&str>) -> RpcMeta {
+        RpcMeta {
+            authorization: authorization.map(ToOwned::to_owned),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn anonymous_calls_public_modules() {
+        let access = access_control(&config());
+        assert!(access
+            .check(&request("get_tip_header"), &meta(None))
+            .is_ok());
+        assert!(access.check(&request("submit_block"), &meta(None)).is_err());
+        assert!(access.check(&request("unknown"), &meta(None)).is_ok());
+    }
+
+    #[test]
+    fn credential_calls_its_modules() {
+        let access = access_control(&config());
+        let bearer = meta(Some("Bearer secret"));
+        assert!(access.check(&request("submit_block"), &bearer).is_ok());
+        assert!(access.check(&request("get_tip_header"), &bearer).is_ok());
+        // base64 of "miner:password"
+        let basic = meta(Some("Basic bWluZXI6cGFzc3dvcmQ="));
+        assert!(access.check(&request("submit_block"), &basic).is_ok());
+        let invalid = meta(Some("Bearer wrong"));
+        assert!(access.check(&request("get_tip_header"), &invalid).is_err());
+
+        assert!(access.is_credential("Bearer secret"));
+        assert!(!access.is_credential("Bearer wrong"));
+    }
+
+    #[test]
+    fn disabled_without_credentials() {
+        let mut config = config();
+        config.credentials.clear();
+        let access = access_control(&config);
+        assert!(access.check(&request("submit_block"), &meta(None)).is_ok());
+        assert!(!access.is_credential("Bearer secret"));
+    }
+}
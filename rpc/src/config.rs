@@ -1,12 +1,17 @@
 use serde_derive::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
 
-#[derive(Clone, Debug, Copy, Eq, PartialEq, Deserialize)]
+#[derive(Clone, Debug, Copy, Eq, PartialEq, Hash, Deserialize)]
 pub enum Module {
     Net,
     Chain,
     Miner,
     Pool,
+    /// Removes transactions from the pool
+    PoolAdmin,
     Trace,
+    Stats,
     IntegrationTest,
 }
 
@@ -16,30 +21,58 @@ pub struct Config {
     pub threads: Option<usize>,
     pub modules: Vec<Module>,
     pub max_request_body_size: usize,
+    /// A second server for the modules which control the node, usually bound to localhost
+    pub admin_listen_address: Option<String>,
+    #[serde(default)]
+    pub admin_modules: Vec<Module>,
+    /// The modules callable without a credential. Ignored when no credentials are configured,
+    /// then every enabled module is public.
+    #[serde(default)]
+    pub public_modules: Vec<Module>,
+    #[serde(default)]
+    pub credentials: Vec<Credential>,
+    /// The max number of calls in a batch request
+    pub max_batch_size: Option<usize>,
+    /// The max number of calls per second of a client, a client is identified by its valid
+    /// credential, then by the IP address in `client_ip_header`.
+    pub max_calls_per_second: Option<u32>,
+    /// The header which carries the client IP address set by a reverse proxy, such as
+    /// "X-Forwarded-For" or "X-Real-IP". Only set it when `listen_address` is reachable through
+    /// the proxy alone, the header of a direct client can't be trusted.
+    pub client_ip_header: Option<String>,
+    /// The addresses of the proxies chained in front of the one reaching the node, their
+    /// entries appended to `client_ip_header` are skipped
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
+    /// Method call timeouts in milliseconds
+    pub default_timeout: Option<u64>,
+    #[serde(default)]
+    pub method_timeouts: HashMap<String, u64>,
 }
 
-impl Config {
-    pub(crate) fn net_enable(&self) -> bool {
-        self.modules.contains(&Module::Net)
-    }
-
-    pub(crate) fn chain_enable(&self) -> bool {
-        self.modules.contains(&Module::Chain)
-    }
-
-    pub(crate) fn miner_enable(&self) -> bool {
-        self.modules.contains(&Module::Miner)
-    }
-
-    pub(crate) fn pool_enable(&self) -> bool {
-        self.modules.contains(&Module::Pool)
-    }
+/// A client identified by an HTTP basic or bearer token `Authorization` header
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Credential {
+    pub name: String,
+    /// Bearer token
+    pub token: Option<String>,
+    /// Basic auth user name and password
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// The modules this credential can call besides the public ones
+    pub modules: Vec<Module>,
+}
 
-    pub(crate) fn trace_enable(&self) -> bool {
-        self.modules.contains(&Module::Trace)
+impl Config {
+    pub(crate) fn auth_enable(&self) -> bool {
+        !self.credentials.is_empty()
     }
 
-    pub(crate) fn integration_test_enable(&self) -> bool {
-        self.modules.contains(&Module::IntegrationTest)
+    pub(crate) fn method_timeout(&self, method: &str) -> Option<Duration> {
+        self.method_timeouts
+            .get(method)
+            .cloned()
+            .or(self.default_timeout)
+            .map(Duration::from_millis)
     }
 }
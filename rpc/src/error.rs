@@ -1,16 +1,236 @@
+use ckb_script::{ScriptError, ScriptSource, TransactionScriptError};
+use ckb_shared::tx_pool::PoolError;
+use ckb_verification::TransactionError;
 use jsonrpc_core::{Error, ErrorCode};
+use numext_fixed_hash::H256;
+use serde_json::{json, Value};
+use std::fmt;
 
+/// The server error codes, see the "Errors" section in rpc/README.md
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum RPCError {
     Invalid = -3,
+    Pruned = -4,
+    Unauthorized = -5,
+    Timeout = -6,
+    RateLimited = -7,
+    BatchTooLarge = -8,
+    Reorganized = -9,
+
+    // PoolError, `InvalidTx` uses the code of the wrapped TransactionError
+    PoolAlreadyInPool = -1001,
+    PoolConflict = -1002,
+    PoolOverCapacity = -1003,
+    PoolDuplicateOutput = -1004,
+    PoolCellbase = -1005,
+    PoolTimeOut = -1006,
+    PoolInvalidBlockNumber = -1007,
+    PoolDuplicate = -1008,
+
+    // TransactionError, `ScriptFailure` uses the code of the wrapped ScriptError
+    TransactionNullInput = -1101,
+    TransactionNullDep = -1102,
+    TransactionCapacityOverflow = -1103,
+    TransactionDuplicateInputs = -1104,
+    TransactionEmpty = -1105,
+    TransactionOutputsSumOverflow = -1106,
+    TransactionInvalidScript = -1107,
+    TransactionInvalidSignature = -1108,
+    TransactionConflict = -1109,
+    TransactionUnknown = -1110,
+    TransactionVersion = -1111,
+    TransactionImmature = -1112,
+    TransactionInvalidValidSince = -1113,
+    TransactionCellbaseImmaturity = -1114,
+
+    // ScriptError
+    ScriptNoScript = -1201,
+    ScriptInvalidReferenceIndex = -1202,
+    ScriptArgumentError = -1203,
+    ScriptValidationFailure = -1204,
+    ScriptVMError = -1205,
+    ScriptExceededMaximumCycles = -1206,
 }
 
 impl RPCError {
     pub fn custom(err: RPCError, message: String) -> Error {
+        Self::with_data(err, message, None)
+    }
+
+    fn with_data(err: RPCError, message: String, data: Option<Value>) -> Error {
         Error {
             code: ErrorCode::ServerError(err as i64),
             message,
-            data: None,
+            data,
         }
     }
+
+    /// A parameter which is well-formed JSON but not a valid value
+    pub fn invalid_params<E: fmt::Display>(param: &str, err: E) -> Error {
+        Error::invalid_params(format!("invalid {}: {}", param, err))
+    }
+
+    pub fn pruned(block_hash: &H256) -> Error {
+        Self::custom(
+            RPCError::Pruned,
+            format!("block {:#x} has been pruned", block_hash),
+        )
+    }
+
+    pub fn from_pool_error(err: PoolError) -> Error {
+        let (code, message) = match err {
+            PoolError::InvalidTx(err) => return Self::from_transaction_error(err),
+            PoolError::AlreadyInPool => (
+                RPCError::PoolAlreadyInPool,
+                "transaction is already in the pool",
+            ),
+            PoolError::Conflict => (
+                RPCError::PoolConflict,
+                "transaction conflicts with a transaction in the pool",
+            ),
+            PoolError::OverCapacity => (RPCError::PoolOverCapacity, "transaction pool is full"),
+            PoolError::DuplicateOutput => (
+                RPCError::PoolDuplicateOutput,
+                "transaction creates an output which already exists in the pool",
+            ),
+            PoolError::Cellbase => (
+                RPCError::PoolCellbase,
+                "cellbase transaction is not accepted by the pool",
+            ),
+            PoolError::TimeOut => (RPCError::PoolTimeOut, "transaction pool timed out"),
+            PoolError::InvalidBlockNumber => (
+                RPCError::PoolInvalidBlockNumber,
+                "transaction has an invalid block number",
+            ),
+            PoolError::Duplicate => (RPCError::PoolDuplicate, "transaction is a duplicate"),
+        };
+        Self::custom(code, message.to_owned())
+    }
+
+    pub fn from_transaction_error(err: TransactionError) -> Error {
+        let (code, message) = match err {
+            TransactionError::ScriptFailure(err) => return Self::from_script_error(err),
+            TransactionError::NullInput => (
+                RPCError::TransactionNullInput,
+                "transaction spends a null out point",
+            ),
+            TransactionError::NullDep => (
+                RPCError::TransactionNullDep,
+                "transaction depends on a null out point",
+            ),
+            TransactionError::CapacityOverflow => (
+                RPCError::TransactionCapacityOverflow,
+                "output occupied capacity exceeds its capacity",
+            ),
+            TransactionError::DuplicateInputs => (
+                RPCError::TransactionDuplicateInputs,
+                "transaction has duplicate inputs",
+            ),
+            TransactionError::Empty => (
+                RPCError::TransactionEmpty,
+                "transaction has no inputs or no outputs",
+            ),
+            TransactionError::OutputsSumOverflow => (
+                RPCError::TransactionOutputsSumOverflow,
+                "sum of outputs capacity exceeds sum of inputs capacity",
+            ),
+            TransactionError::InvalidScript => (
+                RPCError::TransactionInvalidScript,
+                "transaction has an invalid script",
+            ),
+            TransactionError::InvalidSignature => (
+                RPCError::TransactionInvalidSignature,
+                "transaction has an invalid signature",
+            ),
+            TransactionError::Conflict => (
+                RPCError::TransactionConflict,
+                "transaction spends a dead cell",
+            ),
+            TransactionError::Unknown => (
+                RPCError::TransactionUnknown,
+                "transaction references an unknown cell",
+            ),
+            TransactionError::Version => (
+                RPCError::TransactionVersion,
+                "transaction version is not supported",
+            ),
+            TransactionError::Immature => (
+                RPCError::TransactionImmature,
+                "transaction does not satisfy the valid since condition yet",
+            ),
+            TransactionError::InvalidValidSince => (
+                RPCError::TransactionInvalidValidSince,
+                "transaction has invalid valid since flags",
+            ),
+            TransactionError::CellbaseImmaturity => (
+                RPCError::TransactionCellbaseImmaturity,
+                "transaction spends an immature cellbase output",
+            ),
+        };
+        Self::custom(code, message.to_owned())
+    }
+
+    pub fn from_script_error(err: TransactionScriptError) -> Error {
+        let mut data = match err.source {
+            ScriptSource::Input(index) => json!({ "input_index": index }),
+            ScriptSource::Output(index) => json!({ "output_index": index }),
+        };
+        // cycles are u64, they are passed as strings like the other RPC numbers
+        data["cycles"] = json!(err.cycles.to_string());
+        let (code, message) = match err.error {
+            ScriptError::NoScript => (RPCError::ScriptNoScript, "script is not found"),
+            ScriptError::InvalidReferenceIndex => (
+                RPCError::ScriptInvalidReferenceIndex,
+                "script references an invalid dep index",
+            ),
+            ScriptError::ArgumentError => (
+                RPCError::ScriptArgumentError,
+                "script arguments are invalid",
+            ),
+            ScriptError::ValidationFailure(exit_code) => {
+                data["exit_code"] = json!(exit_code);
+                (
+                    RPCError::ScriptValidationFailure,
+                    "script returns a non-zero exit code",
+                )
+            }
+            ScriptError::VMError(vm_error) => {
+                data["vm_error"] = json!(format!("{:?}", vm_error));
+                (RPCError::ScriptVMError, "script is aborted by the VM")
+            }
+            ScriptError::ExceededMaximumCycles => (
+                RPCError::ScriptExceededMaximumCycles,
+                "scripts exceed the maximum cycles",
+            ),
+        };
+        Self::with_data(code, message.to_owned(), Some(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_error_data() {
+        let err = RPCError::from_pool_error(PoolError::InvalidTx(TransactionError::ScriptFailure(
+            TransactionScriptError {
+                source: ScriptSource::Input(2),
+                error: ScriptError::ValidationFailure(5),
+                cycles: 100,
+            },
+        )));
+        assert_eq!(err.code, ErrorCode::ServerError(-1204));
+        assert_eq!(
+            err.data,
+            Some(json!({ "input_index": 2, "exit_code": 5, "cycles": "100" }))
+        );
+    }
+
+    #[test]
+    fn transaction_error_code() {
+        let err = RPCError::from_transaction_error(TransactionError::Immature);
+        assert_eq!(err.code, ErrorCode::ServerError(-1112));
+        assert_eq!(err.data, None);
+    }
 }
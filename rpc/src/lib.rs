@@ -1,7 +1,11 @@
+mod auth;
 mod config;
 mod error;
+mod metrics;
+mod middleware;
 mod module;
 mod server;
 
-pub use crate::config::Config;
+pub use crate::config::{Config, Credential, Module};
+pub use crate::metrics::{MethodMetrics, RpcMetrics, LATENCY_BUCKETS};
 pub use crate::server::RpcServer;
@@ -0,0 +1,103 @@
+use ckb_util::Mutex;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const BOUNDS: usize = 8;
+
+/// The upper bounds of the latency histogram buckets in milliseconds, the last bucket has no
+/// upper bound.
+pub const LATENCY_BUCKETS: [u64; BOUNDS] = [1, 5, 10, 50, 100, 500, 1_000, 5_000];
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MethodMetrics {
+    pub calls: u64,
+    /// Calls which took longer than the method timeout
+    pub timeouts: u64,
+    /// The number of calls in every latency bucket, not cumulative
+    pub latency_buckets: [u64; BOUNDS + 1],
+    pub latency_sum: Duration,
+}
+
+impl MethodMetrics {
+    fn record(&mut self, latency: Duration, timeout: bool) {
+        let millis = latency.as_secs() * 1_000 + u64::from(latency.subsec_millis());
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|bound| millis <= *bound)
+            .unwrap_or(BOUNDS);
+        self.calls += 1;
+        self.latency_buckets[bucket] += 1;
+        self.latency_sum += latency;
+        if timeout {
+            self.timeouts += 1;
+        }
+    }
+}
+
+/// The calls served by the RPC servers
+#[derive(Debug, Default)]
+pub struct RpcMetrics {
+    methods: Mutex<HashMap<String, MethodMetrics>>,
+    rate_limited: Mutex<u64>,
+    batch_rejected: Mutex<u64>,
+}
+
+impl RpcMetrics {
+    pub(crate) fn record_call(&self, method: &str, latency: Duration, timeout: bool) {
+        self.methods
+            .lock()
+            .entry(method.to_owned())
+            .or_insert_with(Default::default)
+            .record(latency, timeout);
+    }
+
+    pub(crate) fn record_rate_limited(&self) {
+        *self.rate_limited.lock() += 1;
+    }
+
+    pub(crate) fn record_batch_rejected(&self) {
+        *self.batch_rejected.lock() += 1;
+    }
+
+    pub fn methods(&self) -> Vec<(String, MethodMetrics)> {
+        let mut methods: Vec<_> = self
+            .methods
+            .lock()
+            .iter()
+            .map(|(method, metrics)| (method.clone(), metrics.clone()))
+            .collect();
+        methods.sort_by(|a, b| a.0.cmp(&b.0));
+        methods
+    }
+
+    /// Requests rejected because the client exceeded the rate limit
+    pub fn rate_limited(&self) -> u64 {
+        *self.rate_limited.lock()
+    }
+
+    /// Batches rejected because they exceeded the max batch size
+    pub fn batch_rejected(&self) -> u64 {
+        *self.batch_rejected.lock()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_latency_buckets() {
+        let metrics = RpcMetrics::default();
+        metrics.record_call("get_tip_header", Duration::from_micros(500), false);
+        metrics.record_call("get_tip_header", Duration::from_millis(7), false);
+        metrics.record_call("get_tip_header", Duration::from_secs(10), true);
+
+        let methods = metrics.methods();
+        assert_eq!(methods.len(), 1);
+        let (ref method, ref metrics) = methods[0];
+        assert_eq!(method, "get_tip_header");
+        assert_eq!(metrics.calls, 3);
+        assert_eq!(metrics.timeouts, 1);
+        assert_eq!(metrics.latency_buckets, [1, 0, 1, 0, 0, 0, 0, 0, 1]);
+    }
+}
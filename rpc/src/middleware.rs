@@ -0,0 +1,294 @@
+//! The middleware in front of every RPC call: access control, batch size and rate limits,
+//! timeouts and metrics.
+//!
+//! The methods are synchronous and run to the end once they are called. A method which may run
+//! for long, such as a scan over a block range, checks the deadline in its `RpcMeta` and returns
+//! the timeout error when it is passed. Any other call longer than its timeout is logged and
+//! counted in the metrics.
+
+use crate::auth::AccessControl;
+use crate::config::Config;
+use crate::error::RPCError;
+use crate::metrics::RpcMetrics;
+use ckb_util::Mutex;
+use futures::future::{self, Either};
+use futures::Future;
+use jsonrpc_core::{Call, Error, Metadata, Middleware, Output, Request, Response, Version};
+use jsonrpc_http_server::hyper::{header::AUTHORIZATION, Body, Request as HttpRequest};
+use log::warn;
+use lru_cache::LruCache;
+use std::slice;
+use std::sync::Arc;
+use std::time::Instant;
+
+// The number of clients tracked by the rate limiter
+const RATE_LIMIT_CLIENTS: usize = 4096;
+// The calls of the methods not served are recorded under this name, so a client can not grow the
+// metrics by calling random names
+const UNKNOWN_METHOD: &str = "unknown";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RpcMeta {
+    pub(crate) authorization: Option<String>,
+    pub(crate) client_ip: Option<String>,
+    /// Set by the middleware from the method timeouts
+    pub(crate) deadline: Option<Instant>,
+}
+
+impl Metadata for RpcMeta {}
+
+impl RpcMeta {
+    pub(crate) fn from_http(request: &HttpRequest<Body>, config: &Config) -> Self {
+        let header = |name: &str| {
+            request
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.trim().to_owned())
+        };
+        RpcMeta {
+            authorization: header(AUTHORIZATION.as_str()),
+            client_ip: config
+                .client_ip_header
+                .as_ref()
+                .and_then(|name| header(name))
+                .and_then(|ips| client_ip(&ips, &config.trusted_proxies)),
+            deadline: None,
+        }
+    }
+
+    /// Returns the timeout error when the deadline of the call is passed
+    pub(crate) fn check_deadline(&self) -> Result<(), Error> {
+        match self.deadline {
+            Some(deadline) if Instant::now() > deadline => Err(RPCError::custom(
+                RPCError::Timeout,
+                "call timed out".to_owned(),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+// X-Forwarded-For is a list which every proxy appends the address it received the request from
+// to. The entries before the trusted proxies may be forged by the client, so the last entry not
+// from a trusted proxy is the client.
+fn client_ip(ips: &str, trusted_proxies: &[String]) -> Option<String> {
+    let ips: Vec<&str> = ips
+        .split(',')
+        .map(str::trim)
+        .filter(|ip| !ip.is_empty())
+        .collect();
+    ips.iter()
+        .rev()
+        .find(|ip| !trusted_proxies.iter().any(|proxy| proxy.as_str() == **ip))
+        .or_else(|| ips.first())
+        .map(|ip| (*ip).to_owned())
+}
+
+/// The names of the methods called in a request
+pub(crate) fn methods(request: &Request) -> impl Iterator<Item = &str> {
+    let calls = match request {
+        Request::Single(call) => slice::from_ref(call),
+        Request::Batch(calls) => calls.as_slice(),
+    };
+    calls.iter().filter_map(|call| match call {
+        Call::MethodCall(call) => Some(call.method.as_str()),
+        Call::Notification(notification) => Some(notification.method.as_str()),
+        Call::Invalid { .. } => None,
+    })
+}
+
+// Every client has a token bucket which is refilled at `max_calls_per_second`, and holds at most
+// one second of calls.
+struct RateLimiter {
+    max_calls_per_second: u32,
+    buckets: Mutex<LruCache<String, (f64, Instant)>>,
+}
+
+impl RateLimiter {
+    fn new(max_calls_per_second: u32) -> Self {
+        RateLimiter {
+            max_calls_per_second,
+            buckets: Mutex::new(LruCache::new(RATE_LIMIT_CLIENTS)),
+        }
+    }
+
+    fn acquire(&self, client: String, calls: usize) -> bool {
+        let max = f64::from(self.max_calls_per_second);
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock();
+        let (tokens, updated_at) = buckets.get_mut(&client).cloned().unwrap_or((max, now));
+        let elapsed = now.duration_since(updated_at);
+        let elapsed = elapsed.as_secs() as f64 + f64::from(elapsed.subsec_nanos()) * 1e-9;
+        let tokens = (tokens + elapsed * max).min(max);
+        let calls = calls as f64;
+        if tokens >= calls {
+            buckets.insert(client, (tokens - calls, now));
+            true
+        } else {
+            buckets.insert(client, (tokens, now));
+            false
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct RpcMiddleware {
+    config: Arc<Config>,
+    access_control: AccessControl,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    metrics: Arc<RpcMetrics>,
+}
+
+impl RpcMiddleware {
+    pub(crate) fn new(
+        config: Arc<Config>,
+        access_control: AccessControl,
+        metrics: Arc<RpcMetrics>,
+    ) -> Self {
+        let rate_limiter = config
+            .max_calls_per_second
+            .map(|max| Arc::new(RateLimiter::new(max)));
+        RpcMiddleware {
+            config,
+            access_control,
+            rate_limiter,
+            metrics,
+        }
+    }
+
+    // A client is identified by its credential, or by its address when it has no valid one,
+    // otherwise a client could get a new bucket for every forged header
+    fn client(&self, meta: &RpcMeta) -> String {
+        meta.authorization
+            .as_ref()
+            .filter(|authorization| self.access_control.is_credential(authorization))
+            .or_else(|| meta.client_ip.as_ref())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn check(&self, request: &Request, meta: &RpcMeta) -> Result<(), Error> {
+        self.access_control.check(request, meta)?;
+        if let (Request::Batch(calls), Some(max)) = (request, self.config.max_batch_size) {
+            if calls.len() > max {
+                self.metrics.record_batch_rejected();
+                return Err(RPCError::custom(
+                    RPCError::BatchTooLarge,
+                    format!("batch of {} calls exceeds the max {}", calls.len(), max),
+                ));
+            }
+        }
+        if let Some(ref rate_limiter) = self.rate_limiter {
+            if !rate_limiter.acquire(self.client(meta), methods(request).count()) {
+                self.metrics.record_rate_limited();
+                return Err(RPCError::custom(
+                    RPCError::RateLimited,
+                    "too many calls".to_owned(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+// A batch is rejected as a whole when any of its calls is rejected
+fn reject(request: Request, error: Error) -> Option<Response> {
+    let output = |call: Call| match call {
+        Call::MethodCall(call) => Some(Output::from(Err(error.clone()), call.id, call.jsonrpc)),
+        Call::Notification(_) => None,
+        Call::Invalid { id } => Some(Output::from(Err(error.clone()), id, Some(Version::V2))),
+    };
+    match request {
+        Request::Single(call) => output(call).map(Response::Single),
+        Request::Batch(calls) => Some(Response::Batch(
+            calls.into_iter().filter_map(output).collect(),
+        )),
+    }
+}
+
+impl Middleware<RpcMeta> for RpcMiddleware {
+    type Future = Box<dyn Future<Item = Option<Response>, Error = ()> + Send>;
+
+    fn on_request<F, X>(
+        &self,
+        request: Request,
+        mut meta: RpcMeta,
+        next: F,
+    ) -> Either<Self::Future, X>
+    where
+        F: FnOnce(Request, RpcMeta) -> X + Send,
+        X: Future<Item = Option<Response>, Error = ()> + Send + 'static,
+    {
+        if let Err(error) = self.check(&request, &meta) {
+            return Either::A(Box::new(future::ok(reject(request, error))));
+        }
+
+        // the calls in a batch share the longest timeout of them and are recorded with the
+        // latency of the whole batch
+        let methods: Vec<String> = methods(&request)
+            .map(|method| {
+                if self.access_control.is_served(method) {
+                    method.to_owned()
+                } else {
+                    UNKNOWN_METHOD.to_owned()
+                }
+            })
+            .collect();
+        let timeout = methods
+            .iter()
+            .map(|method| self.config.method_timeout(method))
+            .max()
+            .and_then(|timeout| timeout);
+        let start = Instant::now();
+        meta.deadline = timeout.map(|timeout| start + timeout);
+
+        let metrics = Arc::clone(&self.metrics);
+        Either::A(Box::new(next(request, meta).map(move |response| {
+            let latency = start.elapsed();
+            let timed_out = timeout.map_or(false, |timeout| latency > timeout);
+            if timed_out {
+                warn!(target: "rpc", "slow call {:?} took {:?}", methods, latency);
+            }
+            for method in &methods {
+                metrics.record_call(method, latency, timed_out);
+            }
+            response
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn forwarded_client_ip() {
+        let trusted_proxies = vec!["10.0.0.2".to_owned()];
+        assert_eq!(
+            client_ip("1.2.3.4, 5.6.7.8, 10.0.0.2", &trusted_proxies),
+            Some("5.6.7.8".to_owned())
+        );
+        assert_eq!(
+            client_ip("1.2.3.4, 5.6.7.8", &[]),
+            Some("5.6.7.8".to_owned())
+        );
+        assert_eq!(
+            client_ip("10.0.0.2", &trusted_proxies),
+            Some("10.0.0.2".to_owned())
+        );
+        assert_eq!(client_ip(" ", &[]), None);
+    }
+
+    #[test]
+    fn rate_limiter_refills() {
+        let rate_limiter = RateLimiter::new(2);
+        assert!(rate_limiter.acquire("a".to_owned(), 2));
+        assert!(!rate_limiter.acquire("a".to_owned(), 1));
+        assert!(rate_limiter.acquire("b".to_owned(), 1));
+        thread::sleep(Duration::from_millis(600));
+        assert!(rate_limiter.acquire("a".to_owned(), 1));
+    }
+}
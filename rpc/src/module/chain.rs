@@ -1,20 +1,52 @@
+use crate::error::RPCError;
+use crate::middleware::RpcMeta;
 use ckb_core::cell::CellProvider;
 use ckb_core::BlockNumber;
-use ckb_shared::{shared::Shared, store::ChainStore};
+use ckb_shared::store::{ChainStore, IndexedCell as CoreIndexedCell};
+use ckb_shared::{shared::Shared, tx_pool::TxPoolStatus};
 use ckb_traits::ChainProvider;
 use jsonrpc_core::{Error, Result};
 use jsonrpc_derive::rpc;
-use jsonrpc_types::{Block, CellOutputWithOutPoint, CellWithStatus, Header, OutPoint, Transaction};
+use jsonrpc_types::{
+    BlockExt, BlockVerbosity, BlockView, Bytes, CellOutputWithOutPoint, CellWithStatus, Checkpoint,
+    Consensus, Header, IndexedCell, IndexedCellStatus, IndexedCellsPage, OutPoint, ProposalWindow,
+    TransactionWithStatus, TxStatus, UncleBlock,
+};
 use numext_fixed_hash::H256;
 use std::convert::TryInto;
 
 #[rpc]
 pub trait ChainRpc {
+    type Metadata;
+
     #[rpc(name = "get_block")]
-    fn get_block(&self, _hash: H256) -> Result<Option<Block>>;
+    fn get_block(
+        &self,
+        _hash: H256,
+        _verbosity: Option<BlockVerbosity>,
+    ) -> Result<Option<BlockView>>;
+
+    #[rpc(name = "get_block_by_number")]
+    fn get_block_by_number(
+        &self,
+        _number: String,
+        _verbosity: Option<BlockVerbosity>,
+    ) -> Result<Option<BlockView>>;
+
+    #[rpc(name = "get_header")]
+    fn get_header(&self, _hash: H256) -> Result<Option<Header>>;
+
+    #[rpc(name = "get_header_by_number")]
+    fn get_header_by_number(&self, _number: String) -> Result<Option<Header>>;
+
+    #[rpc(name = "get_block_ext")]
+    fn get_block_ext(&self, _hash: H256) -> Result<Option<BlockExt>>;
+
+    #[rpc(name = "get_uncle_block")]
+    fn get_uncle_block(&self, _hash: H256, _index: String) -> Result<Option<UncleBlock>>;
 
     #[rpc(name = "get_transaction")]
-    fn get_transaction(&self, _hash: H256) -> Result<Option<Transaction>>;
+    fn get_transaction(&self, _hash: H256) -> Result<Option<TransactionWithStatus>>;
 
     #[rpc(name = "get_block_hash")]
     fn get_block_hash(&self, _number: String) -> Result<Option<H256>>;
@@ -22,40 +54,229 @@ pub trait ChainRpc {
     #[rpc(name = "get_tip_header")]
     fn get_tip_header(&self) -> Result<Header>;
 
-    #[rpc(name = "get_cells_by_lock_hash")]
+    #[rpc(meta, name = "get_cells_by_lock_hash")]
     fn get_cells_by_lock_hash(
         &self,
+        _meta: Self::Metadata,
         _lock_hash: H256,
         _from: String,
         _to: String,
     ) -> Result<Vec<CellOutputWithOutPoint>>;
 
+    #[rpc(meta, name = "get_cells_by_type_hash")]
+    fn get_cells_by_type_hash(
+        &self,
+        _meta: Self::Metadata,
+        _type_hash: H256,
+        _limit: String,
+        _cursor: Option<Bytes>,
+        _include_dead: Option<bool>,
+    ) -> Result<IndexedCellsPage>;
+
+    #[rpc(meta, name = "get_cells_by_data_hash")]
+    fn get_cells_by_data_hash(
+        &self,
+        _meta: Self::Metadata,
+        _data_hash: H256,
+        _limit: String,
+        _cursor: Option<Bytes>,
+        _include_dead: Option<bool>,
+    ) -> Result<IndexedCellsPage>;
+
     #[rpc(name = "get_live_cell")]
     fn get_live_cell(&self, _out_point: OutPoint) -> Result<CellWithStatus>;
 
     #[rpc(name = "get_tip_block_number")]
     fn get_tip_block_number(&self) -> Result<String>;
+
+    #[rpc(name = "get_consensus")]
+    fn get_consensus(&self) -> Result<Consensus>;
 }
 
 pub(crate) struct ChainRpcImpl<CS> {
     pub shared: Shared<CS>,
 }
 
+// The most cells in a page of the indexed cells
+const MAX_CELLS_PER_PAGE: usize = 100;
+// The most index entries scanned in a call, the dead cells skipped count as well
+const MAX_SCANNED_CELLS: usize = 1000;
+
+fn parse_block_number(param: &str, number: String) -> Result<BlockNumber> {
+    number
+        .parse::<BlockNumber>()
+        .map_err(|err| RPCError::invalid_params(param, err))
+}
+
+impl<CS: ChainStore + 'static> ChainRpcImpl<CS> {
+    // The page of the indexed cells, the dead ones are skipped unless `include_dead` is set. The
+    // page ends early once `MAX_SCANNED_CELLS` are scanned, the cursor continues from there.
+    fn cells_page(
+        &self,
+        meta: &RpcMeta,
+        cells: impl Iterator<Item = CoreIndexedCell>,
+        limit: String,
+        include_dead: Option<bool>,
+    ) -> Result<IndexedCellsPage> {
+        let limit = limit
+            .parse::<usize>()
+            .map_err(|err| RPCError::invalid_params("limit", err))?;
+        if limit == 0 || limit > MAX_CELLS_PER_PAGE {
+            return Err(RPCError::invalid_params(
+                "limit",
+                format!("expects 1 to {}", MAX_CELLS_PER_PAGE),
+            ));
+        }
+        let include_dead = include_dead.unwrap_or(false);
+
+        // the persisted cell set is committed along with the tip, no need to lock the chain state
+        let store = self.shared.store();
+        let mut result = Vec::new();
+        let mut scanned = 0;
+        let mut cursor = None;
+        let mut exhausted = true;
+        for cell in cells {
+            if result.len() >= limit || scanned >= MAX_SCANNED_CELLS {
+                exhausted = false;
+                break;
+            }
+            meta.check_deadline()?;
+            scanned += 1;
+            cursor = Some(cell.cursor());
+            let live = store
+                .get_tx_meta(&cell.out_point.tx_hash)
+                .map_or(false, |tx_meta| {
+                    !tx_meta.is_dead(cell.out_point.index as usize)
+                });
+            if !live && !include_dead {
+                continue;
+            }
+            result.push(IndexedCell {
+                transaction: store
+                    .get_transaction(&cell.out_point.tx_hash)
+                    .map(|transaction| (&transaction).into()),
+                out_point: cell.out_point.into(),
+                capacity: cell.capacity.to_string(),
+                lock: cell.lock.into(),
+                type_: cell.type_.map(Into::into),
+                data_hash: cell.data_hash,
+                block_hash: cell.block_hash,
+                block_number: cell.block_number.to_string(),
+                status: if live {
+                    IndexedCellStatus::Live
+                } else {
+                    IndexedCellStatus::Dead
+                },
+            });
+        }
+        Ok(IndexedCellsPage {
+            cells: result,
+            cursor: if exhausted {
+                None
+            } else {
+                cursor.map(Bytes::new)
+            },
+        })
+    }
+}
+
 impl<CS: ChainStore + 'static> ChainRpc for ChainRpcImpl<CS> {
-    fn get_block(&self, hash: H256) -> Result<Option<Block>> {
-        Ok(self.shared.block(&hash).as_ref().map(Into::into))
+    type Metadata = RpcMeta;
+
+    fn get_block(
+        &self,
+        hash: H256,
+        verbosity: Option<BlockVerbosity>,
+    ) -> Result<Option<BlockView>> {
+        match self.shared.block(&hash) {
+            Some(block) => Ok(Some(BlockView::new(&block, verbosity.unwrap_or_default()))),
+            None if self.shared.store().is_pruned(&hash) => Err(RPCError::pruned(&hash)),
+            None => Ok(None),
+        }
+    }
+
+    fn get_block_by_number(
+        &self,
+        number: String,
+        verbosity: Option<BlockVerbosity>,
+    ) -> Result<Option<BlockView>> {
+        match self
+            .shared
+            .block_hash(parse_block_number("number", number)?)
+        {
+            Some(hash) => self.get_block(hash, verbosity),
+            None => Ok(None),
+        }
+    }
+
+    fn get_header(&self, hash: H256) -> Result<Option<Header>> {
+        Ok(self
+            .shared
+            .block_header(&hash)
+            .map(|header| (&header).into()))
+    }
+
+    fn get_header_by_number(&self, number: String) -> Result<Option<Header>> {
+        Ok(self
+            .shared
+            .block_hash(parse_block_number("number", number)?)
+            .and_then(|hash| self.shared.block_header(&hash))
+            .map(|header| (&header).into()))
+    }
+
+    fn get_block_ext(&self, hash: H256) -> Result<Option<BlockExt>> {
+        Ok(self.shared.block_ext(&hash).map(|ext| (&ext).into()))
     }
 
-    fn get_transaction(&self, hash: H256) -> Result<Option<Transaction>> {
-        Ok(self.shared.get_transaction(&hash).as_ref().map(Into::into))
+    fn get_uncle_block(&self, hash: H256, index: String) -> Result<Option<UncleBlock>> {
+        let index = index
+            .parse::<usize>()
+            .map_err(|err| RPCError::invalid_params("index", err))?;
+        match self.shared.uncles(&hash) {
+            Some(uncles) => Ok(uncles.get(index).map(Into::into)),
+            None if self.shared.store().is_pruned(&hash) => Err(RPCError::pruned(&hash)),
+            None => Ok(None),
+        }
+    }
+
+    fn get_transaction(&self, hash: H256) -> Result<Option<TransactionWithStatus>> {
+        let chain_state = self.shared.chain_state().lock();
+        let store = self.shared.store();
+        if let Some(address) = store.get_transaction_address(&hash) {
+            // the transactions of a pruned block are only kept while they have live cells
+            let transaction = store
+                .get_transaction(&hash)
+                .ok_or_else(|| RPCError::pruned(&address.block_hash))?;
+            let block_number = store
+                .get_block_number(&address.block_hash)
+                .ok_or_else(Error::internal_error)?;
+            return Ok(Some(TransactionWithStatus {
+                transaction: (&transaction).into(),
+                tx_status: TxStatus::committed(
+                    address.block_hash.clone(),
+                    block_number,
+                    store.get_transaction_index(&address),
+                    chain_state.tip_number(),
+                ),
+            }));
+        }
+
+        let tx_pool = chain_state.tx_pool();
+        Ok(tx_pool
+            .get_tx_with_status(&hash)
+            .map(|(transaction, status)| TransactionWithStatus {
+                transaction: transaction.into(),
+                tx_status: match status {
+                    TxPoolStatus::Pending => TxStatus::pending(),
+                    TxPoolStatus::Proposed => TxStatus::proposed(),
+                },
+            }))
     }
 
     fn get_block_hash(&self, number: String) -> Result<Option<H256>> {
-        Ok(self.shared.block_hash(
-            number
-                .parse::<BlockNumber>()
-                .map_err(|_| Error::parse_error())?,
-        ))
+        Ok(self
+            .shared
+            .block_hash(parse_block_number("number", number)?))
     }
 
     fn get_tip_header(&self) -> Result<Header> {
@@ -65,24 +286,37 @@ impl<CS: ChainStore + 'static> ChainRpc for ChainRpcImpl<CS> {
     // TODO: we need to build a proper index instead of scanning every time
     fn get_cells_by_lock_hash(
         &self,
+        meta: RpcMeta,
         lock_hash: H256,
         from: String,
         to: String,
     ) -> Result<Vec<CellOutputWithOutPoint>> {
         let mut result = Vec::new();
-        let chain_state = self.shared.chain_state().lock();
-        let from = from
-            .parse::<BlockNumber>()
-            .map_err(|_| Error::parse_error())?;
-        let to = to
-            .parse::<BlockNumber>()
-            .map_err(|_| Error::parse_error())?;
+        let from = parse_block_number("from", from)?;
+        let to = parse_block_number("to", to)?;
+        // the last scanned block, the scan is abandoned once it leaves the main chain
+        let mut scanned: Option<(BlockNumber, H256)> = None;
         for block_number in from..=to {
+            meta.check_deadline()?;
+            // lock per block, a long scan must not block the other callers
+            let chain_state = self.shared.chain_state().lock();
+            if let Some((number, ref hash)) = scanned {
+                if self.shared.block_hash(number).as_ref() != Some(hash) {
+                    return Err(RPCError::custom(
+                        RPCError::Reorganized,
+                        "the main chain was reorganized during the scan".to_owned(),
+                    ));
+                }
+            }
             if let Some(block_hash) = self.shared.block_hash(block_number) {
-                let block = self
-                    .shared
-                    .block(&block_hash)
-                    .ok_or_else(Error::internal_error)?;
+                scanned = Some((block_number, block_hash.clone()));
+                let block = match self.shared.block(&block_hash) {
+                    Some(block) => block,
+                    None if self.shared.store().is_pruned(&block_hash) => {
+                        return Err(RPCError::pruned(&block_hash));
+                    }
+                    None => return Err(Error::internal_error()),
+                };
                 for transaction in block.transactions() {
                     let transaction_meta = chain_state
                         .cell_set()
@@ -106,16 +340,81 @@ impl<CS: ChainStore + 'static> ChainRpc for ChainRpcImpl<CS> {
         Ok(result)
     }
 
+    fn get_cells_by_type_hash(
+        &self,
+        meta: RpcMeta,
+        type_hash: H256,
+        limit: String,
+        cursor: Option<Bytes>,
+        include_dead: Option<bool>,
+    ) -> Result<IndexedCellsPage> {
+        let store = self.shared.store();
+        let cursor = cursor.map(Bytes::into_vec);
+        let cells = store.get_cells_by_type_hash(&type_hash, cursor.as_ref().map(Vec::as_slice));
+        self.cells_page(&meta, cells, limit, include_dead)
+    }
+
+    fn get_cells_by_data_hash(
+        &self,
+        meta: RpcMeta,
+        data_hash: H256,
+        limit: String,
+        cursor: Option<Bytes>,
+        include_dead: Option<bool>,
+    ) -> Result<IndexedCellsPage> {
+        let store = self.shared.store();
+        let cursor = cursor.map(Bytes::into_vec);
+        let cells = store.get_cells_by_data_hash(&data_hash, cursor.as_ref().map(Vec::as_slice));
+        self.cells_page(&meta, cells, limit, include_dead)
+    }
+
     fn get_live_cell(&self, out_point: OutPoint) -> Result<CellWithStatus> {
         Ok(self
             .shared
             .chain_state()
             .lock()
-            .get_cell_status(&(out_point.try_into().map_err(|_| Error::parse_error())?))
+            .get_cell_status(
+                &(out_point
+                    .try_into()
+                    .map_err(|err| RPCError::invalid_params("out_point", err))?),
+            )
             .into())
     }
 
     fn get_tip_block_number(&self) -> Result<String> {
         Ok(self.shared.chain_state().lock().tip_number().to_string())
     }
+
+    fn get_consensus(&self) -> Result<Consensus> {
+        let consensus = self.shared.consensus();
+        let tx_proposal_window = consensus.tx_proposal_window();
+        Ok(Consensus {
+            id: consensus.id.clone(),
+            genesis_hash: consensus.genesis_block().header().hash(),
+            initial_block_reward: consensus.initial_block_reward().to_string(),
+            max_uncles_age: consensus.max_uncles_age().to_string(),
+            max_uncles_num: consensus.max_uncles_num().to_string(),
+            orphan_rate_target: consensus.orphan_rate_target(),
+            pow_time_span: consensus.pow_time_span.to_string(),
+            pow_spacing: consensus.pow_spacing.to_string(),
+            tx_proposal_window: ProposalWindow {
+                closest: tx_proposal_window.end().to_string(),
+                farthest: tx_proposal_window.start().to_string(),
+            },
+            pow: consensus.pow.to_string(),
+            cellbase_maturity: consensus.cellbase_maturity().to_string(),
+            median_time_block_count: consensus.median_time_block_count().to_string(),
+            max_block_cycles: consensus.max_block_cycles().to_string(),
+            max_block_bytes: consensus.max_block_bytes().to_string(),
+            block_version: consensus.block_version(),
+            checkpoints: consensus
+                .checkpoints()
+                .iter()
+                .map(|checkpoint| Checkpoint {
+                    number: checkpoint.number.to_string(),
+                    hash: checkpoint.hash.clone(),
+                })
+                .collect(),
+        })
+    }
 }
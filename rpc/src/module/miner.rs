@@ -1,3 +1,4 @@
+use crate::error::RPCError;
 use ckb_chain::chain::ChainController;
 use ckb_core::block::Block as CoreBlock;
 use ckb_core::Cycle;
@@ -50,11 +51,17 @@ impl<CS: ChainStore + 'static> MinerRpc for MinerRpcImpl<CS> {
         max_version: Option<u32>,
     ) -> Result<BlockTemplate> {
         let cycles_limit = match cycles_limit {
-            Some(c) => Some(c.parse::<Cycle>().map_err(|_| Error::parse_error())?),
+            Some(c) => Some(
+                c.parse::<Cycle>()
+                    .map_err(|err| RPCError::invalid_params("cycles_limit", err))?,
+            ),
             None => None,
         };
         let bytes_limit = match bytes_limit {
-            Some(b) => Some(b.parse::<u64>().map_err(|_| Error::parse_error())?),
+            Some(b) => Some(
+                b.parse::<u64>()
+                    .map_err(|err| RPCError::invalid_params("bytes_limit", err))?,
+            ),
             None => None,
         };
         self.block_assembler
@@ -63,7 +70,10 @@ impl<CS: ChainStore + 'static> MinerRpc for MinerRpcImpl<CS> {
     }
 
     fn submit_block(&self, _work_id: String, data: Block) -> Result<Option<H256>> {
-        let block: Arc<CoreBlock> = Arc::new(data.try_into().map_err(|_| Error::parse_error())?);
+        let block: Arc<CoreBlock> = Arc::new(
+            data.try_into()
+                .map_err(|err| RPCError::invalid_params("data", err))?,
+        );
         let resolver = HeaderResolverWrapper::new(block.header(), self.shared.clone());
         let header_verify_ret = {
             let chain_state = self.shared.chain_state().lock();
@@ -2,6 +2,8 @@ mod chain;
 mod miner;
 mod net;
 mod pool;
+mod pool_admin;
+mod stats;
 mod test;
 mod trace;
 
@@ -9,5 +11,7 @@ pub(crate) use self::chain::{ChainRpc, ChainRpcImpl};
 pub(crate) use self::miner::{MinerRpc, MinerRpcImpl};
 pub(crate) use self::net::{NetworkRpc, NetworkRpcImpl};
 pub(crate) use self::pool::{PoolRpc, PoolRpcImpl};
+pub(crate) use self::pool_admin::{PoolAdminRpc, PoolAdminRpcImpl};
+pub(crate) use self::stats::{StatsRpc, StatsRpcImpl};
 pub(crate) use self::test::{IntegrationTestRpc, IntegrationTestRpcImpl};
 pub(crate) use self::trace::{TraceRpc, TraceRpcImpl};
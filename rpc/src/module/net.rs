@@ -1,8 +1,11 @@
 use build_info::{get_version, Version};
-use ckb_network::NetworkController;
-use jsonrpc_core::Result;
+use ckb_network::{BanTarget, NetworkController, Peer};
+use ckb_shared::store::ChainStore;
+use ckb_sync::Synchronizer;
+use jsonrpc_core::{Error, Result};
 use jsonrpc_derive::rpc;
-use jsonrpc_types::{Node, NodeAddress};
+use jsonrpc_types::{BannedAddress, Node, NodeAddress, PeerDirection, PeerSyncState, RemoteNode};
+use std::time::Duration;
 
 const MAX_ADDRS: usize = 50;
 
@@ -14,14 +17,65 @@ pub trait NetworkRpc {
 
     // curl -d '{"id": 2, "jsonrpc": "2.0", "method":"get_peers","params": []}' -H 'content-type:application/json' 'http://localhost:8114'
     #[rpc(name = "get_peers")]
-    fn get_peers(&self) -> Result<Vec<Node>>;
+    fn get_peers(&self) -> Result<Vec<RemoteNode>>;
+
+    // curl -d '{"id": 2, "jsonrpc": "2.0", "method":"set_ban","params": ["192.168.0.0/16", "86400000", "spam"]}' -H 'content-type:application/json' 'http://localhost:8114'
+    #[rpc(name = "set_ban")]
+    fn set_ban(&self, _address: String, _ban_time: String, _reason: Option<String>) -> Result<()>;
+
+    // curl -d '{"id": 2, "jsonrpc": "2.0", "method":"get_banned_addresses","params": []}' -H 'content-type:application/json' 'http://localhost:8114'
+    #[rpc(name = "get_banned_addresses")]
+    fn get_banned_addresses(&self) -> Result<Vec<BannedAddress>>;
+
+    // curl -d '{"id": 2, "jsonrpc": "2.0", "method":"clear_banned_addresses","params": []}' -H 'content-type:application/json' 'http://localhost:8114'
+    #[rpc(name = "clear_banned_addresses")]
+    fn clear_banned_addresses(&self) -> Result<()>;
 }
 
-pub(crate) struct NetworkRpcImpl {
+pub(crate) struct NetworkRpcImpl<CS: ChainStore> {
     pub network_controller: NetworkController,
+    pub synchronizer: Synchronizer<CS>,
+}
+
+impl<CS: ChainStore> NetworkRpcImpl<CS> {
+    fn sync_state(&self, peer: &Peer) -> Option<PeerSyncState> {
+        let peers = &self.synchronizer.peers;
+        let is_protected = peers
+            .state
+            .read()
+            .get(&peer.session_id)
+            .map(|state| state.chain_sync.protect)?;
+        let best_known_header = peers
+            .best_known_headers
+            .read()
+            .get(&peer.session_id)
+            .map(|header| (header.hash(), header.number()));
+        let last_common_header = peers
+            .last_common_headers
+            .read()
+            .get(&peer.session_id)
+            .map(|header| (header.hash(), header.number()));
+        let inflight_count = peers
+            .blocks_inflight
+            .read()
+            .get(&peer.session_id)
+            .map_or(0, |inflight| inflight.blocks.len());
+        Some(PeerSyncState {
+            best_known_header_hash: best_known_header.as_ref().map(|(hash, _)| hash.clone()),
+            best_known_header_number: best_known_header.map(|(_, number)| number.to_string()),
+            last_common_header_hash: last_common_header.as_ref().map(|(hash, _)| hash.clone()),
+            last_common_header_number: last_common_header.map(|(_, number)| number.to_string()),
+            inflight_count: inflight_count.to_string(),
+            is_protected,
+        })
+    }
+}
+
+fn millis(duration: Duration) -> String {
+    (duration.as_secs() * 1000 + u64::from(duration.subsec_millis())).to_string()
 }
 
-impl NetworkRpc for NetworkRpcImpl {
+impl<CS: ChainStore + 'static> NetworkRpc for NetworkRpcImpl<CS> {
     fn local_node_info(&self) -> Result<Node> {
         Ok(Node {
             version: get_version!().to_string(),
@@ -35,25 +89,74 @@ impl NetworkRpc for NetworkRpcImpl {
         })
     }
 
-    fn get_peers(&self) -> Result<Vec<Node>> {
+    fn get_peers(&self) -> Result<Vec<RemoteNode>> {
         let peers = self.network_controller.connected_peers();
         Ok(peers
             .into_iter()
-            .map(|(peer_id, peer, addresses)| Node {
-                version: peer
-                    .identify_info
-                    .map(|info| info.client_version)
-                    .unwrap_or_else(|| "unknown".to_string()),
-                node_id: peer_id.to_base58(),
-                // TODO how to get correct port and score?
-                addresses: addresses
-                    .into_iter()
-                    .map(|(address, score)| NodeAddress {
-                        address: address.to_string(),
-                        score,
-                    })
-                    .collect(),
+            .map(|(peer_id, peer, addresses)| {
+                let direction = if peer.is_feeler {
+                    PeerDirection::Feeler
+                } else if peer.is_outbound() {
+                    PeerDirection::Outbound
+                } else {
+                    PeerDirection::Inbound
+                };
+                RemoteNode {
+                    version: peer
+                        .identify_info
+                        .as_ref()
+                        .map(|info| info.client_version.clone())
+                        .unwrap_or_else(|| "unknown".to_string()),
+                    node_id: peer_id.to_base58(),
+                    // TODO how to get correct port and score?
+                    addresses: addresses
+                        .into_iter()
+                        .map(|(address, score)| NodeAddress {
+                            address: address.to_string(),
+                            score,
+                        })
+                        .collect(),
+                    direction,
+                    connected_duration: millis(peer.connected_time.elapsed()),
+                    last_ping_duration: peer.ping.map(millis),
+                    score: self.network_controller.peer_score(&peer_id),
+                    sync_state: self.sync_state(&peer),
+                }
             })
             .collect())
     }
+
+    fn set_ban(&self, address: String, ban_time: String, reason: Option<String>) -> Result<()> {
+        let target = address
+            .parse::<BanTarget>()
+            .map_err(Error::invalid_params)?;
+        let ban_time = ban_time
+            .parse::<u64>()
+            .map_err(|_| Error::invalid_params("ban_time should be milliseconds"))?;
+        self.network_controller.ban_network(
+            target,
+            Duration::from_millis(ban_time),
+            reason.unwrap_or_default(),
+        );
+        Ok(())
+    }
+
+    fn get_banned_addresses(&self) -> Result<Vec<BannedAddress>> {
+        Ok(self
+            .network_controller
+            .banned_addrs()
+            .into_iter()
+            .map(|banned| BannedAddress {
+                address: banned.address.to_string(),
+                ban_until: banned.ban_until.as_secs().saturating_mul(1000).to_string(),
+                ban_reason: banned.ban_reason,
+                created_at: banned.created_at.as_secs().saturating_mul(1000).to_string(),
+            })
+            .collect())
+    }
+
+    fn clear_banned_addresses(&self) -> Result<()> {
+        self.network_controller.clear_banned_addrs();
+        Ok(())
+    }
 }
@@ -1,16 +1,18 @@
 use crate::error::RPCError;
+use ckb_core::header::BlockNumber;
 use ckb_core::transaction::{ProposalShortId, Transaction as CoreTransaction};
-use ckb_network::NetworkController;
-use ckb_protocol::RelayMessage;
 use ckb_shared::shared::Shared;
 use ckb_shared::store::ChainStore;
-use ckb_shared::tx_pool::types::PoolEntry;
-use ckb_sync::NetworkProtocol;
+use ckb_shared::tx_pool::types::{PoolEntry, PoolError, PoolSet};
+use ckb_shared::tx_pool::TxPool;
+use ckb_sync::Relayer;
 use ckb_traits::chain_provider::ChainProvider;
-use flatbuffers::FlatBufferBuilder;
-use jsonrpc_core::{Error, Result};
+use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
-use jsonrpc_types::Transaction;
+use jsonrpc_types::{
+    FeeRate, PoolEntry as JsonPoolEntry, PoolSet as JsonPoolSet, PoolSetInfo, RawTxPool,
+    Transaction, TxPoolInfo,
+};
 use log::debug;
 use numext_fixed_hash::H256;
 use std::convert::TryInto;
@@ -24,38 +26,48 @@ pub trait PoolRpc {
     // curl -d '{"id": 2, "jsonrpc": "2.0", "method":"get_pool_transaction","params": [""]}' -H 'content-type:application/json' 'http://localhost:8114'
     #[rpc(name = "get_pool_transaction")]
     fn get_pool_transaction(&self, _hash: H256) -> Result<Option<Transaction>>;
+
+    // curl -d '{"id": 2, "jsonrpc": "2.0", "method":"get_tx_pool_info","params": []}' -H 'content-type:application/json' 'http://localhost:8114'
+    #[rpc(name = "get_tx_pool_info")]
+    fn get_tx_pool_info(&self) -> Result<TxPoolInfo>;
+
+    // curl -d '{"id": 2, "jsonrpc": "2.0", "method":"get_raw_tx_pool","params": []}' -H 'content-type:application/json' 'http://localhost:8114'
+    #[rpc(name = "get_raw_tx_pool")]
+    fn get_raw_tx_pool(&self) -> Result<RawTxPool>;
+
+    // curl -d '{"id": 2, "jsonrpc": "2.0", "method":"get_pool_entry","params": [""]}' -H 'content-type:application/json' 'http://localhost:8114'
+    #[rpc(name = "get_pool_entry")]
+    fn get_pool_entry(&self, _hash: H256) -> Result<Option<JsonPoolEntry>>;
+
+    // curl -d '{"id": 2, "jsonrpc": "2.0", "method":"estimate_fee_rate","params": ["6"]}' -H 'content-type:application/json' 'http://localhost:8114'
+    #[rpc(name = "estimate_fee_rate")]
+    fn estimate_fee_rate(&self, _target_blocks: String) -> Result<Option<FeeRate>>;
 }
 
 pub(crate) struct PoolRpcImpl<CS> {
-    pub network_controller: NetworkController,
+    pub relayer: Relayer<CS>,
     pub shared: Shared<CS>,
 }
 
 impl<CS: ChainStore + 'static> PoolRpc for PoolRpcImpl<CS> {
     fn send_transaction(&self, tx: Transaction) -> Result<H256> {
-        let tx: CoreTransaction = tx.try_into().map_err(|_| Error::parse_error())?;
+        let tx: CoreTransaction = tx
+            .try_into()
+            .map_err(|err| RPCError::invalid_params("tx", err))?;
 
-        let mut chain_state = self.shared.chain_state().lock();
+        let chain_state = self.shared.chain_state().lock();
         let rtx = chain_state.rpc_resolve_tx_from_pool(&tx, &chain_state.tx_pool());
         let tx_result = chain_state.verify_rtx(&rtx, self.shared.consensus().max_block_cycles());
         debug!(target: "rpc", "send_transaction add to pool result: {:?}", tx_result);
         match tx_result {
-            Err(err) => Err(RPCError::custom(RPCError::Invalid, format!("{:?}", err))),
+            Err(err) => Err(RPCError::from_pool_error(PoolError::InvalidTx(err))),
             Ok(cycles) => {
-                let entry = PoolEntry::new(tx.clone(), 0, Some(cycles));
                 let tx_hash = tx.hash().clone();
-                if !chain_state.mut_tx_pool().enqueue_tx(entry) {
-                    // Duplicate tx
-                    Ok(tx_hash)
-                } else {
-                    let fbb = &mut FlatBufferBuilder::new();
-                    let message = RelayMessage::build_transaction(fbb, &tx, cycles);
-                    fbb.finish(message, None);
-                    let data = fbb.finished_data().to_vec();
-                    self.network_controller
-                        .broadcast(NetworkProtocol::RELAY.into(), data);
-                    Ok(tx_hash)
+                if chain_state.rpc_enqueue_tx(&rtx, cycles) {
+                    self.relayer.announce_local_transaction(tx_hash.clone());
                 }
+                // a duplicate tx is already announced
+                Ok(tx_hash)
             }
         }
     }
@@ -70,4 +82,81 @@ impl<CS: ChainStore + 'static> PoolRpc for PoolRpcImpl<CS> {
             .get_tx(&id)
             .map(|tx| (&tx).into()))
     }
+
+    fn get_tx_pool_info(&self) -> Result<TxPoolInfo> {
+        let chain_state = self.shared.chain_state().lock();
+        let tx_pool = chain_state.tx_pool();
+        Ok(TxPoolInfo {
+            pending: set_info(tx_pool.pending_txs_iter()),
+            staging: set_info(tx_pool.staging_txs_iter()),
+            orphan: set_info(tx_pool.orphan_txs_iter()),
+            conflict: set_info(tx_pool.conflict_txs_iter()),
+            last_txs_updated_at: chain_state.get_last_txs_updated_at().to_string(),
+        })
+    }
+
+    fn get_raw_tx_pool(&self) -> Result<RawTxPool> {
+        let chain_state = self.shared.chain_state().lock();
+        let tx_pool = chain_state.tx_pool();
+        Ok(RawTxPool {
+            pending: hashes(tx_pool.pending_txs_iter()),
+            staging: hashes(tx_pool.staging_txs_iter()),
+            orphan: hashes(tx_pool.orphan_txs_iter()),
+            conflict: hashes(tx_pool.conflict_txs_iter()),
+        })
+    }
+
+    fn get_pool_entry(&self, hash: H256) -> Result<Option<JsonPoolEntry>> {
+        let chain_state = self.shared.chain_state().lock();
+        let tx_pool = chain_state.tx_pool();
+        Ok(tx_pool
+            .get_entry_with_set(&hash)
+            .map(|(entry, set)| pool_entry(&tx_pool, entry, set)))
+    }
+
+    fn estimate_fee_rate(&self, target_blocks: String) -> Result<Option<FeeRate>> {
+        let target_blocks = target_blocks
+            .parse::<BlockNumber>()
+            .map_err(|err| RPCError::invalid_params("target_blocks", err))?;
+        Ok(self
+            .shared
+            .chain_state()
+            .lock()
+            .estimate_fee_rate(target_blocks)
+            .map(|fee_rate| FeeRate {
+                fee_rate: fee_rate.to_string(),
+            }))
+    }
+}
+
+fn set_info<'a>(entries: impl Iterator<Item = &'a PoolEntry>) -> PoolSetInfo {
+    let (count, bytes) = entries.fold((0usize, 0usize), |(count, bytes), entry| {
+        (count + 1, bytes + entry.size())
+    });
+    PoolSetInfo {
+        count: count.to_string(),
+        bytes: bytes.to_string(),
+    }
+}
+
+fn hashes<'a>(entries: impl Iterator<Item = &'a PoolEntry>) -> Vec<H256> {
+    entries.map(|entry| entry.transaction.hash()).collect()
+}
+
+fn pool_entry(tx_pool: &TxPool, entry: &PoolEntry, set: PoolSet) -> JsonPoolEntry {
+    let id = entry.transaction.proposal_short_id();
+    JsonPoolEntry {
+        set: match set {
+            PoolSet::Pending => JsonPoolSet::Pending,
+            PoolSet::Staging => JsonPoolSet::Staging,
+            PoolSet::Orphan => JsonPoolSet::Orphan,
+            PoolSet::Conflict => JsonPoolSet::Conflict,
+        },
+        cycles: entry.cycles.map(|cycles| cycles.to_string()),
+        refs_count: entry.refs_count.to_string(),
+        size: entry.size().to_string(),
+        ancestors: hashes(tx_pool.ancestors(&id).into_iter()),
+        descendants: hashes(tx_pool.descendants(&id).into_iter()),
+        timestamp: entry.timestamp.to_string(),
+    }
 }
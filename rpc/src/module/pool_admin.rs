@@ -0,0 +1,42 @@
+use ckb_shared::shared::Shared;
+use ckb_shared::store::ChainStore;
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+use log::info;
+use numext_fixed_hash::H256;
+
+#[rpc]
+pub trait PoolAdminRpc {
+    // curl -d '{"id": 2, "jsonrpc": "2.0", "method":"remove_transaction","params": [""]}' -H 'content-type:application/json' 'http://localhost:8114'
+    #[rpc(name = "remove_transaction")]
+    fn remove_transaction(&self, _hash: H256) -> Result<Vec<H256>>;
+
+    // curl -d '{"id": 2, "jsonrpc": "2.0", "method":"clear_tx_pool","params": []}' -H 'content-type:application/json' 'http://localhost:8114'
+    #[rpc(name = "clear_tx_pool")]
+    fn clear_tx_pool(&self) -> Result<()>;
+}
+
+pub(crate) struct PoolAdminRpcImpl<CS> {
+    pub shared: Shared<CS>,
+}
+
+impl<CS: ChainStore + 'static> PoolAdminRpc for PoolAdminRpcImpl<CS> {
+    fn remove_transaction(&self, hash: H256) -> Result<Vec<H256>> {
+        let removed: Vec<H256> = self
+            .shared
+            .chain_state()
+            .lock()
+            .remove_tx_from_pool(&hash)
+            .into_iter()
+            .map(|entry| entry.transaction.hash())
+            .collect();
+        info!(target: "rpc", "remove_transaction {:#x} removed {} transactions", hash, removed.len());
+        Ok(removed)
+    }
+
+    fn clear_tx_pool(&self) -> Result<()> {
+        self.shared.chain_state().lock().clear_tx_pool();
+        info!(target: "rpc", "clear_tx_pool");
+        Ok(())
+    }
+}
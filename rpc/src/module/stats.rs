@@ -0,0 +1,77 @@
+use crate::error::RPCError;
+use ckb_shared::shared::Shared;
+use ckb_shared::store::ChainStore;
+use ckb_sync::{NetTimeProtocol, Synchronizer};
+use ckb_traits::{BlockMedianTimeContext, ChainProvider};
+use jsonrpc_core::{Error, Result};
+use jsonrpc_derive::rpc;
+use jsonrpc_types::{BlockchainInfo, ColumnStatistics};
+
+#[rpc]
+pub trait StatsRpc {
+    // curl -d '{"id": 2, "jsonrpc": "2.0", "method":"get_db_statistics","params": []}' -H 'content-type:application/json' 'http://localhost:8114'
+    #[rpc(name = "get_db_statistics")]
+    fn get_db_statistics(&self) -> Result<Vec<ColumnStatistics>>;
+
+    // curl -d '{"id": 2, "jsonrpc": "2.0", "method":"get_blockchain_info","params": []}' -H 'content-type:application/json' 'http://localhost:8114'
+    #[rpc(name = "get_blockchain_info")]
+    fn get_blockchain_info(&self) -> Result<BlockchainInfo>;
+}
+
+pub(crate) struct StatsRpcImpl<CS: ChainStore> {
+    pub shared: Shared<CS>,
+    pub synchronizer: Synchronizer<CS>,
+    pub net_time: NetTimeProtocol,
+}
+
+impl<CS: ChainStore + 'static> StatsRpc for StatsRpcImpl<CS> {
+    fn get_db_statistics(&self) -> Result<Vec<ColumnStatistics>> {
+        let statistics = self
+            .shared
+            .store()
+            .get_column_statistics()
+            .map_err(|err| RPCError::custom(RPCError::Invalid, err.to_string()))?;
+        Ok(statistics
+            .into_iter()
+            .map(|(name, column)| ColumnStatistics {
+                name: name.to_owned(),
+                estimate_num_keys: column.estimate_num_keys.map(|n| n.to_string()),
+                total_sst_files_size: column.total_sst_files_size.map(|n| n.to_string()),
+                mem_tables_size: column.mem_tables_size.map(|n| n.to_string()),
+                table_readers_mem: column.table_readers_mem.map(|n| n.to_string()),
+                block_cache_usage: column.block_cache_usage.map(|n| n.to_string()),
+            })
+            .collect())
+    }
+
+    fn get_blockchain_info(&self) -> Result<BlockchainInfo> {
+        // takes the chain state lock, must be called before locking it below
+        let is_initial_block_download = self.synchronizer.is_initial_block_download();
+        let best_known_header = self.synchronizer.best_known_header();
+        let mut warnings = Vec::new();
+        if let Err(offset) = self.net_time.check() {
+            warnings.push(format!(
+                "the local clock is {}ms off from the network peers",
+                offset
+            ));
+        }
+
+        let chain_state = self.shared.chain_state().lock();
+        let tip_header = chain_state.tip_header();
+        let median_time = (&*chain_state)
+            .block_median_time(tip_header.number())
+            .ok_or_else(Error::internal_error)?;
+        Ok(BlockchainInfo {
+            chain: self.shared.consensus().id.clone(),
+            genesis_hash: self.shared.genesis_hash(),
+            tip_hash: tip_header.hash(),
+            tip_number: tip_header.number().to_string(),
+            best_known_header_hash: best_known_header.hash(),
+            best_known_header_number: best_known_header.number().to_string(),
+            is_initial_block_download,
+            median_time: median_time.to_string(),
+            difficulty: tip_header.difficulty().clone(),
+            warnings,
+        })
+    }
+}
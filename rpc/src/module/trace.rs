@@ -1,14 +1,10 @@
 use crate::error::RPCError;
 use ckb_core::transaction::Transaction as CoreTransaction;
-use ckb_network::NetworkController;
-use ckb_protocol::RelayMessage;
 use ckb_shared::shared::Shared;
 use ckb_shared::store::ChainStore;
-use ckb_shared::tx_pool::types::PoolEntry;
-use ckb_sync::NetworkProtocol;
+use ckb_sync::Relayer;
 use ckb_traits::chain_provider::ChainProvider;
-use flatbuffers::FlatBufferBuilder;
-use jsonrpc_core::{Error, Result};
+use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
 use jsonrpc_types::{Transaction, TxTrace};
 use numext_fixed_hash::H256;
@@ -24,37 +20,28 @@ pub trait TraceRpc {
 }
 
 pub(crate) struct TraceRpcImpl<CS> {
-    pub network_controller: NetworkController,
+    pub relayer: Relayer<CS>,
     pub shared: Shared<CS>,
 }
 
 impl<CS: ChainStore + 'static> TraceRpc for TraceRpcImpl<CS> {
     fn trace_transaction(&self, tx: Transaction) -> Result<H256> {
-        let tx: CoreTransaction = tx.try_into().map_err(|_| Error::parse_error())?;
+        let tx: CoreTransaction = tx
+            .try_into()
+            .map_err(|err| RPCError::invalid_params("tx", err))?;
 
-        let mut chain_state = self.shared.chain_state().lock();
+        let chain_state = self.shared.chain_state().lock();
         let rtx = chain_state.rpc_resolve_tx_from_pool(&tx, &chain_state.tx_pool());
         let tx_result = chain_state.verify_rtx(&rtx, self.shared.consensus().max_block_cycles());
         match tx_result {
-            Err(err) => Err(RPCError::custom(RPCError::Invalid, format!("{:?}", err))),
+            Err(err) => Err(RPCError::from_transaction_error(err)),
             Ok(cycles) => {
                 let tx_hash = tx.hash().clone();
-                let entry = PoolEntry::new(tx.clone(), 0, Some(cycles));
-
-                if !chain_state.mut_tx_pool().trace_tx(entry) {
-                    // Duplicate tx
-                    Ok(tx_hash)
-                } else {
-                    let fbb = &mut FlatBufferBuilder::new();
-                    let message = RelayMessage::build_transaction(fbb, &tx, cycles);
-                    fbb.finish(message, None);
-
-                    let data = fbb.finished_data().to_vec();
-                    self.network_controller
-                        .broadcast(NetworkProtocol::RELAY.into(), data);
-
-                    Ok(tx_hash)
+                if chain_state.rpc_trace_tx(&rtx, cycles) {
+                    self.relayer.announce_local_transaction(tx_hash.clone());
                 }
+                // a duplicate tx is already announced
+                Ok(tx_hash)
             }
         }
     }
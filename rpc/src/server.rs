@@ -1,20 +1,29 @@
-use crate::config::Config;
+use crate::auth::AccessControl;
+use crate::config::{Config, Module};
+use crate::metrics::RpcMetrics;
+use crate::middleware::{RpcMeta, RpcMiddleware};
 use crate::module::{
     ChainRpc, ChainRpcImpl, IntegrationTestRpc, IntegrationTestRpcImpl, MinerRpc, MinerRpcImpl,
-    NetworkRpc, NetworkRpcImpl, PoolRpc, PoolRpcImpl, TraceRpc, TraceRpcImpl,
+    NetworkRpc, NetworkRpcImpl, PoolAdminRpc, PoolAdminRpcImpl, PoolRpc, PoolRpcImpl, StatsRpc,
+    StatsRpcImpl, TraceRpc, TraceRpcImpl,
 };
 use ckb_chain::chain::ChainController;
 use ckb_miner::BlockAssemblerController;
 use ckb_network::NetworkController;
 use ckb_shared::shared::Shared;
 use ckb_shared::store::ChainStore;
-use jsonrpc_core::IoHandler;
-use jsonrpc_http_server::{Server, ServerBuilder};
+use ckb_sync::{NetTimeProtocol, Relayer, Synchronizer};
+use jsonrpc_core::{MetaIoHandler, RemoteProcedure};
+use jsonrpc_http_server::{hyper, Server, ServerBuilder};
 use jsonrpc_server_utils::cors::AccessControlAllowOrigin;
 use jsonrpc_server_utils::hosts::DomainsValidation;
+use std::collections::HashMap;
+use std::sync::Arc;
 
 pub struct RpcServer {
     server: Server,
+    admin_server: Option<Server>,
+    metrics: Arc<RpcMetrics>,
 }
 
 impl RpcServer {
@@ -24,85 +33,171 @@ impl RpcServer {
         shared: Shared<CS>,
         chain: ChainController,
         block_assembler: BlockAssemblerController,
+        synchronizer: Synchronizer<CS>,
+        relayer: Relayer<CS>,
+        net_time: NetTimeProtocol,
     ) -> RpcServer
     where
         CS: ChainStore,
     {
-        let mut io = IoHandler::new();
+        let config = Arc::new(config);
+        let metrics = Arc::new(RpcMetrics::default());
+        let handlers = Handlers {
+            config: Arc::clone(&config),
+            metrics: Arc::clone(&metrics),
+            network_controller,
+            shared,
+            chain,
+            block_assembler,
+            synchronizer,
+            relayer,
+            net_time,
+        };
+        // the admin modules are only served on the admin address once it is set
+        let modules: Vec<Module> = if config.admin_listen_address.is_some() {
+            config
+                .modules
+                .iter()
+                .filter(|module| !config.admin_modules.contains(module))
+                .cloned()
+                .collect()
+        } else {
+            config.modules.clone()
+        };
+        let server = handlers.start(&config.listen_address, &modules);
+        let admin_server = config
+            .admin_listen_address
+            .as_ref()
+            .map(|address| handlers.start(address, &config.admin_modules));
 
-        if config.chain_enable() {
-            io.extend_with(
-                ChainRpcImpl {
-                    shared: shared.clone(),
-                }
-                .to_delegate(),
-            );
+        RpcServer {
+            server,
+            admin_server,
+            metrics,
         }
+    }
 
-        if config.pool_enable() {
-            io.extend_with(
-                PoolRpcImpl {
-                    network_controller: network_controller.clone(),
-                    shared: shared.clone(),
-                }
-                .to_delegate(),
-            );
-        }
+    /// The metrics of the calls served by both the public and admin servers
+    pub fn metrics(&self) -> Arc<RpcMetrics> {
+        Arc::clone(&self.metrics)
+    }
 
-        if config.miner_enable() {
-            io.extend_with(
-                MinerRpcImpl {
-                    shared: shared.clone(),
-                    block_assembler,
-                    chain,
-                    network_controller: network_controller.clone(),
-                }
-                .to_delegate(),
-            );
+    pub fn close(self) {
+        self.server.close();
+        if let Some(admin_server) = self.admin_server {
+            admin_server.close();
         }
+    }
+}
 
-        if config.net_enable() {
-            io.extend_with(
-                NetworkRpcImpl {
-                    network_controller: network_controller.clone(),
-                }
-                .to_delegate(),
-            );
-        }
+struct Handlers<CS: ChainStore> {
+    config: Arc<Config>,
+    // shared by the public and admin servers, and so is the rate limit
+    metrics: Arc<RpcMetrics>,
+    network_controller: NetworkController,
+    shared: Shared<CS>,
+    chain: ChainController,
+    block_assembler: BlockAssemblerController,
+    synchronizer: Synchronizer<CS>,
+    relayer: Relayer<CS>,
+    net_time: NetTimeProtocol,
+}
 
-        if config.trace_enable() {
-            io.extend_with(
-                TraceRpcImpl {
-                    network_controller: network_controller.clone(),
-                    shared,
-                }
-                .to_delegate(),
-            );
+impl<CS: ChainStore + 'static> Handlers<CS> {
+    fn methods(&self, module: Module) -> Vec<(String, RemoteProcedure<RpcMeta>)> {
+        match module {
+            Module::Chain => ChainRpcImpl {
+                shared: self.shared.clone(),
+            }
+            .to_delegate()
+            .into_iter()
+            .collect(),
+            Module::Pool => PoolRpcImpl {
+                relayer: self.relayer.clone(),
+                shared: self.shared.clone(),
+            }
+            .to_delegate()
+            .into_iter()
+            .collect(),
+            Module::PoolAdmin => PoolAdminRpcImpl {
+                shared: self.shared.clone(),
+            }
+            .to_delegate()
+            .into_iter()
+            .collect(),
+            Module::Miner => MinerRpcImpl {
+                shared: self.shared.clone(),
+                block_assembler: self.block_assembler.clone(),
+                chain: self.chain.clone(),
+                network_controller: self.network_controller.clone(),
+            }
+            .to_delegate()
+            .into_iter()
+            .collect(),
+            Module::Net => NetworkRpcImpl {
+                network_controller: self.network_controller.clone(),
+                synchronizer: self.synchronizer.clone(),
+            }
+            .to_delegate()
+            .into_iter()
+            .collect(),
+            Module::Trace => TraceRpcImpl {
+                relayer: self.relayer.clone(),
+                shared: self.shared.clone(),
+            }
+            .to_delegate()
+            .into_iter()
+            .collect(),
+            Module::Stats => StatsRpcImpl {
+                shared: self.shared.clone(),
+                synchronizer: self.synchronizer.clone(),
+                net_time: self.net_time.clone(),
+            }
+            .to_delegate()
+            .into_iter()
+            .collect(),
+            Module::IntegrationTest => IntegrationTestRpcImpl {
+                network_controller: self.network_controller.clone(),
+            }
+            .to_delegate()
+            .into_iter()
+            .collect(),
         }
+    }
 
-        if config.integration_test_enable() {
-            io.extend_with(IntegrationTestRpcImpl { network_controller }.to_delegate());
+    fn start(&self, listen_address: &str, modules: &[Module]) -> Server {
+        let config = &self.config;
+        let mut methods = Vec::new();
+        let mut method_modules = HashMap::new();
+        for module in modules {
+            for (name, method) in self.methods(*module) {
+                method_modules.insert(name.clone(), *module);
+                methods.push((name, method));
+            }
         }
+        let middleware = RpcMiddleware::new(
+            Arc::clone(config),
+            AccessControl::new(config, method_modules),
+            Arc::clone(&self.metrics),
+        );
+        let mut io = MetaIoHandler::with_middleware(middleware);
+        io.extend_with(methods);
 
-        let server = ServerBuilder::new(io)
-            .cors(DomainsValidation::AllowOnly(vec![
-                AccessControlAllowOrigin::Null,
-                AccessControlAllowOrigin::Any,
-            ]))
-            .threads(config.threads.unwrap_or_else(num_cpus::get))
-            .max_request_body_size(config.max_request_body_size)
-            .start_http(
-                &config
-                    .listen_address
-                    .parse()
-                    .expect("config listen_address parsed"),
-            )
-            .expect("Jsonrpc initialize");
-
-        RpcServer { server }
-    }
-
-    pub fn close(self) {
-        self.server.close()
+        let meta_config = Arc::clone(config);
+        ServerBuilder::with_meta_extractor(io, move |request: &hyper::Request<hyper::Body>| {
+            RpcMeta::from_http(request, &meta_config)
+        })
+        .cors(DomainsValidation::AllowOnly(vec![
+            AccessControlAllowOrigin::Null,
+            AccessControlAllowOrigin::Any,
+        ]))
+        .threads(config.threads.unwrap_or_else(num_cpus::get))
+        .max_request_body_size(config.max_request_body_size)
+        .start_http(
+            &listen_address
+                .parse()
+                .expect("config listen_address parsed"),
+        )
+        .expect("Jsonrpc initialize")
     }
 }
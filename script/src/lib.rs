@@ -2,6 +2,7 @@ mod cost_model;
 mod syscalls;
 mod verify;
 
+use ckb_core::Cycle;
 use ckb_vm::Error as VMInternalError;
 
 pub use crate::verify::TransactionScriptsVerifier;
@@ -15,3 +16,21 @@ pub enum ScriptError {
     VMError(VMInternalError),
     ExceededMaximumCycles,
 }
+
+/// The cell whose script failed
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub enum ScriptSource {
+    /// The lock script of the input at the index
+    Input(usize),
+    /// The type script of the output at the index
+    Output(usize),
+}
+
+/// A script failure of a transaction
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub struct TransactionScriptError {
+    pub source: ScriptSource,
+    pub error: ScriptError,
+    /// The cycles consumed by the scripts which passed before the failure
+    pub cycles: Cycle,
+}
@@ -1,7 +1,7 @@
 use crate::{
     cost_model::instruction_cycles,
     syscalls::{build_tx, Debugger, LoadCell, LoadCellByField, LoadInputByField, LoadTx},
-    ScriptError,
+    ScriptError, ScriptSource, TransactionScriptError,
 };
 use ckb_core::cell::ResolvedTransaction;
 use ckb_core::script::{Script, ALWAYS_SUCCESS_HASH};
@@ -160,44 +160,58 @@ impl<'a> TransactionScriptsVerifier<'a> {
         })
     }
 
-    pub fn verify(&self, max_cycles: Cycle) -> Result<Cycle, ScriptError> {
+    pub fn verify(&self, max_cycles: Cycle) -> Result<Cycle, TransactionScriptError> {
         let mut cycles = 0;
         for (i, (input, input_cell)) in self.inputs.iter().zip(self.input_cells.iter()).enumerate()
         {
+            let source = ScriptSource::Input(i);
             let prefix = format!("Transaction {}, input {}", self.hash, i);
             let witness = self.witnesses.get(&(i as u32));
             let cycle = self.verify_script(&input_cell.lock, &prefix, input_cell, witness, Some(input), max_cycles - cycles).map_err(|e| {
                 info!(target: "script", "Error validating input {} of transaction {}: {:?}", i, self.hash, e);
-                e
+                script_error(source, e, cycles)
             })?;
-            let current_cycles = cycles
-                .checked_add(cycle)
-                .ok_or(ScriptError::ExceededMaximumCycles)?;
-            if current_cycles > max_cycles {
-                return Err(ScriptError::ExceededMaximumCycles);
-            }
-            cycles = current_cycles;
+            cycles = add_cycles(source, cycles, cycle, max_cycles)?;
         }
         for (i, output) in self.outputs.iter().enumerate() {
             if let Some(ref type_) = output.type_ {
+                let source = ScriptSource::Output(i);
                 let prefix = format!("Transaction {}, output {}", self.hash, i);
                 let cycle = self.verify_script(type_, &prefix, output, None, None, max_cycles - cycles).map_err(|e| {
                     info!(target: "script", "Error validating output {} of transaction {}: {:?}", i, self.hash, e);
-                    e
+                    script_error(source, e, cycles)
                 })?;
-                let current_cycles = cycles
-                    .checked_add(cycle)
-                    .ok_or(ScriptError::ExceededMaximumCycles)?;
-                if current_cycles > max_cycles {
-                    return Err(ScriptError::ExceededMaximumCycles);
-                }
-                cycles = current_cycles;
+                cycles = add_cycles(source, cycles, cycle, max_cycles)?;
             }
         }
         Ok(cycles)
     }
 }
 
+fn script_error(source: ScriptSource, error: ScriptError, cycles: Cycle) -> TransactionScriptError {
+    TransactionScriptError {
+        source,
+        error,
+        cycles,
+    }
+}
+
+fn add_cycles(
+    source: ScriptSource,
+    cycles: Cycle,
+    cycle: Cycle,
+    max_cycles: Cycle,
+) -> Result<Cycle, TransactionScriptError> {
+    match cycles.checked_add(cycle) {
+        Some(current_cycles) if current_cycles <= max_cycles => Ok(current_cycles),
+        _ => Err(script_error(
+            source,
+            ScriptError::ExceededMaximumCycles,
+            cycles,
+        )),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,9 +1,16 @@
+use crate::store::ChainStore;
 use ckb_core::block::Block;
 use ckb_core::transaction::OutPoint;
 use ckb_core::transaction_meta::TransactionMeta;
 use fnv::{FnvHashMap, FnvHashSet};
+use lru_cache::LruCache;
 use numext_fixed_hash::H256;
 use serde_derive::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::hash_map::Entry;
+use std::sync::Arc;
+
+pub const CELL_SET_CACHE_SIZE: usize = 100_000;
 
 #[derive(Default, Clone, Deserialize, Serialize)]
 pub struct CellSetDiff {
@@ -38,139 +45,140 @@ impl CellSetDiff {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-pub struct CellSetOverlay<'a> {
-    origin: &'a FnvHashMap<H256, TransactionMeta>,
-    new: FnvHashMap<H256, TransactionMeta>,
-    removed: FnvHashSet<H256>,
+/// The transaction metas to write and to delete when a `CellSetDiff` is applied
+#[derive(Default, Debug, PartialEq, Eq, Clone)]
+pub struct CellSetChanges {
+    pub(crate) updated: FnvHashMap<H256, TransactionMeta>,
+    pub(crate) removed: FnvHashSet<H256>,
+}
+
+impl CellSetChanges {
+    pub fn updated(&self) -> impl Iterator<Item = (&H256, &TransactionMeta)> {
+        self.updated.iter()
+    }
+
+    pub fn removed(&self) -> impl Iterator<Item = &H256> {
+        self.removed.iter()
+    }
 }
 
-impl<'a> CellSetOverlay<'a> {
-    pub fn get(&self, hash: &H256) -> Option<&TransactionMeta> {
-        if self.removed.get(hash).is_some() {
+#[derive(Debug, Clone)]
+pub struct CellSetOverlay<'a, CS> {
+    origin: &'a CellSet<CS>,
+    changes: CellSetChanges,
+}
+
+impl<'a, CS: ChainStore> CellSetOverlay<'a, CS> {
+    pub fn get(&self, hash: &H256) -> Option<TransactionMeta> {
+        if self.changes.removed.contains(hash) {
             return None;
         }
 
-        self.new.get(hash).or_else(|| self.origin.get(hash))
+        self.changes
+            .updated
+            .get(hash)
+            .cloned()
+            .or_else(|| self.origin.get(hash))
+    }
+
+    pub fn into_changes(self) -> CellSetChanges {
+        self.changes
     }
 }
 
-#[derive(Default, Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
-pub struct CellSet {
-    pub(crate) inner: FnvHashMap<H256, TransactionMeta>,
+/// The live cell set, persisted in `COLUMN_CELL_SET` and read through a bounded cache.
+///
+/// Only `ChainState` mutates it: the changes are written in the same `StoreBatch` as the
+/// attached and detached blocks, the cache is updated once the batch is committed.
+#[derive(Debug, Clone)]
+pub struct CellSet<CS> {
+    store: Arc<CS>,
+    cache: RefCell<LruCache<H256, TransactionMeta>>,
 }
 
-impl CellSet {
-    pub fn new() -> Self {
+impl<CS: ChainStore> CellSet<CS> {
+    pub fn new(store: &Arc<CS>, cache_size: usize) -> Self {
         CellSet {
-            inner: FnvHashMap::default(),
+            store: Arc::clone(store),
+            cache: RefCell::new(LruCache::new(cache_size)),
         }
     }
 
-    pub fn new_overlay<'a>(&'a self, diff: &CellSetDiff) -> CellSetOverlay<'a> {
-        let mut new = FnvHashMap::default();
+    pub fn new_overlay(&self, diff: &CellSetDiff) -> CellSetOverlay<CS> {
+        let mut updated = FnvHashMap::default();
         let mut removed = FnvHashSet::default();
 
         for hash in &diff.old_outputs {
-            if self.inner.get(&hash).is_some() {
-                removed.insert(hash.clone());
-            }
+            removed.insert(hash.clone());
         }
 
         for (hash, (number, cellbase, len)) in diff.new_outputs.clone() {
             removed.remove(&hash);
             if cellbase {
-                new.insert(hash, TransactionMeta::new_cellbase(number, len));
+                updated.insert(hash, TransactionMeta::new_cellbase(number, len));
             } else {
-                new.insert(hash, TransactionMeta::new(number, len));
+                updated.insert(hash, TransactionMeta::new(number, len));
             }
         }
 
         for old_input in &diff.old_inputs {
-            if let Some(meta) = self.inner.get(&old_input.tx_hash) {
-                let meta = new
-                    .entry(old_input.tx_hash.clone())
-                    .or_insert_with(|| meta.clone());
+            if let Some(meta) = self.modify(&mut updated, &removed, &old_input.tx_hash) {
                 meta.unset_dead(old_input.index as usize);
             }
         }
 
         for new_input in &diff.new_inputs {
-            if let Some(meta) = self.inner.get(&new_input.tx_hash) {
-                let meta = new
-                    .entry(new_input.tx_hash.clone())
-                    .or_insert_with(|| meta.clone());
+            if let Some(meta) = self.modify(&mut updated, &removed, &new_input.tx_hash) {
                 meta.set_dead(new_input.index as usize);
             }
         }
 
         CellSetOverlay {
-            new,
-            removed,
-            origin: &self.inner,
+            origin: self,
+            changes: CellSetChanges { updated, removed },
         }
     }
 
-    pub fn is_dead(&self, o: &OutPoint) -> Option<bool> {
-        self.inner
-            .get(&o.tx_hash)
-            .map(|x| x.is_dead(o.index as usize))
+    fn modify<'b>(
+        &self,
+        updated: &'b mut FnvHashMap<H256, TransactionMeta>,
+        removed: &FnvHashSet<H256>,
+        hash: &H256,
+    ) -> Option<&'b mut TransactionMeta> {
+        if removed.contains(hash) {
+            return None;
+        }
+        match updated.entry(hash.clone()) {
+            Entry::Occupied(o) => Some(o.into_mut()),
+            Entry::Vacant(v) => self.get(hash).map(|meta| v.insert(meta)),
+        }
     }
 
-    pub fn get(&self, h: &H256) -> Option<&TransactionMeta> {
-        self.inner.get(h)
+    pub fn is_dead(&self, o: &OutPoint) -> Option<bool> {
+        self.get(&o.tx_hash).map(|x| x.is_dead(o.index as usize))
     }
 
-    pub fn insert(&mut self, tx_hash: H256, number: u64, cellbase: bool, outputs_len: usize) {
-        if cellbase {
-            self.inner
-                .insert(tx_hash, TransactionMeta::new_cellbase(number, outputs_len));
-        } else {
-            self.inner
-                .insert(tx_hash, TransactionMeta::new(number, outputs_len));
+    pub fn get(&self, h: &H256) -> Option<TransactionMeta> {
+        if let Some(meta) = self.cache.borrow_mut().get_mut(h) {
+            return Some(meta.clone());
         }
-    }
 
-    pub fn remove(&mut self, tx_hash: &H256) -> Option<TransactionMeta> {
-        self.inner.remove(tx_hash)
+        let meta = self.store.get_tx_meta(h)?;
+        self.cache.borrow_mut().insert(h.clone(), meta.clone());
+        Some(meta)
     }
 
-    pub fn mark_dead(&mut self, o: &OutPoint) {
-        if let Some(meta) = self.inner.get_mut(&o.tx_hash) {
-            meta.set_dead(o.index as usize);
-        }
-    }
+    /// Apply the changes to the cache, they must have been committed to the store.
+    pub fn update(&mut self, changes: CellSetChanges) {
+        let CellSetChanges { updated, removed } = changes;
+        let cache = self.cache.get_mut();
 
-    fn mark_live(&mut self, o: &OutPoint) {
-        if let Some(meta) = self.inner.get_mut(&o.tx_hash) {
-            meta.unset_dead(o.index as usize);
+        for hash in &removed {
+            cache.remove(hash);
         }
-    }
 
-    pub fn update(&mut self, diff: CellSetDiff) {
-        let CellSetDiff {
-            old_inputs,
-            old_outputs,
-            new_inputs,
-            new_outputs,
-        } = diff;
-
-        old_outputs.iter().for_each(|h| {
-            self.remove(h);
-        });
-
-        old_inputs.iter().for_each(|o| {
-            self.mark_live(o);
-        });
-
-        new_outputs
-            .into_iter()
-            .for_each(|(hash, (number, cellbase, len))| {
-                self.insert(hash, number, cellbase, len);
-            });
-
-        new_inputs.iter().for_each(|o| {
-            self.mark_dead(o);
-        });
+        for (hash, meta) in updated {
+            cache.insert(hash, meta);
+        }
     }
 }
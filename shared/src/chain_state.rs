@@ -1,4 +1,5 @@
-use crate::cell_set::{CellSet, CellSetDiff, CellSetOverlay};
+use crate::cell_set::{CellSet, CellSetChanges, CellSetDiff, CellSetOverlay, CELL_SET_CACHE_SIZE};
+use crate::fee_estimator::{FeeEstimator, FeeStats, MAX_TARGET_BLOCKS};
 use crate::store::ChainStore;
 use crate::tx_pool::{PoolEntry, PoolError, StagingTxResult, TxPool, TxPoolConfig};
 use crate::tx_proposal_table::TxProposalTable;
@@ -17,7 +18,7 @@ use log::error;
 use lru_cache::LruCache;
 use numext_fixed_hash::H256;
 use numext_fixed_uint::U256;
-use std::cell::{Ref, RefCell};
+use std::cell::{Cell, Ref, RefCell};
 use std::sync::Arc;
 
 #[derive(Debug, Clone)]
@@ -25,11 +26,14 @@ pub struct ChainState<CS> {
     store: Arc<CS>,
     tip_header: Header,
     total_difficulty: U256,
-    pub(crate) cell_set: CellSet,
+    pub(crate) cell_set: CellSet<CS>,
     proposal_ids: TxProposalTable,
     // interior mutability for immutable borrow proposal_ids
     tx_pool: RefCell<TxPool>,
     txs_verify_cache: RefCell<LruCache<H256, Cycle>>,
+    // the hits and misses of txs_verify_cache
+    txs_verify_cache_stats: Cell<(u64, u64)>,
+    fee_estimator: RefCell<FeeEstimator>,
     consensus: Arc<Consensus>,
 }
 
@@ -51,12 +55,13 @@ impl<CS: ChainStore> ChainState<CS> {
 
         let txs_verify_cache = LruCache::new(tx_pool_config.txs_verify_cache_size);
         let tx_pool = TxPool::new(tx_pool_config);
+        let fee_estimator = FeeEstimator::new(store.get_fee_stats());
 
         let tip_number = tip_header.number();
         let proposal_window = consensus.tx_proposal_window();
         let proposal_ids = Self::init_proposal_ids(&store, proposal_window, tip_number);
 
-        let cell_set = Self::init_cell_set(&store, tip_number);
+        let cell_set = CellSet::new(store, CELL_SET_CACHE_SIZE);
 
         let total_difficulty = store
             .get_block_ext(&tip_header.hash())
@@ -70,6 +75,8 @@ impl<CS: ChainStore> ChainState<CS> {
             proposal_ids,
             tx_pool: RefCell::new(tx_pool),
             txs_verify_cache: RefCell::new(txs_verify_cache),
+            txs_verify_cache_stats: Cell::new((0, 0)),
+            fee_estimator: RefCell::new(fee_estimator),
             consensus,
         }
     }
@@ -102,26 +109,6 @@ impl<CS: ChainStore> ChainState<CS> {
         proposal_ids
     }
 
-    fn init_cell_set(store: &CS, number: u64) -> CellSet {
-        let mut cell_set = CellSet::new();
-
-        for n in 0..=number {
-            let hash = store.get_block_hash(n).unwrap();
-            for tx in store.get_block_body(&hash).unwrap() {
-                let inputs = tx.input_pts();
-                let output_len = tx.outputs().len();
-
-                for o in inputs {
-                    cell_set.mark_dead(&o);
-                }
-
-                cell_set.insert(tx.hash(), n, tx.is_cellbase(), output_len);
-            }
-        }
-
-        cell_set
-    }
-
     pub fn tip_number(&self) -> BlockNumber {
         self.tip_header.number()
     }
@@ -138,7 +125,7 @@ impl<CS: ChainStore> ChainState<CS> {
         &self.tip_header
     }
 
-    pub fn cell_set(&self) -> &CellSet {
+    pub fn cell_set(&self) -> &CellSet<CS> {
         &self.cell_set
     }
 
@@ -171,10 +158,16 @@ impl<CS: ChainStore> ChainState<CS> {
         self.proposal_ids.finalize(number)
     }
 
-    pub fn update_tip(&mut self, header: Header, total_difficulty: U256, txo_diff: CellSetDiff) {
+    /// `txo_changes` must have been committed in the same batch as the new tip
+    pub fn update_tip(
+        &mut self,
+        header: Header,
+        total_difficulty: U256,
+        txo_changes: CellSetChanges,
+    ) {
         self.tip_header = header;
         self.total_difficulty = total_difficulty;
-        self.cell_set.update(txo_diff);
+        self.cell_set.update(txo_changes);
     }
 
     pub fn add_tx_to_pool(&self, tx: Transaction, max_cycles: Cycle) -> Result<Cycle, PoolError> {
@@ -194,6 +187,7 @@ impl<CS: ChainStore> ChainState<CS> {
                     if !tx_pool.enqueue_tx(entry) {
                         return Err(PoolError::Duplicate);
                     }
+                    self.track_tx(&rtx);
                     Ok(cycles)
                 }
                 Err(TransactionError::Unknown) => {
@@ -222,9 +216,13 @@ impl<CS: ChainStore> ChainState<CS> {
     ) -> Result<Cycle, TransactionError> {
         let tx_hash = rtx.transaction.hash();
         let ret = { self.txs_verify_cache.borrow().get(&tx_hash).cloned() };
-        match ret {
-            Some(cycles) => Ok(cycles),
+        let cycles = match ret {
+            Some(cycles) => {
+                self.record_txs_verify_cache(1, 0);
+                cycles
+            }
             None => {
+                self.record_txs_verify_cache(0, 1);
                 let cycles = TransactionVerifier::new(
                     &rtx,
                     &self,
@@ -234,11 +232,63 @@ impl<CS: ChainStore> ChainState<CS> {
                 .verify(max_cycles)?;
                 // write cache
                 self.txs_verify_cache.borrow_mut().insert(tx_hash, cycles);
-                Ok(cycles)
+                cycles
             }
+        };
+        Ok(cycles)
+    }
+
+    // the fee estimator tracks the transactions entering the pending or staging pool from the
+    // current tip
+    fn track_tx(&self, rtx: &ResolvedTransaction) {
+        self.fee_estimator
+            .borrow_mut()
+            .track_tx(rtx, self.tip_number());
+    }
+
+    // the transactions leaving the pool without being committed are not recorded as failures
+    fn untrack_txs(&self, tx_hashes: impl Iterator<Item = H256>) {
+        let mut fee_estimator = self.fee_estimator.borrow_mut();
+        for tx_hash in tx_hashes {
+            fee_estimator.untrack_tx(&tx_hash);
         }
     }
 
+    /// Enqueues a transaction verified by the rpc into the pending pool, returns false when the
+    /// transaction is already known
+    pub fn rpc_enqueue_tx(&self, rtx: &ResolvedTransaction, cycles: Cycle) -> bool {
+        let entry = PoolEntry::new(rtx.transaction.clone(), 0, Some(cycles));
+        let enqueued = self.tx_pool.borrow_mut().enqueue_tx(entry);
+        if enqueued {
+            self.track_tx(rtx);
+        }
+        enqueued
+    }
+
+    /// Same as `rpc_enqueue_tx`, but additional register a trace
+    pub fn rpc_trace_tx(&self, rtx: &ResolvedTransaction, cycles: Cycle) -> bool {
+        let entry = PoolEntry::new(rtx.transaction.clone(), 0, Some(cycles));
+        let enqueued = self.tx_pool.borrow_mut().trace_tx(entry);
+        if enqueued {
+            self.track_tx(rtx);
+        }
+        enqueued
+    }
+
+    /// Removes the transaction along with its descendants from the pool, returns the removed
+    /// entries
+    pub fn remove_tx_from_pool(&self, tx_hash: &H256) -> Vec<PoolEntry> {
+        let removed = self.tx_pool.borrow_mut().remove_tx(tx_hash);
+        self.untrack_txs(removed.iter().map(|entry| entry.transaction.hash()));
+        removed
+    }
+
+    /// Removes all the transactions from the pool
+    pub fn clear_tx_pool(&self) {
+        self.tx_pool.borrow_mut().clear();
+        self.fee_estimator.borrow_mut().untrack_all();
+    }
+
     /// Only use on rpc transaction/trace transaction interface
     pub fn rpc_resolve_tx_from_pool(
         &self,
@@ -273,7 +323,9 @@ impl<CS: ChainStore> ChainState<CS> {
             match verify_result {
                 Ok(cycles) => {
                     entry.cycles = Some(cycles);
+                    let rtx = self.resolve_tx_from_pool(&entry.transaction, tx_pool);
                     tx_pool.add_staging(entry);
+                    self.track_tx(&rtx);
                 }
                 Err(TransactionError::Conflict) => {
                     tx_pool
@@ -306,6 +358,7 @@ impl<CS: ChainStore> ChainState<CS> {
                     unknowns.push(input.clone());
                 }
                 CellStatus::Dead => {
+                    self.untrack_txs(Some(tx_hash).into_iter());
                     tx_pool.conflict.insert(short_id, entry);
                     return Err(PoolError::Conflict);
                 }
@@ -319,6 +372,7 @@ impl<CS: ChainStore> ChainState<CS> {
                     unknowns.push(dep.clone());
                 }
                 CellStatus::Dead => {
+                    self.untrack_txs(Some(tx_hash).into_iter());
                     tx_pool.conflict.insert(short_id, entry);
                     return Err(PoolError::Conflict);
                 }
@@ -340,6 +394,7 @@ impl<CS: ChainStore> ChainState<CS> {
         }
         let cycles = entry.cycles.expect("cycles must exists");
         tx_pool.add_staging(entry);
+        self.track_tx(&rtx);
         Ok(StagingTxResult::Normal(cycles))
     }
 
@@ -351,7 +406,8 @@ impl<CS: ChainStore> ChainState<CS> {
         max_cycles: Cycle,
     ) {
         let mut tx_pool = self.tx_pool.borrow_mut();
-        tx_pool.remove_expired(detached_proposal_id);
+        let evicted = tx_pool.remove_expired(detached_proposal_id);
+        self.untrack_txs(evicted.into_iter());
 
         let mut detached = FnvHashSet::default();
         let mut attached = FnvHashSet::default();
@@ -361,8 +417,12 @@ impl<CS: ChainStore> ChainState<CS> {
             detached.extend(blk.transactions().iter().skip(1).cloned())
         }
 
-        for blk in attached_blocks {
-            attached.extend(blk.transactions().iter().skip(1).cloned())
+        {
+            let mut fee_estimator = self.fee_estimator.borrow_mut();
+            for blk in attached_blocks {
+                fee_estimator.process_block(blk.header().number(), blk.transactions().iter());
+                attached.extend(blk.transactions().iter().skip(1).cloned())
+            }
         }
 
         let retain: Vec<&Transaction> = detached.difference(&attached).collect();
@@ -383,7 +443,8 @@ impl<CS: ChainStore> ChainState<CS> {
         }
 
         for tx in &attached {
-            tx_pool.committed(tx);
+            let conflicts = tx_pool.committed(tx);
+            self.untrack_txs(conflicts.iter().map(|entry| entry.transaction.hash()));
         }
 
         for id in self.get_proposal_ids_iter() {
@@ -411,6 +472,33 @@ impl<CS: ChainStore> ChainState<CS> {
         self.txs_verify_cache.get_mut()
     }
 
+    pub fn record_txs_verify_cache(&self, hits: u64, misses: u64) {
+        let (total_hits, total_misses) = self.txs_verify_cache_stats.get();
+        self.txs_verify_cache_stats
+            .set((total_hits + hits, total_misses + misses));
+    }
+
+    /// The total hits and misses of the transaction verification cache
+    pub fn txs_verify_cache_stats(&self) -> (u64, u64) {
+        self.txs_verify_cache_stats.get()
+    }
+
+    /// The fee rate in shannons per 1000 bytes for a transaction to be committed within
+    /// `target` blocks. A transaction is proposed at the earliest in the next block and
+    /// committed at the closest end of the proposal window, the shorter targets are raised to it.
+    pub fn estimate_fee_rate(&self, target: BlockNumber) -> Option<u64> {
+        let min_target = self.consensus.tx_proposal_window().end() + 1;
+        let target = target.max(min_target).min(MAX_TARGET_BLOCKS as BlockNumber);
+        self.fee_estimator
+            .borrow()
+            .estimate_fee_rate(target as usize)
+    }
+
+    /// A copy of the fee estimator statistics to persist
+    pub fn fee_stats(&self) -> FeeStats {
+        self.fee_estimator.borrow().stats().clone()
+    }
+
     pub fn get_proposal_and_staging_txs(
         &self,
         max_prop: usize,
@@ -443,7 +531,7 @@ impl<CS: ChainStore> ChainState<CS> {
 }
 
 pub struct ChainCellSetOverlay<'a, CS> {
-    pub(crate) overlay: CellSetOverlay<'a>,
+    pub(crate) overlay: CellSetOverlay<'a, CS>,
     store: Arc<CS>,
 }
 
@@ -0,0 +1,343 @@
+//! Consistency check of the stored main chain.
+//!
+//! The index, headers, bodies, transaction addresses and block exts are checked against each
+//! other. Only what can be rebuilt from the data still stored is repaired, a block whose
+//! header or body is lost must be synced again.
+
+use crate::error::SharedError;
+use crate::store::{ChainStore, StoreBatch};
+use ckb_core::block::{Block, BlockBuilder};
+use ckb_core::extras::BlockExt;
+use ckb_core::header::{BlockNumber, Header};
+use failure::Fail;
+use numext_fixed_hash::H256;
+
+#[derive(Debug, Clone, PartialEq, Eq, Fail)]
+pub enum Inconsistency {
+    #[fail(display = "the tip header is missing")]
+    MissingTip,
+    #[fail(display = "block {}: the tip {:#x} is not in the index", _0, _1)]
+    TipMismatch(BlockNumber, H256),
+    #[fail(display = "block {}: missing in the index", _0)]
+    MissingIndex(BlockNumber),
+    #[fail(display = "block {}: the number of {:#x} is not indexed", _0, _1)]
+    ReverseIndexMismatch(BlockNumber, H256),
+    #[fail(display = "block {}: header {:#x} is missing", _0, _1)]
+    MissingHeader(BlockNumber, H256),
+    #[fail(
+        display = "block {}: header {:#x} does not match its hash or number",
+        _0, _1
+    )]
+    HeaderMismatch(BlockNumber, H256),
+    #[fail(
+        display = "block {}: the parent of {:#x} is not the previous block",
+        _0, _1
+    )]
+    ParentMismatch(BlockNumber, H256),
+    #[fail(display = "block {}: body of {:#x} is missing", _0, _1)]
+    MissingBody(BlockNumber, H256),
+    #[fail(
+        display = "block {}: {} of {:#x} does not match the header",
+        _0, _2, _1
+    )]
+    RootMismatch(BlockNumber, H256, &'static str),
+    #[fail(display = "block {}: address of transaction {:#x} is wrong", _0, _1)]
+    TransactionAddressMismatch(BlockNumber, H256),
+    #[fail(display = "block {}: ext of {:#x} is missing", _0, _1)]
+    MissingBlockExt(BlockNumber, H256),
+    #[fail(display = "block {}: ext of {:#x} disagrees with its parent", _0, _1)]
+    BlockExtMismatch(BlockNumber, H256),
+    #[fail(display = "block {}: {:#x} is indexed above the tip", _0, _1)]
+    StaleIndex(BlockNumber, H256),
+}
+
+enum Repair {
+    /// Rewrite the index and the transaction addresses of a main chain block
+    Attach(H256),
+    /// Rewrite only the index of a main chain block whose body isn't stored
+    Reindex(BlockNumber, H256),
+    /// Remove an index entry above the tip
+    Detach(H256),
+    InsertExt(H256, BlockExt),
+}
+
+pub struct CheckReport {
+    inconsistencies: Vec<Inconsistency>,
+    repairs: Vec<Repair>,
+}
+
+impl CheckReport {
+    pub fn inconsistencies(&self) -> &[Inconsistency] {
+        &self.inconsistencies
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies.is_empty()
+    }
+
+    /// The number of the repairs, some of the inconsistencies can't be repaired
+    pub fn repairs_count(&self) -> usize {
+        self.repairs.len()
+    }
+
+    /// Apply the repairs in a single batch, run the check again to find what remains.
+    pub fn repair<CS: ChainStore>(&self, store: &CS) -> Result<(), SharedError> {
+        let mut batch = store.new_batch().map_err(SharedError::DB)?;
+        for repair in &self.repairs {
+            match repair {
+                Repair::Attach(hash) => {
+                    let block = store
+                        .get_block(hash)
+                        .ok_or_else(|| SharedError::MissingBlockBody(hash.clone()))?;
+                    batch.attach_block(&block).map_err(SharedError::DB)?;
+                }
+                Repair::Reindex(number, hash) => {
+                    batch
+                        .insert_block_index(*number, hash)
+                        .map_err(SharedError::DB)?;
+                }
+                Repair::Detach(hash) => {
+                    // the transactions may have been attached again to the main chain
+                    let header = store.get_header(hash).expect("checked header");
+                    let transactions = store
+                        .get_block_body(hash)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter(|tx| {
+                            store
+                                .get_transaction_address(&tx.hash())
+                                .map_or(false, |address| &address.block_hash == hash)
+                        })
+                        .collect();
+                    let block = BlockBuilder::default()
+                        .header(header)
+                        .transactions(transactions)
+                        .build();
+                    batch.detach_block(&block).map_err(SharedError::DB)?;
+                }
+                Repair::InsertExt(hash, ext) => {
+                    batch.insert_block_ext(hash, ext).map_err(SharedError::DB)?;
+                }
+            }
+        }
+        batch.commit().map_err(SharedError::DB)
+    }
+}
+
+pub struct ConsistencyChecker<'a, CS> {
+    store: &'a CS,
+    inconsistencies: Vec<Inconsistency>,
+    repairs: Vec<Repair>,
+}
+
+impl<'a, CS: ChainStore> ConsistencyChecker<'a, CS> {
+    pub fn new(store: &'a CS) -> Self {
+        ConsistencyChecker {
+            store,
+            inconsistencies: Vec::new(),
+            repairs: Vec::new(),
+        }
+    }
+
+    /// Walk the main chain, `progress` is called with the number of checked and total blocks.
+    pub fn check(mut self, progress: &dyn Fn(u64, u64)) -> CheckReport {
+        let tip = match self.store.get_tip_header() {
+            Some(tip) => tip,
+            None => {
+                // an empty store has no genesis either
+                if self.store.get_block_hash(0).is_some() {
+                    self.inconsistencies.push(Inconsistency::MissingTip);
+                }
+                return self.into_report();
+            }
+        };
+        let tip_number = tip.number();
+        let pruned_number = self.store.get_pruned_number().unwrap_or(0);
+
+        // the hash and the ext of the previous block, `None` after a broken link
+        let mut parent: Option<(H256, BlockExt)> = None;
+        for number in 0..=tip_number {
+            parent = self.check_block(number, parent, pruned_number);
+            progress(number + 1, tip_number + 1);
+        }
+
+        match self.store.get_block_hash(tip_number) {
+            Some(ref hash) if hash == &tip.hash() => {}
+            _ => self
+                .inconsistencies
+                .push(Inconsistency::TipMismatch(tip_number, tip.hash())),
+        }
+
+        let mut number = tip_number + 1;
+        while let Some(hash) = self.store.get_block_hash(number) {
+            if self.store.get_header(&hash).is_some() {
+                // detached before the main chain blocks are attached again
+                self.repairs.insert(0, Repair::Detach(hash.clone()));
+            }
+            self.inconsistencies
+                .push(Inconsistency::StaleIndex(number, hash));
+            number += 1;
+        }
+
+        self.into_report()
+    }
+
+    fn check_block(
+        &mut self,
+        number: BlockNumber,
+        parent: Option<(H256, BlockExt)>,
+        pruned_number: BlockNumber,
+    ) -> Option<(H256, BlockExt)> {
+        let hash = match self.store.get_block_hash(number) {
+            Some(hash) => hash,
+            None => {
+                self.inconsistencies
+                    .push(Inconsistency::MissingIndex(number));
+                return None;
+            }
+        };
+        let header = match self.store.get_header(&hash) {
+            Some(header) => header,
+            None => {
+                self.inconsistencies
+                    .push(Inconsistency::MissingHeader(number, hash));
+                return None;
+            }
+        };
+        if header.hash() != hash || header.number() != number {
+            self.inconsistencies
+                .push(Inconsistency::HeaderMismatch(number, hash));
+            return None;
+        }
+        if let Some((ref parent_hash, _)) = parent {
+            if header.parent_hash() != parent_hash {
+                self.inconsistencies
+                    .push(Inconsistency::ParentMismatch(number, hash.clone()));
+            }
+        }
+
+        let mut reindex = false;
+        let reverse_mismatched = self.store.get_block_number(&hash) != Some(number);
+        if reverse_mismatched {
+            self.inconsistencies
+                .push(Inconsistency::ReverseIndexMismatch(number, hash.clone()));
+        }
+        // genesis is never pruned
+        let body_stored = if number == 0 || number > pruned_number {
+            match self.store.get_block(&hash) {
+                Some(block) => {
+                    reindex = self.check_body(&block);
+                    true
+                }
+                None => {
+                    self.inconsistencies
+                        .push(Inconsistency::MissingBody(number, hash.clone()));
+                    false
+                }
+            }
+        } else {
+            false
+        };
+        if body_stored && (reindex || reverse_mismatched) {
+            self.repairs.push(Repair::Attach(hash.clone()));
+        } else if reverse_mismatched {
+            self.repairs.push(Repair::Reindex(number, hash.clone()));
+        }
+
+        let ext = self.check_ext(&header, parent.as_ref().map(|(_, ext)| ext))?;
+        Some((hash, ext))
+    }
+
+    // returns whether the transaction addresses must be rewritten
+    fn check_body(&mut self, block: &Block) -> bool {
+        let header = block.header();
+        let number = header.number();
+        let roots = [
+            (
+                "transactions root",
+                header.transactions_root() == &block.cal_transactions_root(),
+            ),
+            (
+                "witnesses root",
+                header.witnesses_root() == &block.cal_witnesses_root(),
+            ),
+            (
+                "proposals root",
+                header.proposals_root() == &block.cal_proposals_root(),
+            ),
+            (
+                "uncles hash",
+                header.uncles_hash() == &block.cal_uncles_hash(),
+            ),
+        ];
+        for &(name, matched) in roots.iter() {
+            if !matched {
+                self.inconsistencies
+                    .push(Inconsistency::RootMismatch(number, header.hash(), name));
+            }
+        }
+
+        let mut mismatched = false;
+        for tx in block.transactions() {
+            let tx_hash = tx.hash();
+            let matched = self
+                .store
+                .get_transaction_address(&tx_hash)
+                .map_or(false, |address| address.block_hash == header.hash())
+                && self.store.get_transaction(&tx_hash).as_ref() == Some(tx);
+            if !matched {
+                self.inconsistencies
+                    .push(Inconsistency::TransactionAddressMismatch(number, tx_hash));
+                mismatched = true;
+            }
+        }
+        mismatched
+    }
+
+    // returns the expected ext, `None` if it can't be checked
+    fn check_ext(&mut self, header: &Header, parent_ext: Option<&BlockExt>) -> Option<BlockExt> {
+        let number = header.number();
+        let hash = header.hash();
+        let stored = self.store.get_block_ext(&hash);
+        let (total_difficulty, total_uncles_count) = match parent_ext {
+            Some(parent_ext) => (
+                &parent_ext.total_difficulty + header.difficulty(),
+                parent_ext.total_uncles_count + u64::from(header.uncles_count()),
+            ),
+            None if number == 0 => (header.difficulty().clone(), 0),
+            // the chain is broken, resume the check from the stored ext
+            None => return stored,
+        };
+
+        let matched = stored.as_ref().map_or(false, |ext| {
+            ext.total_difficulty == total_difficulty && ext.total_uncles_count == total_uncles_count
+        });
+        if matched {
+            return stored;
+        }
+
+        let inconsistency = if stored.is_some() {
+            Inconsistency::BlockExtMismatch(number, hash.clone())
+        } else {
+            Inconsistency::MissingBlockExt(number, hash.clone())
+        };
+        self.inconsistencies.push(inconsistency);
+        let ext = BlockExt {
+            received_at: stored
+                .as_ref()
+                .map_or(header.timestamp(), |ext| ext.received_at),
+            total_difficulty,
+            total_uncles_count,
+            txs_verified: stored.and_then(|ext| ext.txs_verified),
+        };
+        self.repairs.push(Repair::InsertExt(hash, ext.clone()));
+        Some(ext)
+    }
+
+    fn into_report(self) -> CheckReport {
+        CheckReport {
+            inconsistencies: self.inconsistencies,
+            repairs: self.repairs,
+        }
+    }
+}
@@ -1,5 +1,7 @@
+use ckb_core::BlockNumber;
 use ckb_db::Error as DBError;
 use failure::Fail;
+use numext_fixed_hash::H256;
 
 #[derive(Debug, PartialEq, Clone, Eq, Fail)]
 pub enum SharedError {
@@ -7,6 +9,26 @@ pub enum SharedError {
     InvalidTransaction(String),
     #[fail(display = "InvalidParentBlock")]
     InvalidParentBlock,
+    #[fail(display = "Fork conflicts with checkpoint {}", _0)]
+    CheckpointConflict(BlockNumber),
+    #[fail(display = "Fork detaches block {} whose body has been pruned", _0)]
+    PrunedFork(BlockNumber),
     #[fail(display = "DB error: {}", _0)]
     DB(DBError),
+    #[fail(
+        display = "Database version {} is older than {}, run `ckb migrate` to upgrade it",
+        _0, _1
+    )]
+    OutdatedDBVersion(u32, u32),
+    #[fail(
+        display = "Database version {} is newer than {} supported by this binary",
+        _0, _1
+    )]
+    UnsupportedDBVersion(u32, u32),
+    #[fail(display = "Snapshot does not match its manifest: {}", _0)]
+    InvalidSnapshot(String),
+    #[fail(display = "Body of block {:#x} is missing", _0)]
+    MissingBlockBody(H256),
+    #[fail(display = "Invalid rollback target: {}", _0)]
+    InvalidRollbackTarget(String),
 }
@@ -0,0 +1,356 @@
+//! Estimates the fee rate for a transaction to be committed within a number of blocks.
+//!
+//! The transactions are tracked from the tip they entered the pool at until they are committed,
+//! and counted in the bucket of their fee rate. A bucket passes a target when most of its
+//! transactions were committed within the target, the estimate is the average fee rate of the
+//! cheapest passing buckets. The counts decay with every block so recent blocks weigh more.
+
+use ckb_core::cell::ResolvedTransaction;
+use ckb_core::header::BlockNumber;
+use ckb_core::transaction::Transaction;
+use ckb_core::Capacity;
+use fnv::FnvHashMap;
+use numext_fixed_hash::H256;
+use serde_derive::{Deserialize, Serialize};
+
+/// Transactions not committed within this many blocks are counted as failed for every target
+pub const MAX_TARGET_BLOCKS: usize = 48;
+
+// The bucket bounds grow exponentially, in shannons per 1000 bytes
+const MIN_BUCKET_FEE_RATE: f64 = 1_000f64;
+const MAX_BUCKET_FEE_RATE: f64 = 1e11;
+const BUCKET_SPACING: f64 = 1.25;
+// The counts halve in about 350 blocks
+const DECAY: f64 = 0.998;
+// The decayed transactions a range of buckets needs before it is judged, fewer ones are mostly
+// noise
+const SUFFICIENT_TXS: f64 = 20.0;
+// The share of transactions committed within the target for a range to pass
+const SUCCESS_RATIO: f64 = 0.85;
+
+/// The decayed statistics of the committed transactions, persisted in the store
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FeeStats {
+    /// The lower fee rate bound of every bucket
+    buckets: Vec<f64>,
+    /// The transactions of every bucket which were committed or failed
+    txs: Vec<f64>,
+    /// The transactions committed within `target` blocks, indexed by `target - 1` and bucket
+    committed: Vec<Vec<f64>>,
+    /// The sum of the fee rates of every bucket
+    fee_rates: Vec<f64>,
+}
+
+impl Default for FeeStats {
+    fn default() -> Self {
+        let mut buckets = Vec::new();
+        let mut bound = MIN_BUCKET_FEE_RATE;
+        while bound <= MAX_BUCKET_FEE_RATE {
+            buckets.push(bound);
+            bound *= BUCKET_SPACING;
+        }
+        let len = buckets.len();
+        FeeStats {
+            buckets,
+            txs: vec![0f64; len],
+            committed: vec![vec![0f64; len]; MAX_TARGET_BLOCKS],
+            fee_rates: vec![0f64; len],
+        }
+    }
+}
+
+impl FeeStats {
+    fn bucket(&self, fee_rate: f64) -> usize {
+        self.buckets
+            .iter()
+            .rposition(|bound| fee_rate >= *bound)
+            .unwrap_or(0)
+    }
+
+    fn decay(&mut self) {
+        let counts = vec![&mut self.txs, &mut self.fee_rates];
+        for counts in self.committed.iter_mut().chain(counts) {
+            for count in counts.iter_mut() {
+                *count *= DECAY;
+            }
+        }
+    }
+
+    // `blocks` is None when the transaction failed to be committed within the max target
+    fn record(&mut self, bucket: usize, fee_rate: f64, blocks: Option<usize>) {
+        self.txs[bucket] += 1f64;
+        self.fee_rates[bucket] += fee_rate;
+        if let Some(blocks) = blocks {
+            for committed in &mut self.committed[blocks.max(1) - 1..] {
+                committed[bucket] += 1f64;
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct TrackedTx {
+    entered_at: BlockNumber,
+    fee_rate: f64,
+    bucket: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FeeEstimator {
+    stats: FeeStats,
+    tracked: FnvHashMap<H256, TrackedTx>,
+}
+
+impl FeeEstimator {
+    /// Statistics saved with a different bucket layout are discarded
+    pub fn new(stats: Option<FeeStats>) -> Self {
+        let default = FeeStats::default();
+        let stats = match stats {
+            Some(ref stats)
+                if stats.buckets == default.buckets
+                    && stats.committed.len() == MAX_TARGET_BLOCKS =>
+            {
+                stats.clone()
+            }
+            _ => default,
+        };
+        FeeEstimator {
+            stats,
+            tracked: FnvHashMap::default(),
+        }
+    }
+
+    pub fn stats(&self) -> &FeeStats {
+        &self.stats
+    }
+
+    /// Starts tracking a transaction entering the pool when the tip is `tip_number`, the
+    /// transactions already tracked and those whose fee is unknown are ignored.
+    pub fn track_tx(&mut self, rtx: &ResolvedTransaction, tip_number: BlockNumber) {
+        let tx_hash = rtx.transaction.hash();
+        if self.tracked.contains_key(&tx_hash) {
+            return;
+        }
+        if let Some(fee_rate) = fee_rate(rtx) {
+            let bucket = self.stats.bucket(fee_rate);
+            self.tracked.insert(
+                tx_hash,
+                TrackedTx {
+                    entered_at: tip_number,
+                    fee_rate,
+                    bucket,
+                },
+            );
+        }
+    }
+
+    /// Stops tracking a transaction which left the pool without being committed
+    pub fn untrack_tx(&mut self, tx_hash: &H256) {
+        self.tracked.remove(tx_hash);
+    }
+
+    /// Stops tracking all the transactions, when the pool is cleared
+    pub fn untrack_all(&mut self) {
+        self.tracked.clear();
+    }
+
+    /// Records the tracked transactions committed in a new main chain block
+    pub fn process_block<'a>(
+        &mut self,
+        number: BlockNumber,
+        txs: impl Iterator<Item = &'a Transaction>,
+    ) {
+        self.stats.decay();
+        for tx in txs {
+            if let Some(tracked) = self.tracked.remove(&tx.hash()) {
+                let blocks = number.saturating_sub(tracked.entered_at) as usize;
+                let blocks = if blocks <= MAX_TARGET_BLOCKS {
+                    Some(blocks)
+                } else {
+                    None
+                };
+                self.stats.record(tracked.bucket, tracked.fee_rate, blocks);
+            }
+        }
+
+        let failed: Vec<H256> = self
+            .tracked
+            .iter()
+            .filter(|(_, tracked)| {
+                number.saturating_sub(tracked.entered_at) as usize > MAX_TARGET_BLOCKS
+            })
+            .map(|(tx_hash, _)| tx_hash.clone())
+            .collect();
+        for tx_hash in failed {
+            if let Some(tracked) = self.tracked.remove(&tx_hash) {
+                self.stats.record(tracked.bucket, tracked.fee_rate, None);
+            }
+        }
+    }
+
+    /// The fee rate in shannons per 1000 bytes for a transaction to be committed within
+    /// `target` blocks, None when there are not enough transactions to tell.
+    ///
+    /// Starting from the most expensive bucket, the buckets are grouped into ranges of enough
+    /// transactions, and the cheapest range of those passing consecutively is picked.
+    pub fn estimate_fee_rate(&self, target: usize) -> Option<u64> {
+        let target = target.max(1).min(MAX_TARGET_BLOCKS);
+        let committed = &self.stats.committed[target - 1];
+        let mut passed = None;
+        let (mut txs, mut committed_txs, mut fee_rates) = (0f64, 0f64, 0f64);
+        for bucket in (0..self.stats.buckets.len()).rev() {
+            txs += self.stats.txs[bucket];
+            committed_txs += committed[bucket];
+            fee_rates += self.stats.fee_rates[bucket];
+            if txs >= SUFFICIENT_TXS {
+                if committed_txs / txs < SUCCESS_RATIO {
+                    break;
+                }
+                passed = Some(fee_rates / txs);
+                txs = 0f64;
+                committed_txs = 0f64;
+                fee_rates = 0f64;
+            }
+        }
+        passed.map(|fee_rate| fee_rate.ceil() as u64)
+    }
+}
+
+// Shannons per 1000 bytes, None when an input is not a live output
+fn fee_rate(rtx: &ResolvedTransaction) -> Option<f64> {
+    let inputs = rtx
+        .input_cells
+        .iter()
+        .try_fold(Capacity::zero(), |sum, cell| {
+            cell.get_live_output()
+                .and_then(|meta| sum.safe_add(meta.capacity()).ok())
+        })?;
+    let outputs = rtx.transaction.outputs_capacity().ok()?;
+    let fee = inputs.safe_sub(outputs).ok()?;
+    let size = bincode::serialized_size(&rtx.transaction).ok()?;
+    Some(fee.as_u64() as f64 * 1_000f64 / size.max(1) as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ckb_core::cell::CellStatus;
+    use ckb_core::script::Script;
+    use ckb_core::transaction::{CellInput, CellOutput, OutPoint, TransactionBuilder};
+
+    fn resolved_tx(index: u32, fee: u64) -> ResolvedTransaction {
+        let capacity = Capacity::shannons(100_000_000_000);
+        let transaction = TransactionBuilder::default()
+            .input(CellInput::new(
+                OutPoint::new(H256::zero(), index),
+                0,
+                Default::default(),
+            ))
+            .output(CellOutput::new(
+                Capacity::shannons(capacity.as_u64() - fee),
+                Vec::new(),
+                Script::default(),
+                None,
+            ))
+            .build();
+        ResolvedTransaction {
+            transaction,
+            dep_cells: Vec::new(),
+            input_cells: vec![CellStatus::live_output(
+                CellOutput::new(capacity, Vec::new(), Script::default(), None),
+                None,
+                false,
+            )],
+        }
+    }
+
+    #[test]
+    fn test_estimate_fee_rate() {
+        let mut estimator = FeeEstimator::new(None);
+        assert_eq!(estimator.estimate_fee_rate(3), None);
+
+        // the expensive transactions are committed after 3 blocks, the cheap ones after 10
+        let mut index = 0;
+        for round in 0..10 {
+            let entered_at = round * 20;
+            let expensive: Vec<_> = (0..5)
+                .map(|_| {
+                    index += 1;
+                    resolved_tx(index, 1_000_000)
+                })
+                .collect();
+            let cheap: Vec<_> = (0..5)
+                .map(|_| {
+                    index += 1;
+                    resolved_tx(index, 10_000)
+                })
+                .collect();
+            for rtx in expensive.iter().chain(cheap.iter()) {
+                estimator.track_tx(rtx, entered_at);
+            }
+            estimator.process_block(entered_at + 3, expensive.iter().map(|rtx| &rtx.transaction));
+            estimator.process_block(entered_at + 10, cheap.iter().map(|rtx| &rtx.transaction));
+        }
+
+        let fast = estimator.estimate_fee_rate(3).expect("enough txs");
+        let slow = estimator.estimate_fee_rate(10).expect("enough txs");
+        let expected = |fee| fee_rate(&resolved_tx(0, fee)).expect("live inputs");
+        assert!((fast as f64 - expected(1_000_000)).abs() <= 1f64);
+        assert!((slow as f64 - expected(10_000)).abs() <= 1f64);
+
+        let restored = FeeEstimator::new(Some(estimator.stats().clone()));
+        assert_eq!(restored.estimate_fee_rate(3), Some(fast));
+    }
+
+    #[test]
+    fn test_evicted_txs_ignored() {
+        let mut estimator = FeeEstimator::new(None);
+        let mut index = 0;
+        let mut committed = |estimator: &mut FeeEstimator, entered_at| {
+            let rtxs: Vec<_> = (0..5)
+                .map(|_| {
+                    index += 1;
+                    resolved_tx(index, 1_000_000)
+                })
+                .collect();
+            for rtx in &rtxs {
+                estimator.track_tx(rtx, entered_at);
+            }
+            estimator.process_block(entered_at + 3, rtxs.iter().map(|rtx| &rtx.transaction));
+        };
+        for round in 0..10 {
+            committed(&mut estimator, round * 20);
+        }
+        let fast = estimator.estimate_fee_rate(3).expect("enough txs");
+
+        // the evicted transactions are never committed, they must not count as failures
+        let evicted: Vec<_> = (0..30).map(|i| resolved_tx(1_000 + i, 1_000_000)).collect();
+        for rtx in &evicted {
+            estimator.track_tx(rtx, 200);
+        }
+        for rtx in &evicted {
+            estimator.untrack_tx(&rtx.transaction.hash());
+        }
+        estimator.process_block(
+            200 + MAX_TARGET_BLOCKS as u64 + 1,
+            None::<&Transaction>.into_iter(),
+        );
+        assert!(estimator.tracked.is_empty());
+        let estimated = estimator.estimate_fee_rate(3).expect("enough txs");
+        assert!((estimated as f64 - fast as f64).abs() <= 1f64);
+    }
+
+    #[test]
+    fn test_uncommitted_txs_fail() {
+        let mut estimator = FeeEstimator::new(None);
+        for index in 0..30 {
+            estimator.track_tx(&resolved_tx(index, 10_000), 0);
+        }
+        estimator.process_block(
+            MAX_TARGET_BLOCKS as u64 + 1,
+            None::<&Transaction>.into_iter(),
+        );
+        assert!(estimator.tracked.is_empty());
+        assert_eq!(estimator.estimate_fee_rate(MAX_TARGET_BLOCKS), None);
+    }
+}
@@ -9,9 +9,14 @@
 
 pub mod cell_set;
 pub mod chain_state;
+pub mod consistency;
 pub mod error;
+pub mod fee_estimator;
 mod flat_serializer;
+pub mod migration;
+pub mod rollback;
 pub mod shared;
+pub mod snapshot;
 pub mod store;
 pub mod tx_pool;
 mod tx_proposal_table;
@@ -21,7 +26,7 @@ mod tests;
 
 use ckb_db::Col;
 
-pub const COLUMNS: u32 = 9;
+pub const COLUMNS: u32 = 13;
 pub const COLUMN_INDEX: Col = 0;
 pub const COLUMN_BLOCK_HEADER: Col = 1;
 pub const COLUMN_BLOCK_BODY: Col = 2;
@@ -31,3 +36,24 @@ pub const COLUMN_TRANSACTION_ADDR: Col = 5;
 pub const COLUMN_EXT: Col = 6;
 pub const COLUMN_BLOCK_TRANSACTION_ADDRESSES: Col = 7;
 pub const COLUMN_BLOCK_PROPOSAL_IDS: Col = 8;
+pub const COLUMN_CELL_SET: Col = 9;
+pub const COLUMN_PRUNED_TRANSACTION: Col = 10;
+pub const COLUMN_CELL_TYPE_INDEX: Col = 11;
+pub const COLUMN_CELL_DATA_INDEX: Col = 12;
+
+/// The names of the columns, as used by the `[db.columns.<name>]` config sections
+pub const COLUMN_NAMES: [&str; COLUMNS as usize] = [
+    "index",
+    "block_header",
+    "block_body",
+    "block_uncle",
+    "meta",
+    "transaction_addr",
+    "ext",
+    "block_transaction_addresses",
+    "block_proposal_ids",
+    "cell_set",
+    "pruned_transaction",
+    "cell_type_index",
+    "cell_data_index",
+];
@@ -0,0 +1,212 @@
+//! Database schema versioning.
+//!
+//! The schema version is stored in `COLUMN_META`. A binary only opens a store whose version
+//! equals `DB_VERSION`, older stores are upgraded by `ckb migrate` which applies the
+//! registered migrations in order.
+
+use crate::cell_set::{CellSet, CellSetDiff, CELL_SET_CACHE_SIZE};
+use crate::error::SharedError;
+use crate::store::{ChainStore, StoreBatch};
+use std::sync::Arc;
+
+/// The schema version written by this binary
+pub const DB_VERSION: u32 = 3;
+
+/// The version of a store which has a tip but no version key, written before versioning.
+pub const LEGACY_DB_VERSION: u32 = 0;
+
+pub trait Migration<CS>: Send + Sync {
+    /// The schema version of the store once the migration is applied
+    fn version(&self) -> u32;
+
+    fn description(&self) -> &'static str;
+
+    /// Rewrite the store, `progress` is called with the number of done and total steps.
+    /// A migration must be safe to restart from scratch if it is interrupted.
+    fn migrate(&self, store: &Arc<CS>, progress: &dyn Fn(u64, u64)) -> Result<(), SharedError>;
+}
+
+pub struct Migrations<CS> {
+    migrations: Vec<Box<dyn Migration<CS>>>,
+}
+
+impl<CS: ChainStore> Default for Migrations<CS> {
+    fn default() -> Self {
+        let mut migrations = Migrations::new();
+        migrations.add_migration(Box::new(CellSetMigration));
+        migrations.add_migration(Box::new(PrunedTransactionMigration));
+        migrations.add_migration(Box::new(CellIndexMigration));
+        migrations
+    }
+}
+
+impl<CS: ChainStore> Migrations<CS> {
+    pub fn new() -> Self {
+        Migrations {
+            migrations: Vec::new(),
+        }
+    }
+
+    pub fn add_migration(&mut self, migration: Box<dyn Migration<CS>>) {
+        assert!(
+            migration.version() > self.latest_version(),
+            "migrations must be registered in version order"
+        );
+        self.migrations.push(migration);
+    }
+
+    pub fn latest_version(&self) -> u32 {
+        self.migrations
+            .last()
+            .map_or(LEGACY_DB_VERSION, |migration| migration.version())
+    }
+
+    /// Returns `None` for an empty store.
+    pub fn db_version(&self, store: &CS) -> Option<u32> {
+        store
+            .get_db_version()
+            .or_else(|| store.get_tip_header().map(|_| LEGACY_DB_VERSION))
+    }
+
+    pub fn check(&self, store: &CS) -> Result<(), SharedError> {
+        match self.db_version(store) {
+            Some(version) if version < self.latest_version() => Err(
+                SharedError::OutdatedDBVersion(version, self.latest_version()),
+            ),
+            Some(version) if version > self.latest_version() => Err(
+                SharedError::UnsupportedDBVersion(version, self.latest_version()),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Apply the pending migrations, the version is saved after each one.
+    /// Returns the versions before and after the migrations.
+    pub fn migrate(
+        &self,
+        store: &Arc<CS>,
+        progress: &dyn Fn(&dyn Migration<CS>, u64, u64),
+    ) -> Result<(u32, u32), SharedError> {
+        let from = match self.db_version(store) {
+            Some(version) => version,
+            None => return Ok((self.latest_version(), self.latest_version())),
+        };
+        if from > self.latest_version() {
+            return Err(SharedError::UnsupportedDBVersion(
+                from,
+                self.latest_version(),
+            ));
+        }
+
+        for migration in self
+            .migrations
+            .iter()
+            .filter(|migration| migration.version() > from)
+        {
+            migration.migrate(store, &|done, total| {
+                progress(migration.as_ref(), done, total)
+            })?;
+            let mut batch = store.new_batch().map_err(SharedError::DB)?;
+            batch
+                .insert_db_version(migration.version())
+                .map_err(SharedError::DB)?;
+            batch.commit().map_err(SharedError::DB)?;
+        }
+
+        Ok((from, self.latest_version()))
+    }
+}
+
+/// Version 1 persists the live cell set in `COLUMN_CELL_SET`, it is rebuilt by replaying the
+/// main chain.
+pub struct CellSetMigration;
+
+impl<CS: ChainStore> Migration<CS> for CellSetMigration {
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn description(&self) -> &'static str {
+        "persist the live cell set"
+    }
+
+    fn migrate(&self, store: &Arc<CS>, progress: &dyn Fn(u64, u64)) -> Result<(), SharedError> {
+        let tip_number = match store.get_tip_header() {
+            Some(header) => header.number(),
+            None => return Ok(()),
+        };
+
+        let mut cell_set = CellSet::new(store, CELL_SET_CACHE_SIZE);
+        for number in 0..=tip_number {
+            let block = store
+                .get_block_hash(number)
+                .and_then(|hash| store.get_block(&hash))
+                .expect("main chain block stored");
+            let mut diff = CellSetDiff::default();
+            diff.push_new(&block);
+            let changes = cell_set.new_overlay(&diff).into_changes();
+
+            let mut batch = store.new_batch().map_err(SharedError::DB)?;
+            batch.update_cell_set(&changes).map_err(SharedError::DB)?;
+            batch.commit().map_err(SharedError::DB)?;
+            cell_set.update(changes);
+            progress(number + 1, tip_number + 1);
+        }
+        Ok(())
+    }
+}
+
+/// Version 2 may prune the block bodies and keep their live transactions in
+/// `COLUMN_PRUNED_TRANSACTION`. Nothing is rewritten, older binaries are refused because they
+/// expect every block body to be stored.
+pub struct PrunedTransactionMigration;
+
+impl<CS: ChainStore> Migration<CS> for PrunedTransactionMigration {
+    fn version(&self) -> u32 {
+        2
+    }
+
+    fn description(&self) -> &'static str {
+        "allow pruning the block bodies"
+    }
+
+    fn migrate(&self, _store: &Arc<CS>, progress: &dyn Fn(u64, u64)) -> Result<(), SharedError> {
+        progress(1, 1);
+        Ok(())
+    }
+}
+
+/// Version 3 indexes the cells by type script hash in `COLUMN_CELL_TYPE_INDEX` and by data hash
+/// in `COLUMN_CELL_DATA_INDEX`, the main chain blocks are indexed again. The pruned blocks are
+/// skipped, their cells are missing in the indexes.
+pub struct CellIndexMigration;
+
+impl<CS: ChainStore> Migration<CS> for CellIndexMigration {
+    fn version(&self) -> u32 {
+        3
+    }
+
+    fn description(&self) -> &'static str {
+        "index the cells by type script and data hash"
+    }
+
+    fn migrate(&self, store: &Arc<CS>, progress: &dyn Fn(u64, u64)) -> Result<(), SharedError> {
+        let tip_number = match store.get_tip_header() {
+            Some(header) => header.number(),
+            None => return Ok(()),
+        };
+
+        for number in 0..=tip_number {
+            let block = store
+                .get_block_hash(number)
+                .and_then(|hash| store.get_block(&hash));
+            if let Some(block) = block {
+                let mut batch = store.new_batch().map_err(SharedError::DB)?;
+                batch.index_cells(&block).map_err(SharedError::DB)?;
+                batch.commit().map_err(SharedError::DB)?;
+            }
+            progress(number + 1, tip_number + 1);
+        }
+        Ok(())
+    }
+}
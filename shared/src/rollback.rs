@@ -0,0 +1,71 @@
+//! Move the tip of the stored main chain back to an earlier block.
+//!
+//! The blocks above the target are detached one by one, each in its own batch, so an
+//! interrupted rollback leaves a consistent store with an intermediate tip. The detached blocks
+//! are kept as fork blocks, their exts are marked unverified so they are verified again if they
+//! are attached to the main chain later.
+
+use crate::cell_set::{CellSet, CellSetDiff, CELL_SET_CACHE_SIZE};
+use crate::error::SharedError;
+use crate::store::{ChainStore, StoreBatch};
+use ckb_core::header::BlockNumber;
+use std::sync::Arc;
+
+/// Detach the main chain blocks above `target`, `progress` is called with the number of
+/// detached and total blocks.
+pub fn rollback<CS: ChainStore>(
+    store: &Arc<CS>,
+    target: BlockNumber,
+    progress: &dyn Fn(u64, u64),
+) -> Result<(), SharedError> {
+    let tip = store
+        .get_tip_header()
+        .ok_or_else(|| SharedError::InvalidRollbackTarget("the store is empty".to_owned()))?;
+    if target > tip.number() {
+        return Err(SharedError::InvalidRollbackTarget(format!(
+            "block {} is above the tip {}",
+            target,
+            tip.number()
+        )));
+    }
+    // the bodies are needed to revert the cell set
+    if let Some(pruned_number) = store.get_pruned_number() {
+        if target < pruned_number {
+            return Err(SharedError::InvalidRollbackTarget(format!(
+                "the blocks up to {} are pruned",
+                pruned_number
+            )));
+        }
+    }
+
+    let total = tip.number() - target;
+    let mut cell_set = CellSet::new(store, CELL_SET_CACHE_SIZE);
+    let mut header = tip;
+    while header.number() > target {
+        let hash = header.hash();
+        let block = store.get_block(&hash).expect("main chain block stored");
+        let parent = store
+            .get_header(header.parent_hash())
+            .expect("main chain parent stored");
+        let mut diff = CellSetDiff::default();
+        diff.push_old(&block);
+        let changes = cell_set.new_overlay(&diff).into_changes();
+
+        let mut batch = store.new_batch().map_err(SharedError::DB)?;
+        batch.update_cell_set(&changes).map_err(SharedError::DB)?;
+        batch.detach_block(&block).map_err(SharedError::DB)?;
+        if let Some(mut ext) = store.get_block_ext(&hash) {
+            ext.txs_verified = None;
+            batch
+                .insert_block_ext(&hash, &ext)
+                .map_err(SharedError::DB)?;
+        }
+        batch.insert_tip_header(&parent).map_err(SharedError::DB)?;
+        batch.commit().map_err(SharedError::DB)?;
+        cell_set.update(changes);
+
+        progress(total - (parent.number() - target), total);
+        header = parent;
+    }
+    Ok(())
+}
@@ -1,17 +1,25 @@
 use crate::chain_state::ChainState;
+use crate::error::SharedError;
+use crate::migration::Migrations;
 use crate::store::ChainKVStore;
 use crate::store::ChainStore;
 use crate::tx_pool::TxPoolConfig;
-use crate::{COLUMNS, COLUMN_BLOCK_HEADER};
+use crate::{
+    COLUMNS, COLUMN_BLOCK_BODY, COLUMN_BLOCK_HEADER, COLUMN_CELL_SET, COLUMN_INDEX, COLUMN_NAMES,
+    COLUMN_TRANSACTION_ADDR,
+};
 use ckb_chain_spec::consensus::Consensus;
 use ckb_core::block::Block;
 use ckb_core::extras::BlockExt;
 use ckb_core::header::{BlockNumber, Header};
 use ckb_core::transaction::{Capacity, ProposalShortId, Transaction};
 use ckb_core::uncle::UncleBlock;
-use ckb_db::{CacheDB, DBConfig, KeyValueDB, MemoryKeyValueDB, RocksDB};
+use ckb_db::{
+    CacheDB, Col, ColumnConfig, Compression, DBConfig, KeyValueDB, MemoryKeyValueDB, RocksDB,
+};
 use ckb_traits::ChainProvider;
 use ckb_util::Mutex;
+use log::warn;
 use numext_fixed_hash::H256;
 use numext_fixed_uint::U256;
 use std::sync::Arc;
@@ -21,6 +29,7 @@ pub struct Shared<CS> {
     store: Arc<CS>,
     chain_state: Arc<Mutex<ChainState<CS>>>,
     consensus: Arc<Consensus>,
+    prune_depth: Option<BlockNumber>,
 }
 
 // https://github.com/rust-lang/rust/issues/40754
@@ -30,12 +39,18 @@ impl<CS: ChainStore> ::std::clone::Clone for Shared<CS> {
             store: Arc::clone(&self.store),
             chain_state: Arc::clone(&self.chain_state),
             consensus: Arc::clone(&self.consensus),
+            prune_depth: self.prune_depth,
         }
     }
 }
 
 impl<CS: ChainStore> Shared<CS> {
-    pub fn new(store: CS, consensus: Consensus, tx_pool_config: TxPoolConfig) -> Self {
+    pub fn new(
+        store: CS,
+        consensus: Consensus,
+        tx_pool_config: TxPoolConfig,
+        prune_depth: Option<BlockNumber>,
+    ) -> Self {
         let store = Arc::new(store);
         let consensus = Arc::new(consensus);
         let chain_state = Arc::new(Mutex::new(ChainState::new(
@@ -48,6 +63,7 @@ impl<CS: ChainStore> Shared<CS> {
             store,
             chain_state,
             consensus,
+            prune_depth,
         }
     }
 
@@ -58,6 +74,11 @@ impl<CS: ChainStore> Shared<CS> {
     pub fn store(&self) -> &Arc<CS> {
         &self.store
     }
+
+    /// The bodies of the main chain blocks deeper than this below the tip are pruned
+    pub fn prune_depth(&self) -> Option<BlockNumber> {
+        self.prune_depth
+    }
 }
 
 impl<CS: ChainStore> ChainProvider for Shared<CS> {
@@ -137,6 +158,12 @@ impl<CS: ChainStore> ChainProvider for Shared<CS> {
                 } else {
                     return None;
                 }
+                // the rest of the walk is on the main chain
+                if self.block_number(&index_walk.hash()) == Some(n_number) {
+                    return self
+                        .block_hash(number)
+                        .and_then(|hash| self.block_header(&hash));
+                }
             }
             return Some(index_walk);
         }
@@ -197,6 +224,7 @@ pub struct SharedBuilder<DB: KeyValueDB> {
     db: Option<DB>,
     consensus: Option<Consensus>,
     tx_pool_config: Option<TxPoolConfig>,
+    prune_depth: Option<BlockNumber>,
 }
 
 impl<DB: KeyValueDB> Default for SharedBuilder<DB> {
@@ -205,6 +233,7 @@ impl<DB: KeyValueDB> Default for SharedBuilder<DB> {
             db: None,
             consensus: None,
             tx_pool_config: None,
+            prune_depth: None,
         }
     }
 }
@@ -215,6 +244,7 @@ impl SharedBuilder<MemoryKeyValueDB> {
             db: Some(MemoryKeyValueDB::open(COLUMNS as usize)),
             consensus: None,
             tx_pool_config: None,
+            prune_depth: None,
         }
     }
 }
@@ -226,15 +256,72 @@ impl SharedBuilder<CacheDB<RocksDB>> {
 
     pub fn db(mut self, config: &DBConfig) -> Self {
         self.db = Some(CacheDB::new(
-            RocksDB::open(config, COLUMNS),
+            RocksDB::open_with_columns(config, &column_configs(config)),
             &[(COLUMN_BLOCK_HEADER, 4096)],
         ));
+        self.prune_depth = config.prune_depth.map(|depth| {
+            if depth < MIN_PRUNE_DEPTH {
+                warn!(
+                    target: "shared",
+                    "prune_depth {} is too small, use {} instead", depth, MIN_PRUNE_DEPTH
+                );
+                MIN_PRUNE_DEPTH
+            } else {
+                depth
+            }
+        });
         self
     }
 }
 
+/// The tuning of every column, the `[db.columns.<name>]` sections override the defaults.
+pub fn column_configs(config: &DBConfig) -> Vec<ColumnConfig> {
+    for name in config.columns.keys() {
+        if !COLUMN_NAMES.contains(&name.as_str()) {
+            warn!(target: "shared", "unknown column {} in the db config", name);
+        }
+    }
+    COLUMN_NAMES
+        .iter()
+        .enumerate()
+        .map(|(col, name)| {
+            let default = default_column_config(col as Col);
+            config
+                .columns
+                .get(*name)
+                .map_or(default.clone(), |column| column.or(&default))
+        })
+        .collect()
+}
+
+fn default_column_config(col: Col) -> ColumnConfig {
+    match col {
+        // point lookups by hash or number
+        COLUMN_INDEX | COLUMN_CELL_SET => ColumnConfig {
+            bloom_filter_bits: Some(10),
+            ..Default::default()
+        },
+        // every transaction lookup starts here
+        COLUMN_TRANSACTION_ADDR => ColumnConfig {
+            block_cache_size: Some(64 << 20),
+            bloom_filter_bits: Some(10),
+            ..Default::default()
+        },
+        // the largest column, rarely read once the blocks are old
+        COLUMN_BLOCK_BODY => ColumnConfig {
+            block_cache_size: Some(64 << 20),
+            compression: Some(Compression::Zstd),
+            ..Default::default()
+        },
+        _ => ColumnConfig::default(),
+    }
+}
+
 pub const MIN_TXS_VERIFY_CACHE_SIZE: Option<usize> = Some(100);
 
+/// Blocks within the uncles, proposals and reorg windows must keep their bodies.
+pub const MIN_PRUNE_DEPTH: BlockNumber = 1000;
+
 impl<DB: KeyValueDB> SharedBuilder<DB> {
     pub fn consensus(mut self, value: Consensus) -> Self {
         self.consensus = Some(value);
@@ -246,10 +333,32 @@ impl<DB: KeyValueDB> SharedBuilder<DB> {
         self
     }
 
+    pub fn prune_depth(mut self, depth: Option<BlockNumber>) -> Self {
+        self.prune_depth = depth;
+        self
+    }
+
     pub fn build(self) -> Shared<ChainKVStore<DB>> {
+        self.try_build()
+            .expect("database version should be compatible")
+    }
+
+    /// Refuses to open a store whose schema version does not match this binary.
+    pub fn try_build(self) -> Result<Shared<ChainKVStore<DB>>, SharedError> {
         let store = ChainKVStore::new(self.db.unwrap());
+        Migrations::default().check(&store)?;
         let consensus = self.consensus.unwrap_or_else(Consensus::default);
         let tx_pool_config = self.tx_pool_config.unwrap_or_else(Default::default);
-        Shared::new(store, consensus, tx_pool_config)
+        Ok(Shared::new(
+            store,
+            consensus,
+            tx_pool_config,
+            self.prune_depth,
+        ))
+    }
+
+    /// Opens the store without checking its schema version, used to migrate it.
+    pub fn build_store(self) -> ChainKVStore<DB> {
+        ChainKVStore::new(self.db.unwrap())
     }
 }
@@ -0,0 +1,77 @@
+//! Point-in-time copies of the store used to bootstrap new nodes.
+//!
+//! A snapshot is a checkpoint of the store along with a manifest describing it, the manifest
+//! is checked against the restored store before a node starts from it. The manifest travels with
+//! the snapshot, so the check only catches corrupted copies unless the expected hashes come from
+//! a trusted source.
+
+use crate::error::SharedError;
+use crate::store::ChainStore;
+use bincode::serialize;
+use ckb_core::header::BlockNumber;
+use hash::new_blake2b;
+use numext_fixed_hash::H256;
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotManifest {
+    pub db_version: u32,
+    pub genesis_hash: H256,
+    pub tip_hash: H256,
+    pub tip_number: BlockNumber,
+    pub cell_set_hash: H256,
+}
+
+impl SnapshotManifest {
+    /// Describe the store, returns `None` for an empty store.
+    pub fn new<CS: ChainStore>(store: &CS) -> Option<Self> {
+        let tip = store.get_tip_header()?;
+        Some(SnapshotManifest {
+            db_version: store.get_db_version()?,
+            genesis_hash: store.get_block_hash(0)?,
+            tip_hash: tip.hash(),
+            tip_number: tip.number(),
+            cell_set_hash: cell_set_hash(store),
+        })
+    }
+
+    pub fn verify<CS: ChainStore>(&self, store: &CS) -> Result<(), SharedError> {
+        let actual = Self::new(store)
+            .ok_or_else(|| SharedError::InvalidSnapshot("the store is empty".to_owned()))?;
+        if actual.db_version != self.db_version {
+            Err(SharedError::InvalidSnapshot(format!(
+                "db version {} != {}",
+                actual.db_version, self.db_version
+            )))
+        } else if actual.genesis_hash != self.genesis_hash {
+            Err(SharedError::InvalidSnapshot(format!(
+                "genesis {:#x} != {:#x}",
+                actual.genesis_hash, self.genesis_hash
+            )))
+        } else if actual.tip_hash != self.tip_hash || actual.tip_number != self.tip_number {
+            Err(SharedError::InvalidSnapshot(format!(
+                "tip {} {:#x} != {} {:#x}",
+                actual.tip_number, actual.tip_hash, self.tip_number, self.tip_hash
+            )))
+        } else if actual.cell_set_hash != self.cell_set_hash {
+            Err(SharedError::InvalidSnapshot(format!(
+                "cell set hash {:#x} != {:#x}",
+                actual.cell_set_hash, self.cell_set_hash
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The blake2b hash of the live cell set entries, in the order of the transaction hashes
+pub fn cell_set_hash<CS: ChainStore>(store: &CS) -> H256 {
+    let mut blake2b = new_blake2b();
+    for (tx_hash, meta) in store.get_cell_set_iter() {
+        blake2b.update(tx_hash.as_bytes());
+        blake2b.update(&serialize(&meta).expect("serializing should be ok"));
+    }
+    let mut result = [0u8; 32];
+    blake2b.finalize(&mut result);
+    result.into()
+}
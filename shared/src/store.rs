@@ -1,21 +1,117 @@
+use crate::cell_set::CellSetChanges;
+use crate::fee_estimator::FeeStats;
 use crate::flat_serializer::{serialize as flat_serialize, serialized_addresses, Address};
+use crate::migration::DB_VERSION;
 use crate::{
     COLUMN_BLOCK_BODY, COLUMN_BLOCK_HEADER, COLUMN_BLOCK_PROPOSAL_IDS,
-    COLUMN_BLOCK_TRANSACTION_ADDRESSES, COLUMN_BLOCK_UNCLE, COLUMN_EXT, COLUMN_INDEX, COLUMN_META,
-    COLUMN_TRANSACTION_ADDR,
+    COLUMN_BLOCK_TRANSACTION_ADDRESSES, COLUMN_BLOCK_UNCLE, COLUMN_CELL_DATA_INDEX,
+    COLUMN_CELL_SET, COLUMN_CELL_TYPE_INDEX, COLUMN_EXT, COLUMN_INDEX, COLUMN_META, COLUMN_NAMES,
+    COLUMN_PRUNED_TRANSACTION, COLUMN_TRANSACTION_ADDR,
 };
 use bincode::{deserialize, serialize};
 use ckb_core::block::{Block, BlockBuilder};
 use ckb_core::extras::{BlockExt, TransactionAddress};
 use ckb_core::header::{BlockNumber, Header, HeaderBuilder};
-use ckb_core::transaction::{ProposalShortId, Transaction, TransactionBuilder};
+use ckb_core::script::Script;
+use ckb_core::transaction::{OutPoint, ProposalShortId, Transaction, TransactionBuilder};
+use ckb_core::transaction_meta::TransactionMeta;
 use ckb_core::uncle::UncleBlock;
-use ckb_db::{Col, DbBatch, Error, KeyValueDB};
+use ckb_core::Capacity;
+use ckb_db::{Col, ColumnStatistics, DbBatch, Direction, Error, IteratorMode, KeyValueDB};
+use fnv::FnvHashMap;
 use numext_fixed_hash::H256;
-use serde::Serialize;
+use serde::ser::Serialize as SerializeTrait;
+use serde_derive::{Deserialize, Serialize};
 use std::ops::Range;
+use std::path::Path;
 
 const META_TIP_HEADER_KEY: &[u8] = b"TIP_HEADER";
+const META_DB_VERSION_KEY: &[u8] = b"DB_VERSION";
+const META_PRUNED_NUMBER_KEY: &[u8] = b"PRUNED_NUMBER";
+const META_FEE_STATS_KEY: &[u8] = b"FEE_STATS";
+
+/// A transaction of a pruned block, kept until all its outputs are spent by pruned blocks
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PrunedTransaction {
+    pub transaction: Transaction,
+    /// The number of outputs spent by pruned blocks, these spends can no longer be reverted
+    pub spent: u32,
+}
+
+impl PrunedTransaction {
+    pub fn new(transaction: Transaction) -> Self {
+        PrunedTransaction {
+            transaction,
+            spent: 0,
+        }
+    }
+
+    pub fn is_all_spent(&self) -> bool {
+        self.spent as usize >= self.transaction.outputs().len()
+    }
+}
+
+/// A main chain cell in the type script and data hash indexes, the cell data is not kept
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IndexedCell {
+    pub out_point: OutPoint,
+    /// The block the cell is created in
+    pub block_number: BlockNumber,
+    pub block_hash: H256,
+    pub capacity: Capacity,
+    pub lock: Script,
+    pub type_: Option<Script>,
+    pub data_hash: H256,
+}
+
+impl IndexedCell {
+    /// The position of the cell among the cells of the same hash, the big endian block number
+    /// followed by the out point, so the cells of a hash are iterated in the main chain order
+    pub fn cursor(&self) -> Vec<u8> {
+        let mut cursor = Vec::with_capacity(44);
+        cursor.extend_from_slice(&self.block_number.to_be_bytes());
+        cursor.extend_from_slice(self.out_point.tx_hash.as_bytes());
+        cursor.extend_from_slice(&self.out_point.index.to_be_bytes());
+        cursor
+    }
+
+    fn index_key(&self, hash: &H256) -> Vec<u8> {
+        let mut key = hash.as_bytes().to_vec();
+        key.extend_from_slice(&self.cursor());
+        key
+    }
+}
+
+// The cells of a block along with their keys in the indexes. The cells without data are not
+// indexed by the data hash, they would all share the hash of the empty data.
+fn block_index_entries(block: &Block) -> Vec<(Col, Vec<u8>, IndexedCell)> {
+    let block_number = block.header().number();
+    let block_hash = block.header().hash();
+    let mut entries = Vec::new();
+    for tx in block.transactions() {
+        let tx_hash = tx.hash();
+        for (index, output) in tx.outputs().iter().enumerate() {
+            let cell = IndexedCell {
+                out_point: OutPoint::new(tx_hash.clone(), index as u32),
+                block_number,
+                block_hash: block_hash.clone(),
+                capacity: output.capacity,
+                lock: output.lock.clone(),
+                type_: output.type_.clone(),
+                data_hash: output.data_hash(),
+            };
+            if let Some(ref type_) = output.type_ {
+                let key = cell.index_key(&type_.hash());
+                entries.push((COLUMN_CELL_TYPE_INDEX, key, cell.clone()));
+            }
+            if !output.data.is_empty() {
+                let key = cell.index_key(&cell.data_hash);
+                entries.push((COLUMN_CELL_DATA_INDEX, key, cell));
+            }
+        }
+    }
+    entries
+}
 
 pub struct ChainKVStore<T> {
     db: T,
@@ -30,11 +126,42 @@ impl<T: KeyValueDB> ChainKVStore<T> {
         self.db.read(col, key).expect("db operation should be ok")
     }
 
+    // The cells of the hash after the cursor, from the first one if there is no cursor
+    fn indexed_cells<'a>(
+        &'a self,
+        col: Col,
+        hash: &H256,
+        after: Option<&[u8]>,
+    ) -> Box<dyn Iterator<Item = IndexedCell> + 'a> {
+        let prefix = hash.as_bytes().to_vec();
+        let mut start = prefix.clone();
+        let skip_start = after.is_some();
+        if let Some(after) = after {
+            start.extend_from_slice(after);
+        }
+        let iter = self
+            .db
+            .iter(col, IteratorMode::From(&start, Direction::Forward))
+            .expect("db operation should be ok");
+        Box::new(
+            iter.skip_while(move |(key, _)| skip_start && *key == start)
+                .take_while(move |(key, _)| key.starts_with(&prefix))
+                .map(|(_, value)| {
+                    deserialize(&value[..]).expect("deserialize indexed cell should be ok")
+                }),
+        )
+    }
+
     pub fn partial_get(&self, col: Col, key: &[u8], range: &Range<usize>) -> Option<Vec<u8>> {
         self.db
             .partial_read(col, key, range)
             .expect("db operation should be ok")
     }
+
+    /// Write a consistent copy of the store to `path`, which must not exist
+    pub fn checkpoint(&self, path: &Path) -> Result<(), Error> {
+        self.db.checkpoint(path)
+    }
 }
 
 pub trait ChainStore: Sync + Send {
@@ -54,15 +181,75 @@ pub trait ChainStore: Sync + Send {
     fn get_tip_header(&self) -> Option<Header>;
     fn get_transaction(&self, h: &H256) -> Option<Transaction>;
     fn get_transaction_address(&self, hash: &H256) -> Option<TransactionAddress>;
+    /// The index of the transaction in its block, `None` when the block body has been pruned
+    fn get_transaction_index(&self, address: &TransactionAddress) -> Option<usize>;
+    fn get_tx_meta(&self, tx_hash: &H256) -> Option<TransactionMeta>;
+    /// Iterates the live cell set in the order of the transaction hashes
+    fn get_cell_set_iter<'a>(&'a self) -> Box<dyn Iterator<Item = (H256, TransactionMeta)> + 'a>;
+    fn get_db_version(&self) -> Option<u32>;
+    fn get_pruned_transaction(&self, tx_hash: &H256) -> Option<PrunedTransaction>;
+    /// The number of the highest main chain block whose body has been pruned
+    fn get_pruned_number(&self) -> Option<BlockNumber>;
+    /// Whether the block header is stored but its body has been pruned
+    fn is_pruned(&self, block_hash: &H256) -> bool;
+    /// The fee estimator statistics saved at the last new tip
+    fn get_fee_stats(&self) -> Option<FeeStats>;
+    /// Iterates the main chain cells, live or dead, whose type script hash is `type_hash`, in
+    /// the main chain order, starting after the `IndexedCell::cursor` of `after` if given
+    fn get_cells_by_type_hash<'a>(
+        &'a self,
+        type_hash: &H256,
+        after: Option<&[u8]>,
+    ) -> Box<dyn Iterator<Item = IndexedCell> + 'a>;
+    /// Iterates the main chain cells, live or dead, whose data hash is `data_hash`, in the main
+    /// chain order, starting after the `IndexedCell::cursor` of `after` if given. The cells
+    /// without data are not indexed.
+    fn get_cells_by_data_hash<'a>(
+        &'a self,
+        data_hash: &H256,
+        after: Option<&[u8]>,
+    ) -> Box<dyn Iterator<Item = IndexedCell> + 'a>;
+    /// Statistics of every column along with its name
+    fn get_column_statistics(&self) -> Result<Vec<(&'static str, ColumnStatistics)>, Error>;
 }
 
 pub trait StoreBatch {
     fn insert_block(&mut self, block: &Block) -> Result<(), Error>;
     fn insert_block_ext(&mut self, block_hash: &H256, ext: &BlockExt) -> Result<(), Error>;
     fn insert_tip_header(&mut self, header: &Header) -> Result<(), Error>;
+    fn insert_db_version(&mut self, version: u32) -> Result<(), Error>;
 
+    /// Index the transactions and cells of a new main chain block
     fn attach_block(&mut self, block: &Block) -> Result<(), Error>;
     fn detach_block(&mut self, block: &Block) -> Result<(), Error>;
+    /// Index the hash and number of a main chain block, without touching its transactions
+    fn insert_block_index(&mut self, number: BlockNumber, hash: &H256) -> Result<(), Error>;
+    /// Index the cells of a main chain block by type script and data hash
+    fn index_cells(&mut self, block: &Block) -> Result<(), Error>;
+
+    fn insert_tx_meta(&mut self, tx_hash: &H256, meta: &TransactionMeta) -> Result<(), Error>;
+    fn delete_tx_meta(&mut self, tx_hash: &H256) -> Result<(), Error>;
+
+    /// Delete the body, uncles and proposals of a block, the header and ext are kept.
+    fn delete_block_body(&mut self, block_hash: &H256) -> Result<(), Error>;
+    fn insert_pruned_transaction(
+        &mut self,
+        tx_hash: &H256,
+        tx: &PrunedTransaction,
+    ) -> Result<(), Error>;
+    fn delete_pruned_transaction(&mut self, tx_hash: &H256) -> Result<(), Error>;
+    fn insert_pruned_number(&mut self, number: BlockNumber) -> Result<(), Error>;
+    fn insert_fee_stats(&mut self, stats: &FeeStats) -> Result<(), Error>;
+
+    fn update_cell_set(&mut self, changes: &CellSetChanges) -> Result<(), Error> {
+        for tx_hash in changes.removed() {
+            self.delete_tx_meta(tx_hash)?;
+        }
+        for (tx_hash, meta) in changes.updated() {
+            self.insert_tx_meta(tx_hash, meta)?;
+        }
+        Ok(())
+    }
 
     fn commit(self) -> Result<(), Error>;
 }
@@ -77,10 +264,9 @@ impl<T: KeyValueDB> ChainStore for ChainKVStore<T> {
     }
 
     fn get_block(&self, h: &H256) -> Option<Block> {
-        self.get_header(h).map(|header| {
-            let transactions = self
-                .get_block_body(h)
-                .expect("block transactions must be stored");
+        let header = self.get_header(h)?;
+        // the body is missing once the block is pruned
+        self.get_block_body(h).map(|transactions| {
             let uncles = self
                 .get_block_uncles(h)
                 .expect("block uncles must be stored");
@@ -149,23 +335,30 @@ impl<T: KeyValueDB> ChainStore for ChainKVStore<T> {
             txs_verified: Some(true),
         };
 
-        let mut cells = Vec::with_capacity(genesis.transactions().len());
+        batch.insert_block(genesis)?;
+        batch.insert_block_ext(&genesis_hash, &ext)?;
+        batch.insert_tip_header(&genesis.header())?;
+        batch.insert_db_version(DB_VERSION)?;
+        batch.attach_block(genesis)?;
 
+        let number = genesis.header().number();
+        let mut tx_metas: FnvHashMap<H256, TransactionMeta> = FnvHashMap::default();
         for tx in genesis.transactions() {
-            let ins = if tx.is_cellbase() {
-                Vec::new()
+            for o in tx.input_pts() {
+                if let Some(meta) = tx_metas.get_mut(&o.tx_hash) {
+                    meta.set_dead(o.index as usize);
+                }
+            }
+            let meta = if tx.is_cellbase() {
+                TransactionMeta::new_cellbase(number, tx.outputs().len())
             } else {
-                tx.input_pts()
+                TransactionMeta::new(number, tx.outputs().len())
             };
-            let outs = tx.output_pts();
-
-            cells.push((ins, outs));
+            tx_metas.insert(tx.hash(), meta);
+        }
+        for (tx_hash, meta) in &tx_metas {
+            batch.insert_tx_meta(tx_hash, meta)?;
         }
-
-        batch.insert_block(genesis)?;
-        batch.insert_block_ext(&genesis_hash, &ext)?;
-        batch.insert_tip_header(&genesis.header())?;
-        batch.attach_block(genesis)?;
         batch.commit()
     }
 
@@ -197,12 +390,97 @@ impl<T: KeyValueDB> ChainStore for ChainKVStore<T> {
             .map(|ref serialized_transaction| {
                 TransactionBuilder::new(serialized_transaction).build()
             })
+            .or_else(|| self.get_pruned_transaction(h).map(|tx| tx.transaction))
     }
 
     fn get_transaction_address(&self, h: &H256) -> Option<TransactionAddress> {
         self.get(COLUMN_TRANSACTION_ADDR, h.as_bytes())
             .map(|raw| deserialize(&raw[..]).unwrap())
     }
+
+    fn get_transaction_index(&self, address: &TransactionAddress) -> Option<usize> {
+        self.get(
+            COLUMN_BLOCK_TRANSACTION_ADDRESSES,
+            address.block_hash.as_bytes(),
+        )
+        .and_then(|serialized_addresses| {
+            let addresses: Vec<Address> =
+                deserialize(&serialized_addresses).expect("deserialize address should be ok");
+            addresses
+                .iter()
+                .position(|block_address| block_address.offset == address.offset)
+        })
+    }
+
+    fn get_tx_meta(&self, tx_hash: &H256) -> Option<TransactionMeta> {
+        self.get(COLUMN_CELL_SET, tx_hash.as_bytes())
+            .map(|raw| deserialize(&raw[..]).expect("deserialize tx meta should be ok"))
+    }
+
+    fn get_cell_set_iter<'a>(&'a self) -> Box<dyn Iterator<Item = (H256, TransactionMeta)> + 'a> {
+        let iter = self
+            .db
+            .iter(COLUMN_CELL_SET, IteratorMode::Start)
+            .expect("db operation should be ok");
+        Box::new(iter.map(|(key, value)| {
+            (
+                H256::from_slice(&key[..]).expect("db safe access"),
+                deserialize(&value[..]).expect("deserialize tx meta should be ok"),
+            )
+        }))
+    }
+
+    fn get_db_version(&self) -> Option<u32> {
+        self.get(COLUMN_META, META_DB_VERSION_KEY)
+            .map(|raw| deserialize(&raw[..]).expect("deserialize db version should be ok"))
+    }
+
+    fn get_pruned_transaction(&self, tx_hash: &H256) -> Option<PrunedTransaction> {
+        self.get(COLUMN_PRUNED_TRANSACTION, tx_hash.as_bytes())
+            .map(|raw| deserialize(&raw[..]).expect("deserialize pruned transaction should be ok"))
+    }
+
+    fn get_pruned_number(&self) -> Option<BlockNumber> {
+        self.get(COLUMN_META, META_PRUNED_NUMBER_KEY)
+            .map(|raw| deserialize(&raw[..]).expect("deserialize pruned number should be ok"))
+    }
+
+    fn get_fee_stats(&self) -> Option<FeeStats> {
+        self.get(COLUMN_META, META_FEE_STATS_KEY)
+            .map(|raw| deserialize(&raw[..]).expect("deserialize fee stats should be ok"))
+    }
+
+    fn get_cells_by_type_hash<'a>(
+        &'a self,
+        type_hash: &H256,
+        after: Option<&[u8]>,
+    ) -> Box<dyn Iterator<Item = IndexedCell> + 'a> {
+        self.indexed_cells(COLUMN_CELL_TYPE_INDEX, type_hash, after)
+    }
+
+    fn get_cells_by_data_hash<'a>(
+        &'a self,
+        data_hash: &H256,
+        after: Option<&[u8]>,
+    ) -> Box<dyn Iterator<Item = IndexedCell> + 'a> {
+        self.indexed_cells(COLUMN_CELL_DATA_INDEX, data_hash, after)
+    }
+
+    fn is_pruned(&self, block_hash: &H256) -> bool {
+        self.get(COLUMN_BLOCK_HEADER, block_hash.as_bytes())
+            .is_some()
+            && self
+                .get(COLUMN_BLOCK_TRANSACTION_ADDRESSES, block_hash.as_bytes())
+                .is_none()
+    }
+
+    fn get_column_statistics(&self) -> Result<Vec<(&'static str, ColumnStatistics)>, Error> {
+        COLUMN_NAMES
+            .iter()
+            .enumerate()
+            .map(|(col, name)| Ok((*name, self.db.column_statistics(col as Col)?)))
+            .collect()
+    }
 }
 
 pub struct DefaultStoreBatch<B> {
@@ -215,7 +493,7 @@ impl<B: DbBatch> DefaultStoreBatch<B> {
         self.inner.insert(col, key, value)
     }
 
-    fn insert_serialize<T: Serialize + ?Sized>(
+    fn insert_serialize<T: SerializeTrait + ?Sized>(
         &mut self,
         col: Col,
         key: &[u8],
@@ -266,23 +544,77 @@ impl<B: DbBatch> StoreBatch for DefaultStoreBatch<B> {
             self.insert_serialize(COLUMN_TRANSACTION_ADDR, tx.hash().as_bytes(), &address)?;
         }
 
-        let number = block.header().number().to_le_bytes();
-        self.insert_raw(COLUMN_INDEX, &number, hash.as_bytes())?;
-        self.insert_raw(COLUMN_INDEX, hash.as_bytes(), &number)
+        self.index_cells(block)?;
+        self.insert_block_index(block.header().number(), &hash)
     }
 
     fn detach_block(&mut self, block: &Block) -> Result<(), Error> {
         for tx in block.transactions() {
             self.delete(COLUMN_TRANSACTION_ADDR, tx.hash().as_bytes())?;
         }
+        for (col, key, _) in block_index_entries(block) {
+            self.delete(col, &key)?;
+        }
         self.delete(COLUMN_INDEX, &block.header().number().to_le_bytes())?;
         self.delete(COLUMN_INDEX, block.header().hash().as_bytes())
     }
 
+    fn insert_block_index(&mut self, number: BlockNumber, hash: &H256) -> Result<(), Error> {
+        let number = number.to_le_bytes();
+        self.insert_raw(COLUMN_INDEX, &number, hash.as_bytes())?;
+        self.insert_raw(COLUMN_INDEX, hash.as_bytes(), &number)
+    }
+
+    fn index_cells(&mut self, block: &Block) -> Result<(), Error> {
+        for (col, key, cell) in block_index_entries(block) {
+            self.insert_serialize(col, &key, &cell)?;
+        }
+        Ok(())
+    }
+
+    fn insert_tx_meta(&mut self, tx_hash: &H256, meta: &TransactionMeta) -> Result<(), Error> {
+        self.insert_serialize(COLUMN_CELL_SET, tx_hash.as_bytes(), meta)
+    }
+
+    fn delete_tx_meta(&mut self, tx_hash: &H256) -> Result<(), Error> {
+        self.delete(COLUMN_CELL_SET, tx_hash.as_bytes())
+    }
+
+    fn delete_block_body(&mut self, block_hash: &H256) -> Result<(), Error> {
+        self.delete(COLUMN_BLOCK_TRANSACTION_ADDRESSES, block_hash.as_bytes())?;
+        self.delete(COLUMN_BLOCK_BODY, block_hash.as_bytes())?;
+        self.delete(COLUMN_BLOCK_UNCLE, block_hash.as_bytes())?;
+        self.delete(COLUMN_BLOCK_PROPOSAL_IDS, block_hash.as_bytes())
+    }
+
+    fn insert_pruned_transaction(
+        &mut self,
+        tx_hash: &H256,
+        tx: &PrunedTransaction,
+    ) -> Result<(), Error> {
+        self.insert_serialize(COLUMN_PRUNED_TRANSACTION, tx_hash.as_bytes(), tx)
+    }
+
+    fn delete_pruned_transaction(&mut self, tx_hash: &H256) -> Result<(), Error> {
+        self.delete(COLUMN_PRUNED_TRANSACTION, tx_hash.as_bytes())
+    }
+
+    fn insert_pruned_number(&mut self, number: BlockNumber) -> Result<(), Error> {
+        self.insert_serialize(COLUMN_META, META_PRUNED_NUMBER_KEY, &number)
+    }
+
+    fn insert_fee_stats(&mut self, stats: &FeeStats) -> Result<(), Error> {
+        self.insert_serialize(COLUMN_META, META_FEE_STATS_KEY, stats)
+    }
+
     fn insert_tip_header(&mut self, h: &Header) -> Result<(), Error> {
         self.insert_raw(COLUMN_META, META_TIP_HEADER_KEY, h.hash().as_bytes())
     }
 
+    fn insert_db_version(&mut self, version: u32) -> Result<(), Error> {
+        self.insert_serialize(COLUMN_META, META_DB_VERSION_KEY, &version)
+    }
+
     fn commit(self) -> Result<(), Error> {
         self.inner.commit()
     }
@@ -294,6 +626,7 @@ mod tests {
     use super::*;
     use crate::store::StoreBatch;
     use ckb_chain_spec::consensus::Consensus;
+    use ckb_core::transaction::CellOutput;
     use ckb_db::{DBConfig, RocksDB};
     use tempfile;
 
@@ -338,6 +671,74 @@ mod tests {
         assert_eq!(block, store.get_block(&hash).unwrap());
     }
 
+    #[test]
+    fn get_transaction_index() {
+        let db = setup_db("get_transaction_index", COLUMNS);
+        let store = ChainKVStore::new(db);
+        let block = BlockBuilder::default()
+            .transaction(TransactionBuilder::default().build())
+            .transaction(TransactionBuilder::default().version(1).build())
+            .build();
+
+        let mut batch = store.new_batch().unwrap();
+        batch.insert_block(&block).unwrap();
+        batch.attach_block(&block).unwrap();
+        batch.commit().unwrap();
+        for (index, tx) in block.transactions().iter().enumerate() {
+            let address = store.get_transaction_address(&tx.hash()).unwrap();
+            assert_eq!(store.get_transaction_index(&address), Some(index));
+        }
+    }
+
+    #[test]
+    fn index_cells_by_type_and_data_hash() {
+        let db = setup_db("index_cells_by_type_and_data_hash", COLUMNS);
+        let store = ChainKVStore::new(db);
+        let type_ = Script::new(vec![vec![1]], H256::zero());
+        let output = |data: Vec<u8>, type_: Option<Script>| {
+            CellOutput::new(Capacity::zero(), data, Script::default(), type_)
+        };
+        let tx = TransactionBuilder::default()
+            .output(output(vec![1], Some(type_.clone())))
+            .output(output(vec![], Some(type_.clone())))
+            .output(output(vec![1], None))
+            .build();
+        let data_hash = tx.outputs()[0].data_hash();
+        let block = BlockBuilder::default().transaction(tx).build();
+
+        let mut batch = store.new_batch().unwrap();
+        batch.insert_block(&block).unwrap();
+        batch.attach_block(&block).unwrap();
+        batch.commit().unwrap();
+        let indexes: Vec<_> = store
+            .get_cells_by_type_hash(&type_.hash(), None)
+            .map(|cell| cell.out_point.index)
+            .collect();
+        assert_eq!(indexes, vec![0, 1]);
+        // the empty data is not indexed
+        let indexes: Vec<_> = store
+            .get_cells_by_data_hash(&data_hash, None)
+            .map(|cell| cell.out_point.index)
+            .collect();
+        assert_eq!(indexes, vec![0, 2]);
+        // continues after the cursor of the last returned cell
+        let first = store
+            .get_cells_by_type_hash(&type_.hash(), None)
+            .next()
+            .unwrap();
+        let indexes: Vec<_> = store
+            .get_cells_by_type_hash(&type_.hash(), Some(&first.cursor()))
+            .map(|cell| cell.out_point.index)
+            .collect();
+        assert_eq!(indexes, vec![1]);
+
+        let mut batch = store.new_batch().unwrap();
+        batch.detach_block(&block).unwrap();
+        batch.commit().unwrap();
+        assert_eq!(store.get_cells_by_type_hash(&type_.hash(), None).count(), 0);
+        assert_eq!(store.get_cells_by_data_hash(&data_hash, None).count(), 0);
+    }
+
     #[test]
     fn save_and_get_block_ext() {
         let db = setup_db("save_and_get_block_ext", COLUMNS);
@@ -1,22 +1,32 @@
-use crate::cell_set::{CellSet, CellSetDiff};
+use crate::cell_set::CellSetDiff;
 use crate::{
     shared::{Shared, SharedBuilder},
-    store::ChainKVStore,
+    store::{ChainKVStore, ChainStore, StoreBatch},
 };
 use ckb_core::cell::{CellProvider, CellStatus, LiveCell};
 use ckb_core::transaction::Transaction;
+use ckb_core::transaction_meta::TransactionMeta;
 use ckb_db::memorydb::MemoryKeyValueDB;
+use fnv::FnvHashMap;
+use numext_fixed_hash::H256;
+use serde_derive::Deserialize;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::Path;
 
-fn cell_set() -> CellSet {
+#[derive(Deserialize)]
+struct CellSetData {
+    inner: FnvHashMap<H256, TransactionMeta>,
+}
+
+fn cell_set() -> FnvHashMap<H256, TransactionMeta> {
     let file = File::open(
         Path::new(env!("CARGO_MANIFEST_DIR")).join("./src/tests/data/no1/cell_set.json"),
     )
     .unwrap();
     let reader = BufReader::new(file);
-    serde_json::from_reader(reader).unwrap()
+    let data: CellSetData = serde_json::from_reader(reader).unwrap();
+    data.inner
 }
 
 fn cell_set_diff() -> CellSetDiff {
@@ -43,8 +53,12 @@ fn new_shared() -> Shared<ChainKVStore<MemoryKeyValueDB>> {
 #[test]
 fn case_no1() {
     let shared = new_shared();
-    let mut chain_state = shared.chain_state().lock();
-    chain_state.cell_set = cell_set();
+    let mut batch = shared.store().new_batch().unwrap();
+    for (tx_hash, meta) in &cell_set() {
+        batch.insert_tx_meta(tx_hash, meta).unwrap();
+    }
+    batch.commit().unwrap();
+    let chain_state = shared.chain_state().lock();
 
     let cell_set_diff = cell_set_diff();
     let cell_set_overlay = chain_state.new_cell_set_overlay(&cell_set_diff);
@@ -0,0 +1,86 @@
+use super::{insert_chain, new_block};
+use crate::consistency::{ConsistencyChecker, Inconsistency};
+use crate::shared::SharedBuilder;
+use crate::store::{ChainStore, StoreBatch};
+use ckb_db::MemoryKeyValueDB;
+use numext_fixed_uint::U256;
+
+#[test]
+fn consistent_store() {
+    let shared = SharedBuilder::<MemoryKeyValueDB>::new().build();
+    insert_chain(shared.store(), 3);
+
+    let report = ConsistencyChecker::new(shared.store().as_ref()).check(&|_, _| ());
+    assert!(report.is_consistent());
+    assert_eq!(report.repairs_count(), 0);
+}
+
+#[test]
+fn repair_inconsistencies() {
+    let shared = SharedBuilder::<MemoryKeyValueDB>::new().build();
+    let store = shared.store();
+    let blocks = insert_chain(store, 3);
+
+    let mut batch = store.new_batch().unwrap();
+    // the ext of block 2 is corrupted
+    let hash = blocks[1].header().hash();
+    let mut ext = store.get_block_ext(&hash).unwrap();
+    ext.total_difficulty = U256::zero();
+    batch.insert_block_ext(&hash, &ext).unwrap();
+    // the body of block 1 is lost
+    batch.delete_block_body(&blocks[0].header().hash()).unwrap();
+    // block 4 is indexed but the tip is still block 3
+    let stale = new_block(blocks[2].header(), None);
+    batch.insert_block(&stale).unwrap();
+    batch.attach_block(&stale).unwrap();
+    batch.commit().unwrap();
+
+    let report = ConsistencyChecker::new(store.as_ref()).check(&|_, _| ());
+    assert_eq!(
+        report.inconsistencies(),
+        &[
+            Inconsistency::MissingBody(1, blocks[0].header().hash()),
+            Inconsistency::BlockExtMismatch(2, hash),
+            Inconsistency::StaleIndex(4, stale.header().hash()),
+        ][..]
+    );
+    assert_eq!(report.repairs_count(), 2);
+    report.repair(store.as_ref()).unwrap();
+
+    let report = ConsistencyChecker::new(store.as_ref()).check(&|_, _| ());
+    assert_eq!(
+        report.inconsistencies(),
+        &[Inconsistency::MissingBody(1, blocks[0].header().hash())][..]
+    );
+    assert!(store.get_block_hash(4).is_none());
+    assert!(store
+        .get_transaction_address(&stale.transactions()[0].hash())
+        .is_none());
+}
+
+#[test]
+fn reindex_pruned_block() {
+    let shared = SharedBuilder::<MemoryKeyValueDB>::new().build();
+    let store = shared.store();
+    let blocks = insert_chain(store, 3);
+
+    let hash = blocks[0].header().hash();
+    let mut batch = store.new_batch().unwrap();
+    batch.delete_block_body(&hash).unwrap();
+    batch.insert_pruned_number(1).unwrap();
+    // the hash of pruned block 1 is indexed with a wrong number
+    batch.insert_block_index(9, &hash).unwrap();
+    batch.commit().unwrap();
+
+    let report = ConsistencyChecker::new(store.as_ref()).check(&|_, _| ());
+    assert_eq!(
+        report.inconsistencies(),
+        &[Inconsistency::ReverseIndexMismatch(1, hash.clone())][..]
+    );
+    assert_eq!(report.repairs_count(), 1);
+    report.repair(store.as_ref()).unwrap();
+
+    let report = ConsistencyChecker::new(store.as_ref()).check(&|_, _| ());
+    assert!(report.is_consistent());
+    assert_eq!(store.get_block_number(&hash), Some(1));
+}
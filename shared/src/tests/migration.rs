@@ -0,0 +1,118 @@
+use crate::error::SharedError;
+use crate::migration::{Migrations, DB_VERSION};
+use crate::{
+    shared::SharedBuilder,
+    store::{ChainKVStore, ChainStore, StoreBatch},
+};
+use ckb_chain_spec::consensus::Consensus;
+use ckb_core::block::BlockBuilder;
+use ckb_core::script::Script;
+use ckb_core::transaction::{CellInput, CellOutput, TransactionBuilder};
+use ckb_core::Capacity;
+use ckb_db::memorydb::MemoryKeyValueDB;
+
+#[test]
+fn latest_migration_matches_db_version() {
+    let migrations = Migrations::<ChainKVStore<MemoryKeyValueDB>>::default();
+    assert_eq!(migrations.latest_version(), DB_VERSION);
+}
+
+#[test]
+fn migrate_cell_set() {
+    let cellbase = TransactionBuilder::default()
+        .input(CellInput::new_cellbase_input(0))
+        .output(CellOutput::new(
+            Capacity::zero(),
+            vec![],
+            Script::default(),
+            None,
+        ))
+        .build();
+    let cellbase_hash = cellbase.hash();
+    let consensus = Consensus::default()
+        .set_genesis_block(BlockBuilder::default().transaction(cellbase).build());
+    let shared = SharedBuilder::<MemoryKeyValueDB>::new()
+        .consensus(consensus)
+        .build();
+    let store = shared.store();
+    let migrations = Migrations::<ChainKVStore<MemoryKeyValueDB>>::default();
+    assert_eq!(store.get_db_version(), Some(DB_VERSION));
+    assert_eq!(migrations.check(store), Ok(()));
+
+    // simulate a store written before the cell set was persisted
+    let mut batch = store.new_batch().unwrap();
+    batch.delete_tx_meta(&cellbase_hash).unwrap();
+    batch.insert_db_version(0).unwrap();
+    batch.commit().unwrap();
+    assert_eq!(
+        migrations.check(store),
+        Err(SharedError::OutdatedDBVersion(0, DB_VERSION))
+    );
+
+    assert_eq!(
+        migrations.migrate(store, &|_, _, _| ()),
+        Ok((0, DB_VERSION))
+    );
+    assert_eq!(migrations.check(store), Ok(()));
+    assert!(store.get_tx_meta(&cellbase_hash).is_some());
+}
+
+#[test]
+fn migrate_cell_index() {
+    let type_ = Script::new(vec![vec![1]], Default::default());
+    let cellbase = TransactionBuilder::default()
+        .input(CellInput::new_cellbase_input(0))
+        .output(CellOutput::new(
+            Capacity::zero(),
+            vec![1, 2, 3],
+            Script::default(),
+            Some(type_.clone()),
+        ))
+        .build();
+    let data_hash = cellbase.outputs()[0].data_hash();
+    let consensus = Consensus::default().set_genesis_block(
+        BlockBuilder::default()
+            .transaction(cellbase.clone())
+            .build(),
+    );
+    let shared = SharedBuilder::<MemoryKeyValueDB>::new()
+        .consensus(consensus)
+        .build();
+    let store = shared.store();
+
+    // the migration indexes the stored blocks again, it can be restarted
+    let mut batch = store.new_batch().unwrap();
+    batch.insert_db_version(2).unwrap();
+    batch.commit().unwrap();
+    let migrations = Migrations::<ChainKVStore<MemoryKeyValueDB>>::default();
+    assert_eq!(
+        migrations.migrate(store, &|_, _, _| ()),
+        Ok((2, DB_VERSION))
+    );
+
+    assert_eq!(store.get_cells_by_type_hash(&type_.hash(), None).count(), 1);
+    let cells: Vec<_> = store.get_cells_by_data_hash(&data_hash, None).collect();
+    assert_eq!(cells.len(), 1);
+    assert_eq!(cells[0].out_point.tx_hash, cellbase.hash());
+    assert_eq!(cells[0].block_number, 0);
+    assert_eq!(cells[0].type_, Some(type_));
+}
+
+#[test]
+fn refuse_newer_db_version() {
+    let shared = SharedBuilder::<MemoryKeyValueDB>::new().build();
+    let store = shared.store();
+    let mut batch = store.new_batch().unwrap();
+    batch.insert_db_version(DB_VERSION + 1).unwrap();
+    batch.commit().unwrap();
+
+    let migrations = Migrations::<ChainKVStore<MemoryKeyValueDB>>::default();
+    assert_eq!(
+        migrations.check(store),
+        Err(SharedError::UnsupportedDBVersion(
+            DB_VERSION + 1,
+            DB_VERSION
+        ))
+    );
+    assert!(migrations.migrate(store, &|_, _, _| ()).is_err());
+}
@@ -1,2 +1,82 @@
+use crate::cell_set::{CellSet, CellSetDiff};
+use crate::store::{ChainKVStore, ChainStore, StoreBatch};
+use ckb_core::block::{Block, BlockBuilder};
+use ckb_core::extras::BlockExt;
+use ckb_core::header::{Header, HeaderBuilder};
+use ckb_core::script::Script;
+use ckb_core::transaction::{CellInput, CellOutput, OutPoint, TransactionBuilder};
+use ckb_core::{capacity_bytes, Capacity};
+use ckb_db::MemoryKeyValueDB;
+use numext_fixed_hash::H256;
+use numext_fixed_uint::U256;
+use std::sync::Arc;
+
 mod cell_set;
+mod consistency;
+mod migration;
+mod rollback;
 mod shared;
+mod snapshot;
+
+// every block spends the cellbase output of its parent
+fn new_block(parent: &Header, parent_cellbase: Option<&H256>) -> Block {
+    let number = parent.number() + 1;
+    let output = CellOutput::new(capacity_bytes!(100), vec![], Script::default(), None);
+    let mut builder = BlockBuilder::default().transaction(
+        TransactionBuilder::default()
+            .input(CellInput::new_cellbase_input(number))
+            .output(output.clone())
+            .build(),
+    );
+    if let Some(hash) = parent_cellbase {
+        builder = builder.transaction(
+            TransactionBuilder::default()
+                .input(CellInput::new(OutPoint::new(hash.clone(), 0), 0, vec![]))
+                .output(output)
+                .build(),
+        );
+    }
+    builder.with_header_builder(
+        HeaderBuilder::default()
+            .parent_hash(parent.hash())
+            .number(number)
+            .difficulty(U256::from(100u64)),
+    )
+}
+
+// returns the main chain blocks after genesis
+fn insert_chain(store: &Arc<ChainKVStore<MemoryKeyValueDB>>, len: usize) -> Vec<Block> {
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut parent = store.get_tip_header().unwrap();
+    let mut parent_ext = store.get_block_ext(&parent.hash()).unwrap();
+    let mut cell_set = CellSet::new(store, 16);
+    for _ in 0..len {
+        let parent_cellbase = blocks.last().map(|block| block.transactions()[0].hash());
+        let block = new_block(&parent, parent_cellbase.as_ref());
+        let ext = BlockExt {
+            received_at: block.header().timestamp(),
+            total_difficulty: &parent_ext.total_difficulty + block.header().difficulty(),
+            total_uncles_count: parent_ext.total_uncles_count,
+            txs_verified: Some(true),
+        };
+        let mut diff = CellSetDiff::default();
+        diff.push_new(&block);
+        let changes = cell_set.new_overlay(&diff).into_changes();
+
+        let mut batch = store.new_batch().unwrap();
+        batch.insert_block(&block).unwrap();
+        batch
+            .insert_block_ext(&block.header().hash(), &ext)
+            .unwrap();
+        batch.attach_block(&block).unwrap();
+        batch.update_cell_set(&changes).unwrap();
+        batch.insert_tip_header(block.header()).unwrap();
+        batch.commit().unwrap();
+        cell_set.update(changes);
+
+        parent = block.header().clone();
+        parent_ext = ext;
+        blocks.push(block);
+    }
+    blocks
+}
@@ -0,0 +1,54 @@
+use super::insert_chain;
+use crate::consistency::ConsistencyChecker;
+use crate::error::SharedError;
+use crate::rollback::rollback;
+use crate::shared::SharedBuilder;
+use crate::snapshot::cell_set_hash;
+use crate::store::{ChainStore, StoreBatch};
+use ckb_db::MemoryKeyValueDB;
+
+#[test]
+fn rollback_to_earlier_block() {
+    let shared = SharedBuilder::<MemoryKeyValueDB>::new().build();
+    let store = shared.store();
+    let mut blocks = insert_chain(store, 3);
+    let expected_cell_set_hash = cell_set_hash(store.as_ref());
+    blocks.extend(insert_chain(store, 3));
+
+    rollback(store, 3, &|_, _| ()).unwrap();
+
+    assert_eq!(store.get_tip_header().as_ref(), Some(blocks[2].header()));
+    assert_eq!(cell_set_hash(store.as_ref()), expected_cell_set_hash);
+    for block in &blocks[3..] {
+        let hash = block.header().hash();
+        assert!(store.get_block_hash(block.header().number()).is_none());
+        assert!(store.get_block(&hash).is_some());
+        assert_eq!(store.get_block_ext(&hash).unwrap().txs_verified, None);
+        for tx in block.transactions() {
+            assert!(store.get_transaction_address(&tx.hash()).is_none());
+        }
+    }
+    let report = ConsistencyChecker::new(store.as_ref()).check(&|_, _| ());
+    assert!(report.is_consistent());
+}
+
+#[test]
+fn rollback_invalid_target() {
+    let shared = SharedBuilder::<MemoryKeyValueDB>::new().build();
+    let store = shared.store();
+    let blocks = insert_chain(store, 3);
+
+    let err = rollback(store, 4, &|_, _| ()).unwrap_err();
+    assert!(match err {
+        SharedError::InvalidRollbackTarget(_) => true,
+        _ => false,
+    });
+
+    let mut batch = store.new_batch().unwrap();
+    batch.delete_block_body(&blocks[0].header().hash()).unwrap();
+    batch.insert_pruned_number(1).unwrap();
+    batch.commit().unwrap();
+    assert!(rollback(store, 0, &|_, _| ()).is_err());
+    assert!(rollback(store, 1, &|_, _| ()).is_ok());
+    assert_eq!(store.get_tip_header().as_ref(), Some(blocks[0].header()));
+}
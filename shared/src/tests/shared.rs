@@ -1,9 +1,10 @@
 use crate::{
-    shared::{Shared, SharedBuilder},
+    shared::{column_configs, Shared, SharedBuilder},
     store::{ChainKVStore, ChainStore, StoreBatch},
+    COLUMNS, COLUMN_BLOCK_BODY, COLUMN_META, COLUMN_NAMES, COLUMN_TRANSACTION_ADDR,
 };
 use ckb_core::{block::BlockBuilder, header::HeaderBuilder};
-use ckb_db::{KeyValueDB, MemoryKeyValueDB};
+use ckb_db::{ColumnConfig, Compression, DBConfig, KeyValueDB, MemoryKeyValueDB};
 use ckb_traits::BlockMedianTimeContext;
 
 fn new_shared() -> Shared<ChainKVStore<MemoryKeyValueDB>> {
@@ -56,3 +57,39 @@ fn test_block_median_time() {
         17
     );
 }
+
+#[test]
+fn test_column_configs() {
+    let mut config = DBConfig::default();
+    config.columns.insert(
+        "block_body".to_owned(),
+        ColumnConfig {
+            compression: Some(Compression::Lz4),
+            ..Default::default()
+        },
+    );
+    let columns = column_configs(&config);
+    assert_eq!(columns.len(), COLUMNS as usize);
+    assert_eq!(
+        columns[COLUMN_BLOCK_BODY as usize].compression,
+        Some(Compression::Lz4)
+    );
+    // the defaults not overridden are kept
+    assert!(columns[COLUMN_BLOCK_BODY as usize]
+        .block_cache_size
+        .is_some());
+    assert!(columns[COLUMN_TRANSACTION_ADDR as usize]
+        .bloom_filter_bits
+        .is_some());
+    assert_eq!(columns[COLUMN_META as usize], ColumnConfig::default());
+}
+
+#[test]
+fn test_column_statistics() {
+    let shared = new_shared();
+    let statistics = shared.store().get_column_statistics().unwrap();
+    let names: Vec<_> = statistics.iter().map(|(name, _)| *name).collect();
+    assert_eq!(&names[..], &COLUMN_NAMES[..]);
+    // the genesis block is indexed
+    assert_eq!(statistics[0].1.estimate_num_keys, Some(2));
+}
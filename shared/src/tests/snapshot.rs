@@ -0,0 +1,50 @@
+use crate::error::SharedError;
+use crate::migration::DB_VERSION;
+use crate::shared::SharedBuilder;
+use crate::snapshot::{cell_set_hash, SnapshotManifest};
+use crate::store::{ChainStore, StoreBatch};
+use ckb_chain_spec::consensus::Consensus;
+use ckb_core::block::BlockBuilder;
+use ckb_core::script::Script;
+use ckb_core::transaction::{CellInput, CellOutput, TransactionBuilder};
+use ckb_core::transaction_meta::TransactionMeta;
+use ckb_core::Capacity;
+use ckb_db::memorydb::MemoryKeyValueDB;
+use numext_fixed_hash::H256;
+
+#[test]
+fn verify_manifest() {
+    let cellbase = TransactionBuilder::default()
+        .input(CellInput::new_cellbase_input(0))
+        .output(CellOutput::new(
+            Capacity::zero(),
+            vec![],
+            Script::default(),
+            None,
+        ))
+        .build();
+    let genesis = BlockBuilder::default().transaction(cellbase).build();
+    let consensus = Consensus::default().set_genesis_block(genesis.clone());
+    let shared = SharedBuilder::<MemoryKeyValueDB>::new()
+        .consensus(consensus)
+        .build();
+    let store = shared.store();
+
+    let manifest = SnapshotManifest::new(store.as_ref()).expect("store is not empty");
+    assert_eq!(manifest.db_version, DB_VERSION);
+    assert_eq!(manifest.genesis_hash, genesis.header().hash());
+    assert_eq!(manifest.tip_hash, genesis.header().hash());
+    assert_eq!(manifest.tip_number, 0);
+    assert_eq!(manifest.verify(store.as_ref()), Ok(()));
+
+    let mut batch = store.new_batch().unwrap();
+    batch
+        .insert_tx_meta(&H256::zero(), &TransactionMeta::new(0, 1))
+        .unwrap();
+    batch.commit().unwrap();
+    assert_ne!(cell_set_hash(store.as_ref()), manifest.cell_set_hash);
+    match manifest.verify(store.as_ref()) {
+        Err(SharedError::InvalidSnapshot(_)) => {}
+        result => panic!("unexpected {:?}", result),
+    }
+}
@@ -4,5 +4,6 @@ pub mod types;
 
 pub use self::pool::TxPool;
 pub use self::types::{
-    OrphanPool, PendingQueue, PoolEntry, PoolError, StagingPool, StagingTxResult, TxPoolConfig,
+    OrphanPool, PendingQueue, PoolEntry, PoolError, PoolSet, StagingPool, StagingTxResult,
+    TxPoolConfig, TxPoolStatus,
 };
@@ -1,6 +1,8 @@
 //! Top-level Pool type, methods, and tests
 use super::trace::TxTraceMap;
-use super::types::{OrphanPool, PendingQueue, PoolEntry, StagingPool, TxPoolConfig};
+use super::types::{
+    OrphanPool, PendingQueue, PoolEntry, PoolSet, StagingPool, TxPoolConfig, TxPoolStatus,
+};
 use ckb_core::transaction::{OutPoint, ProposalShortId, Transaction};
 use faketime::unix_time_as_millis;
 use jsonrpc_types::TxTrace;
@@ -8,6 +10,8 @@ use log::trace;
 use lru_cache::LruCache;
 use numext_fixed_hash::H256;
 
+const FILTER_SIZE: usize = 1000;
+
 #[derive(Debug, Clone)]
 pub(crate) struct TxFilter {
     map: LruCache<H256, ()>,
@@ -23,6 +27,10 @@ impl TxFilter {
     pub fn insert(&mut self, hash: H256) -> bool {
         self.map.insert(hash, ()).is_none()
     }
+
+    pub fn remove(&mut self, hash: &H256) -> bool {
+        self.map.remove(hash).is_some()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -52,7 +60,7 @@ impl TxPool {
 
         TxPool {
             config,
-            filter: TxFilter::new(1000),
+            filter: TxFilter::new(FILTER_SIZE),
             pending: PendingQueue::new(),
             staging: StagingPool::new(),
             orphan: OrphanPool::new(),
@@ -117,13 +125,14 @@ impl TxPool {
         self.staging.add_tx(entry);
     }
 
-    pub(crate) fn committed(&mut self, tx: &Transaction) {
+    /// Returns the staging entries removed because they conflict with `tx`
+    pub(crate) fn committed(&mut self, tx: &Transaction) -> Vec<PoolEntry> {
         let hash = tx.hash();
         trace!(target: "tx_pool", "committed {:#x}", hash);
         if self.config.trace_enable() {
             self.trace.committed(&hash, "tx committed".to_string());
         }
-        self.staging.commit_tx(tx);
+        self.staging.commit_tx(tx)
     }
 
     pub(crate) fn remove_pending_from_proposal(
@@ -146,6 +155,22 @@ impl TxPool {
         self.staging.capacity() + self.orphan.capacity()
     }
 
+    pub fn pending_size(&self) -> usize {
+        self.pending.size()
+    }
+
+    pub fn staging_size(&self) -> usize {
+        self.staging.capacity()
+    }
+
+    pub fn orphan_size(&self) -> usize {
+        self.orphan.capacity()
+    }
+
+    pub fn conflict_size(&self) -> usize {
+        self.conflict.len()
+    }
+
     pub(crate) fn touch_last_txs_updated_at(&mut self) {
         self.last_txs_updated_at = unix_time_as_millis();
     }
@@ -154,6 +179,19 @@ impl TxPool {
         self.staging.txs_iter()
     }
 
+    pub fn pending_txs_iter(&self) -> impl Iterator<Item = &PoolEntry> {
+        self.pending.txs_iter()
+    }
+
+    pub fn orphan_txs_iter(&self) -> impl Iterator<Item = &PoolEntry> {
+        self.orphan.txs_iter()
+    }
+
+    /// Recently evicted or conflicting transactions, still useful for block reconstruction
+    pub fn conflict_txs_iter(&self) -> impl Iterator<Item = &PoolEntry> {
+        self.conflict.iter().map(|(_, entry)| entry)
+    }
+
     pub fn contains_proposal_id(&self, id: &ProposalShortId) -> bool {
         self.pending.contains_key(id)
             || self.conflict.contains_key(id)
@@ -170,12 +208,112 @@ impl TxPool {
             .cloned()
     }
 
+    /// The pending, orphan or staging transaction with the hash, the evicted and conflicting
+    /// ones are not in the pool anymore
+    pub fn get_tx_with_status(&self, hash: &H256) -> Option<(&Transaction, TxPoolStatus)> {
+        let id = ProposalShortId::from_tx_hash(hash);
+        self.pending
+            .get_tx(&id)
+            .or_else(|| self.orphan.get_tx(&id))
+            .map(|tx| (tx, TxPoolStatus::Pending))
+            .or_else(|| {
+                self.staging
+                    .get_tx(&id)
+                    .map(|tx| (tx, TxPoolStatus::Proposed))
+            })
+            // short ids may collide
+            .filter(|(tx, _)| &tx.hash() == hash)
+    }
+
+    pub fn get_entry(&self, id: &ProposalShortId) -> Option<&PoolEntry> {
+        self.pending
+            .get(id)
+            .or_else(|| self.staging.get(id))
+            .or_else(|| self.orphan.get(id))
+            .or_else(|| self.conflict.get(id))
+    }
+
+    /// The entry with the hash and the set it is in
+    pub fn get_entry_with_set(&self, hash: &H256) -> Option<(&PoolEntry, PoolSet)> {
+        let id = ProposalShortId::from_tx_hash(hash);
+        self.pending
+            .get(&id)
+            .map(|entry| (entry, PoolSet::Pending))
+            .or_else(|| self.staging.get(&id).map(|entry| (entry, PoolSet::Staging)))
+            .or_else(|| self.orphan.get(&id).map(|entry| (entry, PoolSet::Orphan)))
+            .or_else(|| self.conflict.get(&id).map(|entry| (entry, PoolSet::Conflict)))
+            // short ids may collide
+            .filter(|(entry, _)| &entry.transaction.hash() == hash)
+    }
+
+    /// The staging entries the entry depends on, the other sets do not track the dependencies
+    pub fn ancestors(&self, id: &ProposalShortId) -> Vec<&PoolEntry> {
+        self.staging
+            .ancestors(id)
+            .iter()
+            .filter_map(|id| self.staging.get(id))
+            .collect()
+    }
+
+    /// The staging entries depending on the entry, the other sets do not track the dependencies
+    pub fn descendants(&self, id: &ProposalShortId) -> Vec<&PoolEntry> {
+        self.staging
+            .descendants(id)
+            .iter()
+            .filter_map(|id| self.staging.get(id))
+            .collect()
+    }
+
+    /// Removes the transaction along with the staging and orphan transactions depending on it,
+    /// returns the removed entries
+    pub fn remove_tx(&mut self, hash: &H256) -> Vec<PoolEntry> {
+        if self.get_entry_with_set(hash).is_none() {
+            return Vec::new();
+        }
+        let id = ProposalShortId::from_tx_hash(hash);
+        let mut removed = Vec::new();
+        removed.extend(self.pending.remove(&id));
+        removed.extend(self.staging.remove(&id).unwrap_or_default());
+        removed.extend(self.orphan.recursion_remove(&id));
+        removed.extend(self.conflict.remove(&id));
+        // the orphans spending the removed transactions can never be resolved
+        let mut i = 0;
+        while i < removed.len() {
+            let orphans = self.orphan.remove_descendants(&removed[i].transaction);
+            removed.extend(orphans);
+            i += 1;
+        }
+        for entry in &removed {
+            trace!(target: "tx_pool", "remove {:#x}", entry.transaction.hash());
+            // allow the transaction to be submitted again
+            self.filter.remove(&entry.transaction.hash());
+        }
+        self.touch_last_txs_updated_at();
+        removed
+    }
+
+    /// Removes all the transactions
+    pub fn clear(&mut self) {
+        self.filter = TxFilter::new(FILTER_SIZE);
+        self.pending = PendingQueue::new();
+        self.staging = StagingPool::new();
+        self.orphan = OrphanPool::new();
+        self.conflict = LruCache::new(self.config.max_cache_size);
+        self.touch_last_txs_updated_at();
+    }
+
     //FIXME: use memsize
     pub fn is_full(&self) -> bool {
         self.capacity() > self.config.max_pool_size
     }
 
-    pub fn remove_expired<'a>(&mut self, ids: impl Iterator<Item = &'a ProposalShortId>) {
+    /// Moves the transactions whose proposals expired back to pending, returns the hashes of
+    /// those evicted to the conflict cache instead
+    pub fn remove_expired<'a>(
+        &mut self,
+        ids: impl Iterator<Item = &'a ProposalShortId>,
+    ) -> Vec<H256> {
+        let mut evicted = Vec::new();
         for id in ids {
             if let Some(entries) = self.staging.remove(id) {
                 let first = entries[0].clone();
@@ -190,6 +328,7 @@ impl TxPool {
                         self.trace
                             .expired(&entry.transaction.hash(), "tx proposal expired".to_string());
                     }
+                    evicted.push(entry.transaction.hash());
                     self.conflict
                         .insert(entry.transaction.proposal_short_id(), entry);
                 }
@@ -207,5 +346,118 @@ impl TxPool {
                 self.pending.insert(*id, entry);
             }
         }
+        evicted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ckb_core::transaction::{CellInput, TransactionBuilder};
+
+    fn build_tx(index: u32) -> Transaction {
+        TransactionBuilder::default()
+            .input(CellInput::new(
+                OutPoint::new(H256::zero(), index),
+                0,
+                vec![],
+            ))
+            .build()
+    }
+
+    #[test]
+    fn test_get_tx_with_status() {
+        let mut pool = TxPool::new(TxPoolConfig::default());
+        let pending = build_tx(0);
+        let proposed = build_tx(1);
+        pool.enqueue_tx(PoolEntry::new(pending.clone(), 0, None));
+        pool.add_staging(PoolEntry::new(proposed.clone(), 0, None));
+
+        assert_eq!(
+            pool.get_tx_with_status(&pending.hash()),
+            Some((&pending, TxPoolStatus::Pending))
+        );
+        assert_eq!(
+            pool.get_tx_with_status(&proposed.hash()),
+            Some((&proposed, TxPoolStatus::Proposed))
+        );
+        assert_eq!(pool.get_tx_with_status(&build_tx(2).hash()), None);
+    }
+
+    #[test]
+    fn test_remove_tx_with_descendants() {
+        let mut pool = TxPool::new(TxPoolConfig::default());
+        let parent = TransactionBuilder::default()
+            .input(CellInput::new(OutPoint::new(H256::zero(), 0), 0, vec![]))
+            .output(Default::default())
+            .build();
+        let child = TransactionBuilder::default()
+            .input(CellInput::new(OutPoint::new(parent.hash(), 0), 0, vec![]))
+            .build();
+        let pending = build_tx(1);
+        pool.add_staging(PoolEntry::new(parent.clone(), 0, None));
+        pool.add_staging(PoolEntry::new(child.clone(), 0, None));
+        pool.enqueue_tx(PoolEntry::new(pending.clone(), 0, None));
+
+        let parent_id = parent.proposal_short_id();
+        let child_id = child.proposal_short_id();
+        assert_eq!(
+            pool.descendants(&parent_id)
+                .into_iter()
+                .map(|entry| entry.transaction.clone())
+                .collect::<Vec<_>>(),
+            vec![child.clone()]
+        );
+        assert_eq!(
+            pool.ancestors(&child_id)
+                .into_iter()
+                .map(|entry| entry.transaction.clone())
+                .collect::<Vec<_>>(),
+            vec![parent.clone()]
+        );
+
+        assert_eq!(pool.remove_tx(&parent.hash()).len(), 2);
+        assert_eq!(pool.staging_size(), 0);
+        assert_eq!(pool.pending_size(), 1);
+        // removed transactions can be submitted again
+        assert!(pool.enqueue_tx(PoolEntry::new(parent, 0, None)));
+
+        pool.clear();
+        assert!(pool.get_entry_with_set(&pending.hash()).is_none());
+        assert_eq!(pool.pending_size(), 0);
+    }
+
+    #[test]
+    fn test_remove_tx_with_orphan_descendants() {
+        let mut pool = TxPool::new(TxPoolConfig::default());
+        let parent = TransactionBuilder::default()
+            .input(CellInput::new(OutPoint::new(H256::zero(), 0), 0, vec![]))
+            .output(Default::default())
+            .build();
+        let unknown = OutPoint::new(H256::zero(), 1);
+        // the orphan also misses another input, it is removed anyway
+        let orphan = TransactionBuilder::default()
+            .input(CellInput::new(OutPoint::new(parent.hash(), 0), 0, vec![]))
+            .input(CellInput::new(unknown.clone(), 0, vec![]))
+            .output(Default::default())
+            .build();
+        let grandchild = TransactionBuilder::default()
+            .input(CellInput::new(OutPoint::new(orphan.hash(), 0), 0, vec![]))
+            .build();
+        pool.enqueue_tx(PoolEntry::new(parent.clone(), 0, None));
+        pool.add_orphan(
+            PoolEntry::new(orphan.clone(), 0, None),
+            vec![OutPoint::new(parent.hash(), 0), unknown],
+        );
+        pool.add_orphan(
+            PoolEntry::new(grandchild.clone(), 0, None),
+            vec![OutPoint::new(orphan.hash(), 0)],
+        );
+        assert_eq!(pool.orphan_size(), 2);
+
+        assert_eq!(pool.remove_tx(&parent.hash()).len(), 3);
+        assert_eq!(pool.orphan_size(), 0);
+        assert!(pool.orphan.edges.is_empty());
+        assert_eq!(pool.pending_size(), 0);
     }
 }
@@ -6,6 +6,7 @@ use ckb_core::transaction::{CellOutput, OutPoint, ProposalShortId, Transaction};
 use ckb_core::Cycle;
 use ckb_verification::TransactionError;
 use failure::Fail;
+use faketime::unix_time_as_millis;
 use fnv::{FnvHashMap, FnvHashSet};
 use linked_hash_map::LinkedHashMap;
 use serde_derive::{Deserialize, Serialize};
@@ -57,6 +58,25 @@ pub enum StagingTxResult {
     Unknown,
 }
 
+/// The sets of the pool entries
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub enum PoolSet {
+    Pending,
+    Staging,
+    Orphan,
+    /// Evicted or conflicting transactions, they are not in the pool anymore
+    Conflict,
+}
+
+/// Where a transaction is in the pool
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub enum TxPoolStatus {
+    /// In the pending queue or the orphan pool, waiting to be proposed
+    Pending,
+    /// In the staging pool, proposed and waiting to be committed
+    Proposed,
+}
+
 // TODO document this enum more accurately
 /// Enum of errors
 #[derive(Debug, Clone, PartialEq, Fail)]
@@ -96,6 +116,8 @@ pub struct PoolEntry {
     pub refs_count: usize,
     /// Cycles
     pub cycles: Option<Cycle>,
+    /// The time the entry was created in milliseconds
+    pub timestamp: u64,
 }
 
 impl PoolEntry {
@@ -105,8 +127,15 @@ impl PoolEntry {
             transaction: tx,
             refs_count: count,
             cycles,
+            timestamp: unix_time_as_millis(),
         }
     }
+
+    /// The serialized size of the transaction in bytes, computed on demand since only the
+    /// inspection RPCs need it
+    pub fn size(&self) -> usize {
+        bincode::serialized_size(&self.transaction).expect("serialize transaction") as usize
+    }
 }
 
 impl Hash for PoolEntry {
@@ -375,7 +404,8 @@ impl StagingPool {
         self.vertices.insert(id, entry);
     }
 
-    pub fn commit_tx(&mut self, tx: &Transaction) {
+    /// Returns the entries removed because they conflict with `tx`
+    pub fn commit_tx(&mut self, tx: &Transaction) -> Vec<PoolEntry> {
         let outputs = tx.output_pts();
         let inputs = tx.input_pts();
         let deps = tx.dep_pts();
@@ -402,25 +432,29 @@ impl StagingPool {
             for d in deps {
                 self.edges.delete_value_in_deps(&d, &id)
             }
+            Vec::new()
         } else {
-            self.resolve_conflict(tx);
+            self.resolve_conflict(tx)
         }
     }
 
-    pub fn resolve_conflict(&mut self, tx: &Transaction) {
+    /// Removes the entries spending the inputs of `tx`, returns the removed entries
+    pub fn resolve_conflict(&mut self, tx: &Transaction) -> Vec<PoolEntry> {
         let inputs = tx.input_pts();
+        let mut removed = Vec::new();
 
         for i in inputs {
             if let Some(id) = self.edges.remove_outer(&i) {
-                self.remove(&id);
+                removed.extend(self.remove(&id).unwrap_or_default());
             }
 
             if let Some(x) = self.edges.remove_deps(&i) {
                 for id in x {
-                    self.remove(&id);
+                    removed.extend(self.remove(&id).unwrap_or_default());
                 }
             }
         }
+        removed
     }
 
     /// Get n transactions in topology
@@ -436,6 +470,59 @@ impl StagingPool {
         self.vertices.values()
     }
 
+    /// The entries in the pool whose outputs are spent or referenced by the entry, directly or
+    /// indirectly
+    pub fn ancestors(&self, id: &ProposalShortId) -> Vec<ProposalShortId> {
+        self.walk(id, |entry| {
+            entry
+                .transaction
+                .input_pts()
+                .into_iter()
+                .chain(entry.transaction.dep_pts())
+                .map(|o| ProposalShortId::from_tx_hash(&o.tx_hash))
+                .collect()
+        })
+    }
+
+    /// The entries in the pool spending or referencing the outputs of the entry, directly or
+    /// indirectly
+    pub fn descendants(&self, id: &ProposalShortId) -> Vec<ProposalShortId> {
+        self.walk(id, |entry| {
+            let mut children = Vec::new();
+            for o in entry.transaction.output_pts() {
+                if let Some(Some(cid)) = self.edges.get_inner(&o) {
+                    children.push(*cid);
+                }
+                if let Some(cids) = self.edges.get_deps(&o) {
+                    children.extend(cids.iter().cloned());
+                }
+            }
+            children
+        })
+    }
+
+    fn walk<F>(&self, id: &ProposalShortId, neighbours: F) -> Vec<ProposalShortId>
+    where
+        F: Fn(&PoolEntry) -> Vec<ProposalShortId>,
+    {
+        let mut visited = FnvHashSet::default();
+        visited.insert(*id);
+        let mut found = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(*id);
+        while let Some(id) = queue.pop_front() {
+            if let Some(entry) = self.vertices.get(&id) {
+                for nid in neighbours(entry) {
+                    if self.vertices.contains_key(&nid) && visited.insert(nid) {
+                        found.push(nid);
+                        queue.push_back(nid);
+                    }
+                }
+            }
+        }
+        found
+    }
+
     pub fn inc_ref(&mut self, id: &ProposalShortId) {
         if let Some(x) = self.vertices.get_mut(&id) {
             x.refs_count += 1;
@@ -469,6 +556,10 @@ impl OrphanPool {
         self.vertices.get(id)
     }
 
+    pub fn txs_iter(&self) -> impl Iterator<Item = &PoolEntry> {
+        self.vertices.values()
+    }
+
     pub fn get_tx(&self, id: &ProposalShortId) -> Option<&Transaction> {
         self.get(id).map(|x| &x.transaction)
     }
@@ -552,6 +643,45 @@ impl OrphanPool {
         txs
     }
 
+    /// Removes the orphans spending the outputs of `tx` and their orphan descendants, unlike
+    /// `remove_by_ancestor` they are removed even if they still miss other inputs
+    pub fn remove_descendants(&mut self, tx: &Transaction) -> Vec<PoolEntry> {
+        let mut txs = Vec::new();
+        let mut queue = VecDeque::new();
+
+        queue.push_back(tx.output_pts());
+        while let Some(outputs) = queue.pop_front() {
+            for o in outputs {
+                if let Some(ids) = self.edges.remove(&o) {
+                    for cid in ids {
+                        if let Some(x) = self.vertices.remove(&cid) {
+                            self.remove_edges(&cid, &x.transaction);
+                            queue.push_back(x.transaction.output_pts());
+                            txs.push(x);
+                        }
+                    }
+                }
+            }
+        }
+        txs
+    }
+
+    // drops the edges of the other out points the removed orphan is still waiting for
+    fn remove_edges(&mut self, id: &ProposalShortId, tx: &Transaction) {
+        for out_point in tx.input_pts().into_iter().chain(tx.dep_pts()) {
+            let empty = match self.edges.get_mut(&out_point) {
+                Some(ids) => {
+                    ids.retain(|x| x != id);
+                    ids.is_empty()
+                }
+                None => false,
+            };
+            if empty {
+                self.edges.remove(&out_point);
+            }
+        }
+    }
+
     pub fn remove_conflict(&mut self, tx: &Transaction) {
         let inputs = tx.input_pts();
 
@@ -604,6 +734,10 @@ impl PendingQueue {
     pub fn fetch(&self, n: usize) -> Vec<ProposalShortId> {
         self.inner.keys().take(n).cloned().collect()
     }
+
+    pub fn txs_iter(&self) -> impl Iterator<Item = &PoolEntry> {
+        self.inner.values()
+    }
 }
 
 impl CellProvider for PendingQueue {
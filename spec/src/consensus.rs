@@ -2,7 +2,9 @@ use ckb_core::block::{Block, BlockBuilder};
 use ckb_core::header::HeaderBuilder;
 use ckb_core::{capacity_bytes, BlockNumber, Capacity, Cycle, Version};
 use ckb_pow::{Pow, PowEngine};
+use numext_fixed_hash::H256;
 use numext_fixed_uint::U256;
+use serde_derive::Deserialize;
 use std::sync::Arc;
 
 pub(crate) const DEFAULT_BLOCK_REWARD: Capacity = capacity_bytes!(5_000);
@@ -35,6 +37,13 @@ impl ProposalWindow {
     }
 }
 
+/// A trusted (number, hash) pair, any chain conflicting with it is rejected
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize)]
+pub struct Checkpoint {
+    pub number: BlockNumber,
+    pub hash: H256,
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct Consensus {
     pub id: String,
@@ -59,6 +68,8 @@ pub struct Consensus {
     pub max_block_bytes: u64,
     // block version number supported
     pub block_version: Version,
+    // Trusted checkpoints, sorted by block number
+    pub checkpoints: Vec<Checkpoint>,
 }
 
 // genesis difficulty should not be zero
@@ -83,6 +94,7 @@ impl Default for Consensus {
             max_block_cycles: MAX_BLOCK_CYCLES,
             max_block_bytes: MAX_BLOCK_BYTES,
             block_version: BLOCK_VERSION,
+            checkpoints: Vec::new(),
         }
     }
 }
@@ -118,6 +130,13 @@ impl Consensus {
         self
     }
 
+    pub fn set_checkpoints(mut self, mut checkpoints: Vec<Checkpoint>) -> Self {
+        checkpoints.sort_by_key(|checkpoint| checkpoint.number);
+        checkpoints.dedup_by_key(|checkpoint| checkpoint.number);
+        self.checkpoints = checkpoints;
+        self
+    }
+
     pub fn genesis_block(&self) -> &Block {
         &self.genesis_block
     }
@@ -173,4 +192,28 @@ impl Consensus {
     pub fn tx_proposal_window(&self) -> ProposalWindow {
         self.tx_proposal_window
     }
+
+    pub fn checkpoints(&self) -> &[Checkpoint] {
+        &self.checkpoints
+    }
+
+    /// Returns the checkpoint at the given block number, if any
+    pub fn checkpoint(&self, number: BlockNumber) -> Option<&Checkpoint> {
+        self.checkpoints
+            .binary_search_by_key(&number, |checkpoint| checkpoint.number)
+            .ok()
+            .map(|index| &self.checkpoints[index])
+    }
+
+    /// Returns the checkpoint with the highest block number
+    pub fn last_checkpoint(&self) -> Option<&Checkpoint> {
+        self.checkpoints.last()
+    }
+
+    /// Whether a header with the given number and hash conflicts with a checkpoint
+    pub fn is_checkpoint_conflict(&self, number: BlockNumber, hash: &H256) -> bool {
+        self.checkpoint(number)
+            .map(|checkpoint| &checkpoint.hash != hash)
+            .unwrap_or(false)
+    }
 }
@@ -5,7 +5,7 @@
 //! In order to run a chain different to the official public one,
 //! with a config file specifying chain = "path" under [ckb].
 
-use crate::consensus::Consensus;
+use crate::consensus::{Checkpoint, Consensus};
 use ckb_core::block::BlockBuilder;
 use ckb_core::header::HeaderBuilder;
 use ckb_core::script::Script;
@@ -31,6 +31,7 @@ pub struct ChainSpec {
     pub params: Params,
     pub system_cells: Vec<Resource>,
     pub pow: Pow,
+    pub checkpoints: Vec<Checkpoint>,
 }
 
 #[derive(Deserialize)]
@@ -40,6 +41,8 @@ struct ChainSpecConfig {
     pub params: Params,
     pub system_cells: Vec<SystemCell>,
     pub pow: Pow,
+    #[serde(default)]
+    pub checkpoints: Vec<Checkpoint>,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize)]
@@ -119,6 +122,7 @@ impl ChainSpec {
             genesis: spec_config.genesis,
             params: spec_config.params,
             pow: spec_config.pow,
+            checkpoints: spec_config.checkpoints,
         })
     }
 
@@ -173,7 +177,8 @@ impl ChainSpec {
             .set_cellbase_maturity(self.params.cellbase_maturity)
             .set_initial_block_reward(self.params.initial_block_reward)
             .set_max_block_cycles(self.params.max_block_cycles)
-            .set_pow(self.pow.clone());
+            .set_pow(self.pow.clone())
+            .set_checkpoints(self.checkpoints.clone());
 
         Ok(consensus)
     }
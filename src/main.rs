@@ -30,6 +30,21 @@ fn run_app() -> Result<(), ExitCode> {
         (cli::CMD_MINER, _) => subcommand::miner(setup.miner()?),
         (cli::CMD_EXPORT, Some(matches)) => subcommand::export(setup.export(&matches)?),
         (cli::CMD_IMPORT, Some(matches)) => subcommand::import(setup.import(&matches)?),
+        (cli::CMD_MIGRATE, _) => subcommand::migrate(setup.migrate()?),
+        (cli::CMD_SNAPSHOT, Some(matches)) => match matches.subcommand() {
+            (cli::CMD_CREATE, Some(matches)) => {
+                subcommand::snapshot::create(setup.snapshot_create(&matches)?)
+            }
+            (cli::CMD_RESTORE, Some(matches)) => {
+                subcommand::snapshot::restore(setup.snapshot_restore(&matches)?)
+            }
+            _ => unreachable!(),
+        },
+        (cli::CMD_DB, Some(matches)) => match matches.subcommand() {
+            (cli::CMD_CHECK, Some(matches)) => subcommand::db::check(setup.db_check(&matches)?),
+            _ => unreachable!(),
+        },
+        (cli::CMD_RESET, Some(matches)) => subcommand::reset(setup.reset(&matches)?),
         _ => unreachable!(),
     }
 }
@@ -5,6 +5,7 @@ use serde_derive::Deserialize;
 
 use ckb_chain_spec::ChainSpec;
 use ckb_db::DBConfig;
+use ckb_metrics::Config as MetricsConfig;
 use ckb_miner::BlockAssemblerConfig;
 use ckb_miner::MinerConfig;
 use ckb_network::NetworkConfig;
@@ -41,6 +42,8 @@ pub struct CKBAppConfig {
     pub rpc: RpcConfig,
     pub sync: SyncConfig,
     pub tx_pool: TxPoolConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -1,9 +1,11 @@
 use super::app_config::CKBAppConfig;
 use ckb_chain_spec::consensus::Consensus;
+use ckb_core::BlockNumber;
 use ckb_instrument::Format;
 use ckb_miner::MinerConfig;
 use ckb_pow::PowEngine;
 use ckb_resource::ResourceLocator;
+use numext_fixed_hash::H256;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -21,6 +23,38 @@ pub struct ImportArgs {
     pub source: PathBuf,
 }
 
+pub struct MigrateArgs {
+    pub config: Box<CKBAppConfig>,
+}
+
+pub struct DBCheckArgs {
+    pub config: Box<CKBAppConfig>,
+    pub repair: bool,
+}
+
+pub enum ResetTarget {
+    Number(BlockNumber),
+    Hash(H256),
+}
+
+pub struct ResetArgs {
+    pub config: Box<CKBAppConfig>,
+    pub target: ResetTarget,
+    pub clear_peer_store: bool,
+}
+
+pub struct SnapshotCreateArgs {
+    pub config: Box<CKBAppConfig>,
+    pub target: PathBuf,
+}
+
+pub struct SnapshotRestoreArgs {
+    pub config: Box<CKBAppConfig>,
+    pub consensus: Consensus,
+    pub source: PathBuf,
+    pub cell_set_hash: Option<H256>,
+}
+
 pub struct RunArgs {
     pub config: Box<CKBAppConfig>,
     pub consensus: Consensus,
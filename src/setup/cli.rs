@@ -6,6 +6,13 @@ pub const CMD_RUN: &str = "run";
 pub const CMD_MINER: &str = "miner";
 pub const CMD_EXPORT: &str = "export";
 pub const CMD_IMPORT: &str = "import";
+pub const CMD_MIGRATE: &str = "migrate";
+pub const CMD_SNAPSHOT: &str = "snapshot";
+pub const CMD_CREATE: &str = "create";
+pub const CMD_RESTORE: &str = "restore";
+pub const CMD_DB: &str = "db";
+pub const CMD_CHECK: &str = "check";
+pub const CMD_RESET: &str = "reset";
 pub const CMD_INIT: &str = "init";
 pub const CMD_CLI: &str = "cli";
 pub const CMD_KEYGEN: &str = "keygen";
@@ -21,6 +28,10 @@ pub const ARG_P2P_PORT: &str = "p2p-port";
 pub const ARG_RPC_PORT: &str = "rpc-port";
 pub const ARG_FORCE: &str = "force";
 pub const ARG_LOG_TO: &str = "log-to";
+pub const ARG_REPAIR: &str = "repair";
+pub const ARG_TO: &str = "to";
+pub const ARG_CLEAR_PEER_STORE: &str = "clear-peer-store";
+pub const ARG_CELL_SET_HASH: &str = "cell-set-hash";
 
 pub fn get_matches() -> ArgMatches<'static> {
     let version = get_version!();
@@ -45,6 +56,10 @@ pub fn get_matches() -> ArgMatches<'static> {
         .subcommand(miner())
         .subcommand(export())
         .subcommand(import())
+        .subcommand(migrate())
+        .subcommand(snapshot())
+        .subcommand(db())
+        .subcommand(reset())
         .subcommand(cli())
         .subcommand(init())
         .get_matches()
@@ -98,6 +113,87 @@ fn import() -> App<'static, 'static> {
         )
 }
 
+fn migrate() -> App<'static, 'static> {
+    SubCommand::with_name(CMD_MIGRATE).about("Upgrade the database to the version of this binary")
+}
+
+fn snapshot() -> App<'static, 'static> {
+    SubCommand::with_name(CMD_SNAPSHOT)
+        .about("Create or restore a database snapshot")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name(CMD_CREATE)
+                .about("Copy the database and write its manifest")
+                .arg(
+                    Arg::with_name(ARG_TARGET)
+                        .short("t")
+                        .long(ARG_TARGET)
+                        .value_name("path")
+                        .required(true)
+                        .index(1)
+                        .help("Specify the snapshot directory, it must not exist."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name(CMD_RESTORE)
+                .about("Verify a snapshot and restore it into the empty database directory")
+                .arg(
+                    Arg::with_name(ARG_SOURCE)
+                        .short("s")
+                        .long(ARG_SOURCE)
+                        .value_name("path")
+                        .required(true)
+                        .index(1)
+                        .help("Specify the snapshot directory."),
+                )
+                .arg(
+                    Arg::with_name(ARG_CELL_SET_HASH)
+                        .long(ARG_CELL_SET_HASH)
+                        .value_name("hash")
+                        .takes_value(true)
+                        .help(
+                            "Specify the 0x-prefixed cell set hash the snapshot is expected to have, \
+                             published by a trusted source.",
+                        ),
+                ),
+        )
+}
+
+fn db() -> App<'static, 'static> {
+    SubCommand::with_name(CMD_DB)
+        .about("Database tools")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name(CMD_CHECK)
+                .about("Check the stored chain for inconsistencies")
+                .arg(
+                    Arg::with_name(ARG_REPAIR)
+                        .long(ARG_REPAIR)
+                        .help("Repair the inconsistencies which can be rebuilt from the store"),
+                ),
+        )
+}
+
+fn reset() -> App<'static, 'static> {
+    SubCommand::with_name(CMD_RESET)
+        .about("Move the tip of the stored chain back to an earlier block")
+        .arg(
+            Arg::with_name(ARG_TO)
+                .long(ARG_TO)
+                .value_name("number|hash")
+                .required(true)
+                .takes_value(true)
+                .help(
+                    "Specify the new tip, a block number or a 0x-prefixed main chain block hash.",
+                ),
+        )
+        .arg(
+            Arg::with_name(ARG_CLEAR_PEER_STORE)
+                .long(ARG_CLEAR_PEER_STORE)
+                .help("Remove the peer store as well"),
+        )
+}
+
 fn cli() -> App<'static, 'static> {
     SubCommand::with_name(CMD_CLI)
         .about("CLI tools")
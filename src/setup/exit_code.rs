@@ -1,3 +1,4 @@
+use ckb_shared::error::SharedError;
 use std::io;
 
 /// Uses 0, 64 - 113 as exit code.
@@ -7,6 +8,8 @@ pub enum ExitCode {
     Cli = 64,
     Config = 65,
     IO = 66,
+    /// The database schema version does not match the binary, see `ckb migrate`
+    IncompatibleDatabase = 67,
     Failure = 113,
 }
 
@@ -43,3 +46,15 @@ impl From<clap::Error> for ExitCode {
         ExitCode::Cli
     }
 }
+
+impl From<SharedError> for ExitCode {
+    fn from(err: SharedError) -> ExitCode {
+        eprintln!("Database Error: {}", err);
+        match err {
+            SharedError::OutdatedDBVersion(..) | SharedError::UnsupportedDBVersion(..) => {
+                ExitCode::IncompatibleDatabase
+            }
+            _ => ExitCode::Failure,
+        }
+    }
+}
@@ -5,7 +5,10 @@ mod exit_code;
 mod sentry_config;
 
 pub use app_config::AppConfig;
-pub use args::{ExportArgs, ImportArgs, InitArgs, MinerArgs, RunArgs};
+pub use args::{
+    DBCheckArgs, ExportArgs, ImportArgs, InitArgs, MigrateArgs, MinerArgs, ResetArgs, ResetTarget,
+    RunArgs, SnapshotCreateArgs, SnapshotRestoreArgs,
+};
 pub use exit_code::ExitCode;
 
 use ckb_chain_spec::{consensus::Consensus, ChainSpec};
@@ -14,6 +17,7 @@ use ckb_resource::ResourceLocator;
 use clap::{value_t, ArgMatches};
 use log::info;
 use logger::LoggerInitGuard;
+use numext_fixed_hash::H256;
 use std::path::PathBuf;
 
 pub struct Setup {
@@ -132,6 +136,88 @@ impl Setup {
         })
     }
 
+    pub fn migrate(self) -> Result<MigrateArgs, ExitCode> {
+        let config = self.config.into_ckb()?;
+
+        Ok(MigrateArgs { config })
+    }
+
+    pub fn db_check<'m>(self, matches: &ArgMatches<'m>) -> Result<DBCheckArgs, ExitCode> {
+        let config = self.config.into_ckb()?;
+        let repair = matches.is_present(cli::ARG_REPAIR);
+
+        Ok(DBCheckArgs { config, repair })
+    }
+
+    pub fn reset<'m>(self, matches: &ArgMatches<'m>) -> Result<ResetArgs, ExitCode> {
+        let config = self.config.into_ckb()?;
+        let to = matches.value_of(cli::ARG_TO).expect("required arg");
+        let target = if to.starts_with("0x") {
+            H256::from_hex_str(&to[2..]).map(ResetTarget::Hash).ok()
+        } else {
+            to.parse().map(ResetTarget::Number).ok()
+        }
+        .ok_or_else(|| {
+            eprintln!(
+                "Invalid --{} {}, expect a block number or hash",
+                cli::ARG_TO,
+                to
+            );
+            ExitCode::Cli
+        })?;
+        let clear_peer_store = matches.is_present(cli::ARG_CLEAR_PEER_STORE);
+
+        Ok(ResetArgs {
+            config,
+            target,
+            clear_peer_store,
+        })
+    }
+
+    pub fn snapshot_create<'m>(
+        self,
+        matches: &ArgMatches<'m>,
+    ) -> Result<SnapshotCreateArgs, ExitCode> {
+        let config = self.config.into_ckb()?;
+        let target = value_t!(matches.value_of(cli::ARG_TARGET), PathBuf)?;
+
+        Ok(SnapshotCreateArgs { config, target })
+    }
+
+    pub fn snapshot_restore<'m>(
+        self,
+        matches: &ArgMatches<'m>,
+    ) -> Result<SnapshotRestoreArgs, ExitCode> {
+        let consensus = self.consensus()?;
+        let config = self.config.into_ckb()?;
+        let source = value_t!(matches.value_of(cli::ARG_SOURCE), PathBuf)?;
+        let cell_set_hash = matches
+            .value_of(cli::ARG_CELL_SET_HASH)
+            .map(|hash| {
+                if hash.starts_with("0x") {
+                    H256::from_hex_str(&hash[2..]).ok()
+                } else {
+                    None
+                }
+                .ok_or_else(|| {
+                    eprintln!(
+                        "Invalid --{} {}, expect a 0x-prefixed hash",
+                        cli::ARG_CELL_SET_HASH,
+                        hash
+                    );
+                    ExitCode::Cli
+                })
+            })
+            .transpose()?;
+
+        Ok(SnapshotRestoreArgs {
+            config,
+            consensus,
+            source,
+            cell_set_hash,
+        })
+    }
+
     pub fn init<'m>(matches: &ArgMatches<'m>) -> Result<InitArgs, ExitCode> {
         let locator = locator_from_matches(matches)?;
         let export_specs = matches.is_present(cli::ARG_EXPORT_SPECS);
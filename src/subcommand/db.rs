@@ -0,0 +1,45 @@
+use crate::setup::{DBCheckArgs, ExitCode};
+use ckb_db::{CacheDB, RocksDB};
+use ckb_shared::consistency::ConsistencyChecker;
+use ckb_shared::migration::Migrations;
+use ckb_shared::shared::SharedBuilder;
+use log::info;
+
+const PROGRESS_LOG_INTERVAL: u64 = 10_000;
+
+pub fn check(args: DBCheckArgs) -> Result<(), ExitCode> {
+    let store = SharedBuilder::<CacheDB<RocksDB>>::default()
+        .db(&args.config.db)
+        .build_store();
+    Migrations::default().check(&store)?;
+
+    let progress = |done: u64, total: u64| {
+        if done % PROGRESS_LOG_INTERVAL == 0 || done == total {
+            info!(target: "main", "checked {}/{} blocks", done, total);
+        }
+    };
+    let mut report = ConsistencyChecker::new(&store).check(&progress);
+    for inconsistency in report.inconsistencies() {
+        eprintln!("{}", inconsistency);
+    }
+    if args.repair && report.repairs_count() > 0 {
+        info!(target: "main", "applying {} repairs", report.repairs_count());
+        report.repair(&store)?;
+        report = ConsistencyChecker::new(&store).check(&progress);
+        for inconsistency in report.inconsistencies() {
+            eprintln!("not repaired: {}", inconsistency);
+        }
+    }
+
+    if report.is_consistent() {
+        info!(target: "main", "database is consistent");
+        Ok(())
+    } else {
+        eprintln!(
+            "Found {} inconsistencies, {} can be repaired by `ckb db check --repair`",
+            report.inconsistencies().len(),
+            report.repairs_count()
+        );
+        Err(ExitCode::Failure)
+    }
+}
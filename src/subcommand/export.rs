@@ -7,7 +7,7 @@ pub fn export(args: ExportArgs) -> Result<(), ExitCode> {
     let shared = SharedBuilder::<CacheDB<RocksDB>>::default()
         .consensus(args.consensus)
         .db(&args.config.db)
-        .build();
+        .try_build()?;
     Export::new(shared, args.format, args.target)
         .execute()
         .map_err(|err| {
@@ -0,0 +1,36 @@
+use crate::setup::{ExitCode, MigrateArgs};
+use ckb_db::{CacheDB, RocksDB};
+use ckb_shared::migration::Migrations;
+use ckb_shared::shared::SharedBuilder;
+use log::info;
+use std::sync::Arc;
+
+const PROGRESS_LOG_INTERVAL: u64 = 10_000;
+
+pub fn migrate(args: MigrateArgs) -> Result<(), ExitCode> {
+    let store = Arc::new(
+        SharedBuilder::<CacheDB<RocksDB>>::default()
+            .db(&args.config.db)
+            .build_store(),
+    );
+
+    let (from, to) = Migrations::default().migrate(&store, &|migration, done, total| {
+        if done % PROGRESS_LOG_INTERVAL == 0 || done == total {
+            info!(
+                target: "main",
+                "migrating to version {} ({}): {}/{}",
+                migration.version(),
+                migration.description(),
+                done,
+                total
+            );
+        }
+    })?;
+
+    if from == to {
+        info!(target: "main", "database is up to date at version {}", to);
+    } else {
+        info!(target: "main", "database migrated from version {} to {}", from, to);
+    }
+    Ok(())
+}
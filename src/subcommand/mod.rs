@@ -1,12 +1,18 @@
 pub mod cli;
+pub mod db;
 mod export;
 mod import;
 mod init;
+mod migrate;
 mod miner;
+mod reset;
 mod run;
+pub mod snapshot;
 
 pub use self::export::export;
 pub use self::import::import;
 pub use self::init::init;
+pub use self::migrate::migrate;
 pub use self::miner::miner;
+pub use self::reset::reset;
 pub use self::run::run;
@@ -0,0 +1,52 @@
+use crate::setup::{ExitCode, ResetArgs, ResetTarget};
+use ckb_db::{CacheDB, RocksDB};
+use ckb_shared::migration::Migrations;
+use ckb_shared::rollback::rollback;
+use ckb_shared::shared::SharedBuilder;
+use ckb_shared::store::ChainStore;
+use log::info;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const PROGRESS_LOG_INTERVAL: u64 = 1_000;
+
+pub fn reset(args: ResetArgs) -> Result<(), ExitCode> {
+    let store = SharedBuilder::<CacheDB<RocksDB>>::default()
+        .db(&args.config.db)
+        .build_store();
+    Migrations::default().check(&store)?;
+    let store = Arc::new(store);
+
+    let target = match args.target {
+        ResetTarget::Number(number) => number,
+        ResetTarget::Hash(hash) => store.get_block_number(&hash).ok_or_else(|| {
+            eprintln!("Block {:#x} is not in the main chain", hash);
+            ExitCode::Cli
+        })?,
+    };
+
+    let progress = |done: u64, total: u64| {
+        if done % PROGRESS_LOG_INTERVAL == 0 || done == total {
+            info!(target: "main", "detached {}/{} blocks", done, total);
+        }
+    };
+    rollback(&store, target, &progress)?;
+    info!(target: "main", "the tip is reset to block {}", target);
+
+    // the tx pool is not persisted, it is empty when the node starts again
+    if args.clear_peer_store {
+        let path = args.config.network.peer_store_path();
+        // sqlite keeps uncheckpointed writes in the -wal and -shm files next to the database
+        for suffix in &["", "-wal", "-shm"] {
+            let mut file_name = path.clone().into_os_string();
+            file_name.push(suffix);
+            let file = PathBuf::from(file_name);
+            if file.exists() {
+                fs::remove_file(&file)?;
+                info!(target: "main", "removed the peer store file {}", file.display());
+            }
+        }
+    }
+    Ok(())
+}
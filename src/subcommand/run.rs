@@ -2,6 +2,7 @@ use crate::helper::{deadlock_detection, wait_for_exit};
 use crate::setup::{ExitCode, RunArgs};
 use ckb_chain::chain::{ChainBuilder, ChainController};
 use ckb_db::{CacheDB, RocksDB};
+use ckb_metrics::NodeMetrics;
 use ckb_miner::BlockAssembler;
 use ckb_network::{CKBProtocol, NetworkService, NetworkState};
 use ckb_notify::{NotifyController, NotifyService};
@@ -20,7 +21,7 @@ pub fn run(args: RunArgs) -> Result<(), ExitCode> {
         .consensus(args.consensus)
         .db(&args.config.db)
         .tx_pool_config(args.config.tx_pool)
-        .build();
+        .try_build()?;
 
     let notify = NotifyService::default().start(Some("notify"));
 
@@ -35,13 +36,17 @@ pub fn run(args: RunArgs) -> Result<(), ExitCode> {
     );
     let synchronizer =
         Synchronizer::new(chain_controller.clone(), shared.clone(), args.config.sync);
+    let rpc_synchronizer = synchronizer.clone();
+    let metrics_synchronizer = synchronizer.clone();
 
     let relayer = Relayer::new(
         chain_controller.clone(),
         shared.clone(),
         synchronizer.peers(),
     );
+    let rpc_relayer = relayer.clone();
     let net_timer = NetTimeProtocol::default();
+    let rpc_net_timer = net_timer.clone();
 
     let protocols = vec![
         CKBProtocol::new(
@@ -72,12 +77,26 @@ pub fn run(args: RunArgs) -> Result<(), ExitCode> {
 
     let rpc_server = RpcServer::new(
         args.config.rpc,
-        network_controller,
-        shared,
-        chain_controller,
+        network_controller.clone(),
+        shared.clone(),
+        chain_controller.clone(),
         block_assembler_controller,
+        rpc_synchronizer,
+        rpc_relayer,
+        rpc_net_timer,
     );
 
+    if let Some(ref listen_address) = args.config.metrics.listen_address {
+        let metrics = NodeMetrics {
+            shared,
+            chain: chain_controller,
+            network: network_controller,
+            synchronizer: metrics_synchronizer,
+            rpc: Some(rpc_server.metrics()),
+        };
+        ckb_metrics::start(listen_address, metrics)?;
+    }
+
     wait_for_exit();
 
     info!(target: "main", "Finishing work, please wait...");
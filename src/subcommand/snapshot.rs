@@ -0,0 +1,147 @@
+use crate::setup::{ExitCode, SnapshotCreateArgs, SnapshotRestoreArgs};
+use ckb_db::{CacheDB, DBConfig, RocksDB};
+use ckb_shared::migration::{Migrations, DB_VERSION};
+use ckb_shared::shared::SharedBuilder;
+use ckb_shared::snapshot::SnapshotManifest;
+use ckb_shared::store::ChainKVStore;
+use ckb_shared::COLUMNS;
+use log::info;
+use std::fs;
+use std::path::Path;
+
+const MANIFEST_FILE_NAME: &str = "manifest.toml";
+const DB_DIR_NAME: &str = "db";
+
+pub fn create(args: SnapshotCreateArgs) -> Result<(), ExitCode> {
+    if args.target.exists() {
+        eprintln!("Snapshot target {} already exists", args.target.display());
+        return Err(ExitCode::Cli);
+    }
+
+    let store = SharedBuilder::<CacheDB<RocksDB>>::default()
+        .db(&args.config.db)
+        .build_store();
+    Migrations::default().check(&store)?;
+    // the store is locked while it's open, so the checkpoint is taken of what the manifest
+    // describes, and the checkpoint itself is never opened
+    let manifest = SnapshotManifest::new(&store).ok_or_else(|| {
+        eprintln!("Snapshot error: the database is empty");
+        ExitCode::Failure
+    })?;
+    fs::create_dir_all(&args.target)?;
+    store
+        .checkpoint(&args.target.join(DB_DIR_NAME))
+        .map_err(|err| {
+            eprintln!("Snapshot error: {}", err);
+            ExitCode::Failure
+        })?;
+    drop(store);
+
+    let content = toml::to_string(&manifest).map_err(|err| {
+        eprintln!("Snapshot error: {}", err);
+        ExitCode::Failure
+    })?;
+    fs::write(args.target.join(MANIFEST_FILE_NAME), content)?;
+
+    info!(
+        target: "main",
+        "snapshot of block {} {:#x} with cell set hash {:#x} written to {}",
+        manifest.tip_number,
+        manifest.tip_hash,
+        manifest.cell_set_hash,
+        args.target.display()
+    );
+    Ok(())
+}
+
+pub fn restore(args: SnapshotRestoreArgs) -> Result<(), ExitCode> {
+    let manifest: SnapshotManifest =
+        toml::from_slice(&fs::read(args.source.join(MANIFEST_FILE_NAME))?)?;
+    let genesis_hash = args.consensus.genesis_block().header().hash();
+    if manifest.genesis_hash != genesis_hash {
+        eprintln!(
+            "Snapshot genesis {:#x} does not match the chain spec genesis {:#x}",
+            manifest.genesis_hash, genesis_hash
+        );
+        return Err(ExitCode::Config);
+    }
+    if manifest.db_version > DB_VERSION {
+        eprintln!(
+            "Snapshot database version {} is newer than {} supported by this binary",
+            manifest.db_version, DB_VERSION
+        );
+        return Err(ExitCode::IncompatibleDatabase);
+    }
+    // the manifest ships with the snapshot, only an expected hash from elsewhere catches tampering
+    if let Some(ref cell_set_hash) = args.cell_set_hash {
+        if &manifest.cell_set_hash != cell_set_hash {
+            eprintln!(
+                "Snapshot cell set hash {:#x} does not match the expected {:#x}",
+                manifest.cell_set_hash, cell_set_hash
+            );
+            return Err(ExitCode::Config);
+        }
+    }
+
+    let db_path = &args.config.db.path;
+    if fs::read_dir(db_path)?.next().is_some() {
+        eprintln!(
+            "Database directory {} is not empty, remove it to restore the snapshot",
+            db_path.display()
+        );
+        return Err(ExitCode::Cli);
+    }
+
+    // verify the copy rather than the source, opening the source would write to it
+    copy_dir(&args.source.join(DB_DIR_NAME), db_path)?;
+    let verified = manifest.verify(&open_store(db_path));
+    if let Err(err) = verified {
+        for entry in fs::read_dir(db_path)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                fs::remove_dir_all(entry.path())?;
+            } else {
+                fs::remove_file(entry.path())?;
+            }
+        }
+        return Err(err.into());
+    }
+
+    info!(
+        target: "main",
+        "restored the snapshot of block {} {:#x} into {}",
+        manifest.tip_number,
+        manifest.tip_hash,
+        db_path.display()
+    );
+    if manifest.db_version < DB_VERSION {
+        info!(
+            target: "main",
+            "the snapshot database version is {}, run `ckb migrate` before `ckb run`",
+            manifest.db_version
+        );
+    }
+    Ok(())
+}
+
+fn copy_dir(source: &Path, target: &Path) -> Result<(), ExitCode> {
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let target = target.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            fs::create_dir(&target)?;
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+fn open_store(path: &Path) -> ChainKVStore<RocksDB> {
+    let config = DBConfig {
+        path: path.to_path_buf(),
+        ..Default::default()
+    };
+    ChainKVStore::new(RocksDB::open(&config, COLUMNS))
+}
@@ -4,6 +4,7 @@ use ckb_util::RwLock;
 use flatbuffers::FlatBufferBuilder;
 use log::{debug, info, warn};
 use std::collections::VecDeque;
+use std::sync::Arc;
 
 const TOLERANT_OFFSET: u64 = 7_200_000;
 const MIN_SAMPLES: usize = 5;
@@ -71,32 +72,33 @@ impl Default for NetTimeChecker {
 }
 
 /// Collect time offset samples from network peers and send notify to user if offset is too large
+///
+/// The clones share the samples.
+#[derive(Clone)]
 pub struct NetTimeProtocol {
-    checker: RwLock<NetTimeChecker>,
-}
-
-impl Clone for NetTimeProtocol {
-    fn clone(&self) -> Self {
-        NetTimeProtocol {
-            checker: RwLock::new(self.checker.read().clone()),
-        }
-    }
+    checker: Arc<RwLock<NetTimeChecker>>,
 }
 
 impl NetTimeProtocol {
     pub fn new(min_samples: usize, max_samples: usize, tolerant_offset: u64) -> Self {
-        let checker = RwLock::new(NetTimeChecker::new(
+        let checker = Arc::new(RwLock::new(NetTimeChecker::new(
             min_samples,
             max_samples,
             tolerant_offset,
-        ));
+        )));
         NetTimeProtocol { checker }
     }
+
+    /// Returns the median offset of the local clock from the network peers when it exceeds
+    /// the tolerance
+    pub fn check(&self) -> Result<(), i64> {
+        self.checker.read().check()
+    }
 }
 
 impl Default for NetTimeProtocol {
     fn default() -> Self {
-        let checker = RwLock::new(NetTimeChecker::default());
+        let checker = Arc::new(RwLock::new(NetTimeChecker::default()));
         NetTimeProtocol { checker }
     }
 }
@@ -189,4 +191,15 @@ mod tests {
         ntc.add_sample(-(TOLERANT_OFFSET as i64) - 3);
         assert_eq!(ntc.check().unwrap_err(), -(TOLERANT_OFFSET as i64) - 1);
     }
+
+    #[test]
+    fn test_clones_share_samples() {
+        let protocol = NetTimeProtocol::new(1, 5, TOLERANT_OFFSET);
+        let cloned = protocol.clone();
+        cloned
+            .checker
+            .write()
+            .add_sample(TOLERANT_OFFSET as i64 + 1);
+        assert_eq!(protocol.check().unwrap_err(), TOLERANT_OFFSET as i64 + 1);
+    }
 }
@@ -0,0 +1,74 @@
+use crate::relayer::{Relayer, MAX_RELAY_TX_HASHES_PER_BATCH};
+use ckb_core::transaction::ProposalShortId;
+use ckb_network::{CKBProtocolContext, PeerIndex};
+use ckb_protocol::{cast, GetRelayTransactions, RelayMessage};
+use ckb_shared::store::ChainStore;
+use failure::Error as FailureError;
+use flatbuffers::FlatBufferBuilder;
+use log::debug;
+use numext_fixed_hash::H256;
+use std::convert::TryInto;
+
+pub struct GetTransactionsProcess<'a, CS> {
+    message: &'a GetRelayTransactions<'a>,
+    relayer: &'a Relayer<CS>,
+    nc: &'a CKBProtocolContext,
+    peer: PeerIndex,
+}
+
+impl<'a, CS: ChainStore> GetTransactionsProcess<'a, CS> {
+    pub fn new(
+        message: &'a GetRelayTransactions,
+        relayer: &'a Relayer<CS>,
+        nc: &'a CKBProtocolContext,
+        peer: PeerIndex,
+    ) -> Self {
+        GetTransactionsProcess {
+            message,
+            relayer,
+            nc,
+            peer,
+        }
+    }
+
+    pub fn execute(self) -> Result<(), FailureError> {
+        let tx_hashes = cast!(self.message.tx_hashes())?;
+
+        if tx_hashes.len() > MAX_RELAY_TX_HASHES_PER_BATCH {
+            debug!(target: "relay", "peer {} requests too many txs: {}", self.peer, tx_hashes.len());
+            cast!(None)?;
+        }
+
+        let tx_hashes = tx_hashes
+            .iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<H256>, FailureError>>()?;
+
+        let entries = {
+            let chain_state = self.relayer.shared.chain_state().lock();
+            let tx_pool = chain_state.tx_pool();
+            tx_hashes
+                .iter()
+                .filter_map(|tx_hash| {
+                    tx_pool
+                        .get_entry(&ProposalShortId::from_tx_hash(tx_hash))
+                        .and_then(|entry| {
+                            entry
+                                .cycles
+                                .map(|cycles| (entry.transaction.clone(), cycles))
+                        })
+                })
+                .collect::<Vec<_>>()
+        };
+
+        if !entries.is_empty() {
+            let fbb = &mut FlatBufferBuilder::new();
+            let message = RelayMessage::build_transactions(fbb, &entries);
+            fbb.finish(message, None);
+            self.nc
+                .send_message_to(self.peer, fbb.finished_data().to_vec());
+        }
+
+        Ok(())
+    }
+}
@@ -4,7 +4,11 @@ pub mod compact_block;
 mod compact_block_process;
 mod get_block_proposal_process;
 mod get_block_transactions_process;
+mod get_transactions_process;
+mod send_compact_block_process;
+mod transaction_hashes_process;
 mod transaction_process;
+mod transactions_process;
 
 use self::block_proposal_process::BlockProposalProcess;
 use self::block_transactions_process::BlockTransactionsProcess;
@@ -12,7 +16,11 @@ use self::compact_block::CompactBlock;
 use self::compact_block_process::CompactBlockProcess;
 use self::get_block_proposal_process::GetBlockProposalProcess;
 use self::get_block_transactions_process::GetBlockTransactionsProcess;
+use self::get_transactions_process::GetTransactionsProcess;
+use self::send_compact_block_process::SendCompactBlockProcess;
+use self::transaction_hashes_process::TransactionHashesProcess;
 use self::transaction_process::TransactionProcess;
+use self::transactions_process::TransactionsProcess;
 use crate::relayer::compact_block::ShortTransactionID;
 use crate::types::Peers;
 use ckb_chain::chain::ChainController;
@@ -35,13 +43,26 @@ use fnv::{FnvHashMap, FnvHashSet};
 use log::{debug, info};
 use lru_cache::LruCache;
 use numext_fixed_hash::H256;
+use rand::{thread_rng, Rng};
 use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Duration;
 
 pub const TX_PROPOSAL_TOKEN: u64 = 0;
+pub const TX_HASHES_TOKEN: u64 = 1;
+pub const TX_REQUEST_TOKEN: u64 = 2;
 pub const MAX_RELAY_PEERS: usize = 128;
+// Peers asked to announce compact blocks to us before validating them
+pub const MAX_HIGH_BANDWIDTH_PEERS: usize = 3;
 pub const TX_FILTER_SIZE: usize = 1000;
+pub const MAX_RELAY_TX_HASHES_PER_BATCH: usize = 1000;
+pub const INFLIGHT_TRANSACTIONS_SIZE: usize = 50_000;
+// Average delay between two announcements to the same peer, in ms
+pub const TX_TRICKLE_INTERVAL: u64 = 500;
+// Another announcer is asked for the transaction if the requested peer does not respond in time
+pub const TX_REQUEST_TIMEOUT: u64 = 10 * 1000;
+// The announcers remembered for a requested transaction besides the requested one
+pub const MAX_TX_ANNOUNCERS: usize = 8;
 
 pub struct Relayer<CS> {
     chain: ChainController,
@@ -97,6 +118,15 @@ impl<CS: ChainStore> Relayer<CS> {
                 )
                 .execute()?;
             }
+            RelayPayload::RelayTransactions => {
+                TransactionsProcess::new(
+                    &cast!(message.payload_as_relay_transactions())?,
+                    self,
+                    nc,
+                    peer,
+                )
+                .execute()?;
+            }
             RelayPayload::GetBlockTransactions => {
                 GetBlockTransactionsProcess::new(
                     &cast!(message.payload_as_get_block_transactions())?,
@@ -128,6 +158,32 @@ impl<CS: ChainStore> Relayer<CS> {
                 BlockProposalProcess::new(&cast!(message.payload_as_block_proposal())?, self)
                     .execute()?;
             }
+            RelayPayload::RelayTransactionHashes => {
+                TransactionHashesProcess::new(
+                    &cast!(message.payload_as_relay_transaction_hashes())?,
+                    self,
+                    nc,
+                    peer,
+                )
+                .execute()?;
+            }
+            RelayPayload::GetRelayTransactions => {
+                GetTransactionsProcess::new(
+                    &cast!(message.payload_as_get_relay_transactions())?,
+                    self,
+                    nc,
+                    peer,
+                )
+                .execute()?;
+            }
+            RelayPayload::SendCompactBlock => {
+                SendCompactBlockProcess::new(
+                    &cast!(message.payload_as_send_compact_block())?,
+                    self,
+                    peer,
+                )
+                .execute()?;
+            }
             RelayPayload::NONE => {
                 cast!(None)?;
             }
@@ -168,30 +224,211 @@ impl<CS: ChainStore> Relayer<CS> {
     }
 
     pub fn accept_block(&self, nc: &CKBProtocolContext, peer: PeerIndex, block: &Arc<Block>) {
+        let block_hash = block.header().hash();
+        // high bandwidth peers get the block once its header is verified,
+        // the others wait until it passes the full validation
+        let high_bandwidth_peers: Vec<PeerIndex> = {
+            let high_bandwidth_peers = self.state.high_bandwidth_peers.lock();
+            let mut known_blocks = self.peers.known_blocks.lock();
+            high_bandwidth_peers
+                .iter()
+                .filter(|target_peer| {
+                    known_blocks.insert(**target_peer, block_hash.clone())
+                        && (peer != **target_peer)
+                })
+                .cloned()
+                .collect()
+        };
+        self.send_compact_block(nc, block, &high_bandwidth_peers);
+
         let ret = self.chain.process_block(Arc::clone(&block));
 
         if ret.is_ok() {
-            debug!(target: "relay", "[block_relay] relayer accept_block {} {}", block.header().hash(), unix_time_as_millis());
-            let block_hash = block.header().hash();
+            debug!(target: "relay", "[block_relay] relayer accept_block {} {}", block_hash, unix_time_as_millis());
+            nc.new_block_received(peer);
+            let selected_peers: Vec<PeerIndex> = {
+                let mut known_blocks = self.peers.known_blocks.lock();
+                nc.connected_peers()
+                    .into_iter()
+                    .filter(|target_peer| {
+                        known_blocks.insert(*target_peer, block_hash.clone())
+                            && (peer != *target_peer)
+                    })
+                    .take(MAX_RELAY_PEERS)
+                    .collect()
+            };
+            self.send_compact_block(nc, block, &selected_peers);
+            self.update_high_bandwidth_senders(nc, peer);
+        } else {
+            debug!(target: "relay", "accept_block verify error {:?}", ret);
+        }
+    }
+
+    // Prefill the transactions which the target peer is not known to have,
+    // so it can rebuild the block without a `GetBlockTransactions` round trip.
+    fn send_compact_block(
+        &self,
+        nc: &CKBProtocolContext,
+        block: &Block,
+        target_peers: &[PeerIndex],
+    ) {
+        if target_peers.is_empty() {
+            return;
+        }
+        let known_txs = self.peers.known_txs.lock();
+        for target_peer in target_peers {
+            let prefilled_transactions_indexes = block
+                .transactions()
+                .iter()
+                .enumerate()
+                .skip(1)
+                .filter(|(_, tx)| !known_txs.contains(target_peer, &tx.hash()))
+                .map(|(index, _)| index)
+                .collect::<HashSet<_>>();
             let fbb = &mut FlatBufferBuilder::new();
-            let message = RelayMessage::build_compact_block(fbb, block, &HashSet::new());
+            let message =
+                RelayMessage::build_compact_block(fbb, block, &prefilled_transactions_indexes);
             fbb.finish(message, None);
+            nc.send_message_to(*target_peer, fbb.finished_data().to_vec());
+        }
+    }
 
-            let mut known_blocks = self.peers.known_blocks.lock();
-            let selected_peers: Vec<PeerIndex> = nc
-                .connected_peers()
-                .into_iter()
-                .filter(|target_peer| {
-                    known_blocks.insert(*target_peer, block_hash.clone()) && (peer != *target_peer)
-                })
-                .take(MAX_RELAY_PEERS)
-                .collect();
+    // The peers which most recently delivered new blocks to us are asked to switch
+    // to the high bandwidth mode, the least recent one is switched back when full.
+    fn update_high_bandwidth_senders(&self, nc: &CKBProtocolContext, peer: PeerIndex) {
+        let evicted = {
+            let mut senders = self.state.high_bandwidth_senders.lock();
+            if let Some(position) = senders.iter().position(|sender| *sender == peer) {
+                senders.remove(position);
+                senders.push(peer);
+                return;
+            }
+            senders.push(peer);
+            if senders.len() > MAX_HIGH_BANDWIDTH_PEERS {
+                Some(senders.remove(0))
+            } else {
+                None
+            }
+        };
 
-            for target_peer in selected_peers {
-                nc.send_message_to(target_peer, fbb.finished_data().to_vec());
+        let fbb = &mut FlatBufferBuilder::new();
+        let message = RelayMessage::build_send_compact_block(fbb, true);
+        fbb.finish(message, None);
+        nc.send_message_to(peer, fbb.finished_data().to_vec());
+
+        if let Some(evicted) = evicted {
+            let fbb = &mut FlatBufferBuilder::new();
+            let message = RelayMessage::build_send_compact_block(fbb, false);
+            fbb.finish(message, None);
+            nc.send_message_to(evicted, fbb.finished_data().to_vec());
+        }
+    }
+
+    // Queue the tx hash for every connected peer which does not know it yet,
+    // the queues are flushed by `send_bulk_of_tx_hashes` on trickle timers.
+    // The peers only know the hash once it is sent, see `send_compact_block`.
+    pub fn announce_transaction(&self, nc: &CKBProtocolContext, source: PeerIndex, tx_hash: H256) {
+        self.peers.known_txs.lock().insert(source, tx_hash.clone());
+        self.queue_tx_hash(nc, tx_hash);
+    }
+
+    /// Announces a transaction submitted to this node, such as through the rpc. It is queued for
+    /// the peers on the next trickle timer, the same as the relayed ones.
+    pub fn announce_local_transaction(&self, tx_hash: H256) {
+        self.state.local_tx_hashes.lock().push(tx_hash);
+    }
+
+    fn queue_tx_hash(&self, nc: &CKBProtocolContext, tx_hash: H256) {
+        let known_txs = self.peers.known_txs.lock();
+        let mut tx_hashes = self.state.tx_hashes.lock();
+        for target_peer in nc
+            .connected_peers()
+            .into_iter()
+            .filter(|target_peer| !known_txs.contains(target_peer, &tx_hash))
+            .take(MAX_RELAY_PEERS)
+        {
+            tx_hashes
+                .entry(target_peer)
+                .or_insert_with(TxHashesQueue::default)
+                .hashes
+                .push(tx_hash.clone());
+        }
+    }
+
+    fn send_bulk_of_tx_hashes(&self, nc: &CKBProtocolContext) {
+        let local_tx_hashes: Vec<H256> = self.state.local_tx_hashes.lock().drain(..).collect();
+        for tx_hash in local_tx_hashes {
+            self.queue_tx_hash(nc, tx_hash);
+        }
+
+        let now = unix_time_as_millis();
+        let mut selected = FnvHashMap::default();
+        {
+            let mut rng = thread_rng();
+            let mut tx_hashes = self.state.tx_hashes.lock();
+            for (peer, queue) in tx_hashes.iter_mut() {
+                if queue.hashes.is_empty() || queue.next_announce_at > now {
+                    continue;
+                }
+                let len = std::cmp::min(queue.hashes.len(), MAX_RELAY_TX_HASHES_PER_BATCH);
+                selected.insert(*peer, queue.hashes.drain(..len).collect::<Vec<_>>());
+                // randomize the delay so that the origin of a transaction is harder to trace
+                queue.next_announce_at = now + rng.gen_range(0, TX_TRICKLE_INTERVAL * 2);
+            }
+        }
+
+        for (peer, mut hashes) in selected {
+            // skip the hashes queued more than once or learned from the peer meanwhile
+            {
+                let mut known_txs = self.peers.known_txs.lock();
+                hashes.retain(|tx_hash| known_txs.insert(peer, tx_hash.clone()));
+            }
+            if hashes.is_empty() {
+                continue;
+            }
+            let fbb = &mut FlatBufferBuilder::new();
+            let message = RelayMessage::build_transaction_hashes(fbb, &hashes);
+            fbb.finish(message, None);
+            nc.send_message_to(peer, fbb.finished_data().to_vec());
+        }
+    }
+
+    // Ask the next announcer for the transactions whose requests timed out, those announced by
+    // no other peer are forgotten until they are announced again.
+    fn retry_tx_requests(&self, nc: &CKBProtocolContext) {
+        let now = unix_time_as_millis();
+        let mut requests: FnvHashMap<PeerIndex, Vec<H256>> = FnvHashMap::default();
+        {
+            let mut inflight = self.state.inflight_transactions.lock();
+            let mut expired = Vec::new();
+            for (tx_hash, request) in inflight.iter_mut() {
+                if now < request.requested_at + TX_REQUEST_TIMEOUT {
+                    continue;
+                }
+                if request.announcers.is_empty() {
+                    expired.push(tx_hash.clone());
+                    continue;
+                }
+                request.peer = request.announcers.remove(0);
+                request.requested_at = now;
+                requests
+                    .entry(request.peer)
+                    .or_insert_with(Vec::new)
+                    .push(tx_hash.clone());
+            }
+            for tx_hash in expired {
+                inflight.remove(&tx_hash);
+            }
+        }
+
+        for (peer, tx_hashes) in requests {
+            debug!(target: "relay", "request {} timed out txs from peer {}", tx_hashes.len(), peer);
+            for tx_hashes in tx_hashes.chunks(MAX_RELAY_TX_HASHES_PER_BATCH) {
+                let fbb = &mut FlatBufferBuilder::new();
+                let message = RelayMessage::build_get_transactions(fbb, tx_hashes);
+                fbb.finish(message, None);
+                nc.send_message_to(peer, fbb.finished_data().to_vec());
             }
-        } else {
-            debug!(target: "relay", "accept_block verify error {:?}", ret);
         }
     }
 
@@ -214,14 +451,22 @@ impl<CS: ChainStore> Relayer<CS> {
 
         {
             let tx_pool = chain_state.tx_pool();
-            let iter = tx_pool.staging_txs_iter().filter_map(|entry| {
-                let short_id = short_transaction_id(key0, key1, &entry.transaction.witness_hash());
-                if compact_block.short_ids.contains(&short_id) {
-                    Some((short_id, entry.transaction.clone()))
-                } else {
-                    None
-                }
-            });
+            // transactions not proposed yet, orphans and recently evicted ones may
+            // also be committed in the block, try them all before asking the peer
+            let iter = tx_pool
+                .staging_txs_iter()
+                .chain(tx_pool.pending_txs_iter())
+                .chain(tx_pool.orphan_txs_iter())
+                .chain(tx_pool.conflict_txs_iter())
+                .filter_map(|entry| {
+                    let short_id =
+                        short_transaction_id(key0, key1, &entry.transaction.witness_hash());
+                    if compact_block.short_ids.contains(&short_id) {
+                        Some((short_id, entry.transaction.clone()))
+                    } else {
+                        None
+                    }
+                });
             txs_map.extend(iter);
         }
 
@@ -311,6 +556,8 @@ impl<CS: ChainStore> Relayer<CS> {
 impl<CS: ChainStore> CKBProtocolHandler for Relayer<CS> {
     fn init(&mut self, nc: Box<dyn CKBProtocolContext>) {
         nc.set_notify(Duration::from_millis(100), TX_PROPOSAL_TOKEN);
+        nc.set_notify(Duration::from_millis(100), TX_HASHES_TOKEN);
+        nc.set_notify(Duration::from_millis(1000), TX_REQUEST_TOKEN);
     }
 
     fn received(
@@ -344,12 +591,26 @@ impl<CS: ChainStore> CKBProtocolHandler for Relayer<CS> {
 
     fn disconnected(&mut self, _nc: Box<dyn CKBProtocolContext>, peer_index: PeerIndex) {
         info!(target: "relay", "RelayProtocol.disconnected peer={}", peer_index);
-        // TODO
+        self.state.tx_hashes.lock().remove(&peer_index);
+        // ask the other announcers for the transactions requested from the peer at once
+        for (_, request) in self.state.inflight_transactions.lock().iter_mut() {
+            request.announcers.retain(|peer| *peer != peer_index);
+            if request.peer == peer_index {
+                request.requested_at = 0;
+            }
+        }
+        self.state.high_bandwidth_peers.lock().remove(&peer_index);
+        self.state
+            .high_bandwidth_senders
+            .lock()
+            .retain(|peer| *peer != peer_index);
     }
 
     fn notify(&mut self, nc: Box<dyn CKBProtocolContext>, token: u64) {
         match token {
             TX_PROPOSAL_TOKEN => self.prune_tx_proposal_request(nc.as_ref()),
+            TX_HASHES_TOKEN => self.send_bulk_of_tx_hashes(nc.as_ref()),
+            TX_REQUEST_TOKEN => self.retry_tx_requests(nc.as_ref()),
             _ => unreachable!(),
         }
     }
@@ -360,6 +621,40 @@ pub struct RelayState {
     pub inflight_proposals: Mutex<FnvHashSet<ProposalShortId>>,
     pub pending_proposals_request: Mutex<FnvHashMap<ProposalShortId, FnvHashSet<PeerIndex>>>,
     pub tx_filter: Mutex<LruCache<H256, ()>>,
+    // tx hashes waiting to be announced to each peer
+    pub tx_hashes: Mutex<FnvHashMap<PeerIndex, TxHashesQueue>>,
+    // hashes of the txs submitted to this node, waiting to be queued for the peers
+    pub local_tx_hashes: Mutex<Vec<H256>>,
+    // txs requested with `GetRelayTransactions` and not received yet
+    pub inflight_transactions: Mutex<LruCache<H256, InflightTransaction>>,
+    // peers which want compact blocks before we fully validate them
+    pub high_bandwidth_peers: Mutex<FnvHashSet<PeerIndex>>,
+    // peers we have asked to send compact blocks in high bandwidth mode, least recent first
+    pub high_bandwidth_senders: Mutex<Vec<PeerIndex>>,
+}
+
+pub struct InflightTransaction {
+    // the requested peer and when it was asked
+    pub peer: PeerIndex,
+    pub requested_at: u64,
+    // the other peers which announced the transaction, asked in turn after timeouts
+    pub announcers: Vec<PeerIndex>,
+}
+
+impl InflightTransaction {
+    pub fn new(peer: PeerIndex, requested_at: u64) -> Self {
+        InflightTransaction {
+            peer,
+            requested_at,
+            announcers: Vec::new(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TxHashesQueue {
+    pub hashes: Vec<H256>,
+    pub next_announce_at: u64,
 }
 
 impl Default for RelayState {
@@ -369,6 +664,11 @@ impl Default for RelayState {
             inflight_proposals: Mutex::new(FnvHashSet::default()),
             pending_proposals_request: Mutex::new(FnvHashMap::default()),
             tx_filter: Mutex::new(LruCache::new(TX_FILTER_SIZE)),
+            tx_hashes: Mutex::new(FnvHashMap::default()),
+            local_tx_hashes: Mutex::new(Vec::new()),
+            inflight_transactions: Mutex::new(LruCache::new(INFLIGHT_TRANSACTIONS_SIZE)),
+            high_bandwidth_peers: Mutex::new(FnvHashSet::default()),
+            high_bandwidth_senders: Mutex::new(Vec::new()),
         }
     }
 }
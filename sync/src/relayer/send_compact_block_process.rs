@@ -0,0 +1,34 @@
+use crate::relayer::Relayer;
+use ckb_network::PeerIndex;
+use ckb_protocol::SendCompactBlock;
+use ckb_shared::store::ChainStore;
+use failure::Error as FailureError;
+use log::debug;
+
+pub struct SendCompactBlockProcess<'a, CS> {
+    message: &'a SendCompactBlock<'a>,
+    relayer: &'a Relayer<CS>,
+    peer: PeerIndex,
+}
+
+impl<'a, CS: ChainStore> SendCompactBlockProcess<'a, CS> {
+    pub fn new(message: &'a SendCompactBlock, relayer: &'a Relayer<CS>, peer: PeerIndex) -> Self {
+        SendCompactBlockProcess {
+            message,
+            relayer,
+            peer,
+        }
+    }
+
+    pub fn execute(self) -> Result<(), FailureError> {
+        let high_bandwidth = self.message.high_bandwidth();
+        debug!(target: "relay", "peer {} sets high bandwidth mode {}", self.peer, high_bandwidth);
+        let mut high_bandwidth_peers = self.relayer.state.high_bandwidth_peers.lock();
+        if high_bandwidth {
+            high_bandwidth_peers.insert(self.peer);
+        } else {
+            high_bandwidth_peers.remove(&self.peer);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use crate::relayer::{
+    InflightTransaction, Relayer, MAX_RELAY_TX_HASHES_PER_BATCH, MAX_TX_ANNOUNCERS,
+    TX_REQUEST_TIMEOUT,
+};
+use ckb_core::transaction::ProposalShortId;
+use ckb_network::{CKBProtocolContext, PeerIndex};
+use ckb_protocol::{cast, RelayMessage, RelayTransactionHashes};
+use ckb_shared::store::ChainStore;
+use failure::Error as FailureError;
+use faketime::unix_time_as_millis;
+use flatbuffers::FlatBufferBuilder;
+use log::debug;
+use numext_fixed_hash::H256;
+use std::convert::TryInto;
+
+pub struct TransactionHashesProcess<'a, CS> {
+    message: &'a RelayTransactionHashes<'a>,
+    relayer: &'a Relayer<CS>,
+    nc: &'a CKBProtocolContext,
+    peer: PeerIndex,
+}
+
+impl<'a, CS: ChainStore> TransactionHashesProcess<'a, CS> {
+    pub fn new(
+        message: &'a RelayTransactionHashes,
+        relayer: &'a Relayer<CS>,
+        nc: &'a CKBProtocolContext,
+        peer: PeerIndex,
+    ) -> Self {
+        TransactionHashesProcess {
+            message,
+            relayer,
+            nc,
+            peer,
+        }
+    }
+
+    pub fn execute(self) -> Result<(), FailureError> {
+        let tx_hashes = cast!(self.message.tx_hashes())?;
+
+        if tx_hashes.len() > MAX_RELAY_TX_HASHES_PER_BATCH {
+            debug!(target: "relay", "peer {} announces too many tx hashes: {}", self.peer, tx_hashes.len());
+            cast!(None)?;
+        }
+
+        let tx_hashes = tx_hashes
+            .iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<H256>, FailureError>>()?;
+
+        {
+            let mut known_txs = self.relayer.peers.known_txs.lock();
+            for tx_hash in &tx_hashes {
+                known_txs.insert(self.peer, tx_hash.clone());
+            }
+        }
+
+        let unknown_tx_hashes: Vec<H256> = {
+            let chain_state = self.relayer.shared.chain_state().lock();
+            let tx_pool = chain_state.tx_pool();
+            let tx_filter = self.relayer.state.tx_filter.lock();
+            let mut inflight = self.relayer.state.inflight_transactions.lock();
+            let now = unix_time_as_millis();
+
+            tx_hashes
+                .into_iter()
+                .filter(|tx_hash| {
+                    !tx_filter.contains_key(tx_hash)
+                        && !tx_pool.contains_proposal_id(&ProposalShortId::from_tx_hash(tx_hash))
+                })
+                .filter(|tx_hash| match inflight.get_mut(tx_hash) {
+                    // remember this peer to ask it if the requested one does not respond
+                    Some(ref mut request) if now < request.requested_at + TX_REQUEST_TIMEOUT => {
+                        if request.peer != self.peer
+                            && !request.announcers.contains(&self.peer)
+                            && request.announcers.len() < MAX_TX_ANNOUNCERS
+                        {
+                            request.announcers.push(self.peer);
+                        }
+                        false
+                    }
+                    Some(ref mut request) => {
+                        request.peer = self.peer;
+                        request.requested_at = now;
+                        true
+                    }
+                    None => {
+                        inflight.insert(tx_hash.clone(), InflightTransaction::new(self.peer, now));
+                        true
+                    }
+                })
+                .collect()
+        };
+
+        if !unknown_tx_hashes.is_empty() {
+            debug!(target: "relay", "request {} txs from peer {}", unknown_tx_hashes.len(), self.peer);
+            let fbb = &mut FlatBufferBuilder::new();
+            let message = RelayMessage::build_get_transactions(fbb, &unknown_tx_hashes);
+            fbb.finish(message, None);
+            self.nc
+                .send_message_to(self.peer, fbb.finished_data().to_vec());
+        }
+
+        Ok(())
+    }
+}
@@ -1,14 +1,12 @@
 use crate::relayer::Relayer;
-use crate::relayer::MAX_RELAY_PEERS;
 use ckb_core::{transaction::Transaction, Cycle};
 use ckb_network::{CKBProtocolContext, PeerIndex};
-use ckb_protocol::{RelayMessage, RelayTransaction as FbsRelayTransaction};
+use ckb_protocol::RelayTransaction as FbsRelayTransaction;
 use ckb_shared::store::ChainStore;
 use ckb_shared::tx_pool::types::PoolError;
 use ckb_traits::chain_provider::ChainProvider;
 use ckb_verification::TransactionError;
 use failure::Error as FailureError;
-use flatbuffers::FlatBufferBuilder;
 use log::debug;
 use numext_fixed_hash::H256;
 use std::convert::TryInto;
@@ -41,6 +39,11 @@ impl<'a, CS: ChainStore> TransactionProcess<'a, CS> {
     pub fn execute(self) -> Result<(), FailureError> {
         let (tx, relay_cycles): (Transaction, Cycle) = (*self.message).try_into()?;
         let tx_hash = tx.hash();
+        self.relayer
+            .state
+            .inflight_transactions
+            .lock()
+            .remove(&tx_hash);
 
         if self.already_known(tx_hash.clone()) {
             debug!(target: "relay", "discarding already known transaction {:#x}", tx_hash);
@@ -55,27 +58,9 @@ impl<'a, CS: ChainStore> TransactionProcess<'a, CS> {
         // disconnect peer if cycles mismatch
         match tx_result {
             Ok(cycles) if cycles == relay_cycles => {
-                // broadcast tx
-                let fbb = &mut FlatBufferBuilder::new();
-                let message = RelayMessage::build_transaction(fbb, &tx, cycles);
-                fbb.finish(message, None);
-
-                let mut known_txs = self.relayer.peers.known_txs.lock();
-                let selected_peers: Vec<PeerIndex> = self
-                    .nc
-                    .connected_peers()
-                    .into_iter()
-                    .filter(|target_peer| {
-                        known_txs.insert(*target_peer, tx_hash.clone())
-                            && (self.peer != *target_peer)
-                    })
-                    .take(MAX_RELAY_PEERS)
-                    .collect();
-
-                for target_peer in selected_peers {
-                    self.nc
-                        .send_message_to(target_peer, fbb.finished_data().to_vec());
-                }
+                self.nc.new_tx_received(self.peer);
+                self.relayer
+                    .announce_transaction(self.nc, self.peer, tx_hash);
             }
             Err(PoolError::InvalidTx(TransactionError::Unknown))
             | Err(PoolError::InvalidTx(TransactionError::Conflict))
@@ -0,0 +1,45 @@
+use crate::relayer::transaction_process::TransactionProcess;
+use crate::relayer::{Relayer, MAX_RELAY_TX_HASHES_PER_BATCH};
+use ckb_network::{CKBProtocolContext, PeerIndex};
+use ckb_protocol::{cast, FlatbuffersVectorIterator, RelayTransactions};
+use ckb_shared::store::ChainStore;
+use failure::Error as FailureError;
+use log::debug;
+
+pub struct TransactionsProcess<'a, CS> {
+    message: &'a RelayTransactions<'a>,
+    relayer: &'a Relayer<CS>,
+    nc: &'a CKBProtocolContext,
+    peer: PeerIndex,
+}
+
+impl<'a, CS: ChainStore> TransactionsProcess<'a, CS> {
+    pub fn new(
+        message: &'a RelayTransactions,
+        relayer: &'a Relayer<CS>,
+        nc: &'a CKBProtocolContext,
+        peer: PeerIndex,
+    ) -> Self {
+        TransactionsProcess {
+            message,
+            relayer,
+            nc,
+            peer,
+        }
+    }
+
+    pub fn execute(self) -> Result<(), FailureError> {
+        let transactions = cast!(self.message.transactions())?;
+
+        if transactions.len() > MAX_RELAY_TX_HASHES_PER_BATCH {
+            debug!(target: "relay", "peer {} relays too many txs: {}", self.peer, transactions.len());
+            cast!(None)?;
+        }
+
+        for transaction in FlatbuffersVectorIterator::new(transactions) {
+            TransactionProcess::new(&transaction, self.relayer, self.nc, self.peer).execute()?;
+        }
+
+        Ok(())
+    }
+}
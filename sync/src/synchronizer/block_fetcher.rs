@@ -190,6 +190,7 @@ where
 
                 let block_status = self.synchronizer.get_block_status(&to_fetch_hash);
                 if block_status == BlockStatus::VALID_MASK
+                    && !inflight.is_not_found(&to_fetch_hash)
                     && inflight.insert(to_fetch_hash.clone().clone())
                 {
                     trace!(
@@ -1,4 +1,4 @@
-use crate::synchronizer::Synchronizer;
+use crate::synchronizer::{BlockStatus, Synchronizer};
 use ckb_core::block::Block;
 use ckb_network::{CKBProtocolContext, PeerIndex};
 use ckb_protocol::Block as PBlock;
@@ -11,6 +11,7 @@ pub struct BlockProcess<'a, CS: ChainStore + 'a> {
     message: &'a PBlock<'a>,
     synchronizer: &'a Synchronizer<CS>,
     peer: PeerIndex,
+    nc: &'a CKBProtocolContext,
 }
 
 impl<'a, CS> BlockProcess<'a, CS>
@@ -21,12 +22,13 @@ where
         message: &'a PBlock,
         synchronizer: &'a Synchronizer<CS>,
         peer: PeerIndex,
-        _nc: &'a CKBProtocolContext,
+        nc: &'a CKBProtocolContext,
     ) -> Self {
         BlockProcess {
             message,
             synchronizer,
             peer,
+            nc,
         }
     }
 
@@ -35,7 +37,13 @@ where
         debug!(target: "sync", "BlockProcess received block {} {:x}", block.header().number(), block.header().hash());
 
         self.synchronizer.peers.block_received(self.peer, &block);
+        let block_hash = block.header().hash();
+        let is_new = self.synchronizer.get_block_status(&block_hash) == BlockStatus::VALID_MASK;
         self.synchronizer.process_new_block(self.peer, block);
+        if is_new && self.synchronizer.get_block_status(&block_hash) == BlockStatus::BLOCK_HAVE_MASK
+        {
+            self.nc.new_block_received(self.peer);
+        }
         Ok(())
     }
 }
@@ -0,0 +1,42 @@
+use crate::synchronizer::Synchronizer;
+use ckb_network::PeerIndex;
+use ckb_protocol::{cast, BlocksNotFound};
+use ckb_shared::store::ChainStore;
+use failure::Error as FailureError;
+use numext_fixed_hash::H256;
+use std::convert::TryInto;
+
+pub struct BlocksNotFoundProcess<'a, CS: ChainStore + 'a> {
+    message: &'a BlocksNotFound<'a>,
+    synchronizer: &'a Synchronizer<CS>,
+    peer: PeerIndex,
+}
+
+impl<'a, CS> BlocksNotFoundProcess<'a, CS>
+where
+    CS: ChainStore + 'a,
+{
+    pub fn new(
+        message: &'a BlocksNotFound,
+        synchronizer: &'a Synchronizer<CS>,
+        peer: PeerIndex,
+    ) -> Self {
+        BlocksNotFoundProcess {
+            message,
+            synchronizer,
+            peer,
+        }
+    }
+
+    pub fn execute(self) -> Result<(), FailureError> {
+        let block_hashes = cast!(self.message.block_hashes())?
+            .iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<H256>, FailureError>>()?;
+
+        self.synchronizer
+            .peers
+            .blocks_not_found(self.peer, block_hashes);
+        Ok(())
+    }
+}
@@ -34,6 +34,7 @@ where
 
     pub fn execute(self) -> Result<(), FailureError> {
         let block_hashes = cast!(self.message.block_hashes())?;
+        let mut not_found = Vec::new();
 
         for fbs_h256 in block_hashes {
             let block_hash = fbs_h256.try_into()?;
@@ -46,11 +47,23 @@ where
                 self.nc
                     .send_message_to(self.peer, fbb.finished_data().to_vec());
             } else {
-                // TODO response not found
-                // TODO add timeout check in synchronizer
+                // the historical blocks are not served once pruned
+                if self.synchronizer.is_pruned(&block_hash) {
+                    debug!(target: "sync", "get_blocks {:x} has been pruned", block_hash);
+                }
+                not_found.push(block_hash);
             }
         }
 
+        // let the peer request them from others instead of waiting for the download timeout
+        if !not_found.is_empty() {
+            let fbb = &mut FlatBufferBuilder::new();
+            let message = SyncMessage::build_blocks_not_found(fbb, &not_found);
+            fbb.finish(message, None);
+            self.nc
+                .send_message_to(self.peer, fbb.finished_data().to_vec());
+        }
+
         Ok(())
     }
 }
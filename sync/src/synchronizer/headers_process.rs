@@ -370,6 +370,29 @@ where
         })
     }
 
+    // Reject headers conflicting with a checkpoint, and headers forking off
+    // the main chain below the latest checkpoint once we have passed it.
+    pub fn checkpoint_check(&self, state: &mut ValidationResult) -> Result<(), ()> {
+        let consensus = self.synchronizer.consensus();
+        let number = self.header.number();
+        if consensus.is_checkpoint_conflict(number, &self.header.hash()) {
+            state.dos(Some(ValidationError::Checkpoint), 100);
+            return Err(());
+        }
+
+        if let Some(checkpoint) = consensus.last_checkpoint() {
+            if number <= checkpoint.number
+                && self.synchronizer.shared.block_hash(checkpoint.number)
+                    == Some(checkpoint.hash.clone())
+                && self.synchronizer.shared.block_hash(number) != Some(self.header.hash())
+            {
+                state.dos(Some(ValidationError::Checkpoint), 100);
+                return Err(());
+            }
+        }
+        Ok(())
+    }
+
     pub fn version_check(&self, state: &mut ValidationResult) -> Result<(), ()> {
         if self.header.version() != 0 {
             state.invalid(Some(ValidationError::Version));
@@ -393,6 +416,13 @@ where
             return result;
         }
 
+        if self.checkpoint_check(&mut result).is_err() {
+            debug!(target: "sync", "HeadersProcess accept {:?} checkpoint", self.header.number());
+            self.synchronizer
+                .insert_block_status(self.header.hash().clone(), BlockStatus::FAILED_MASK);
+            return result;
+        }
+
         if self.non_contextual_check(&mut result).is_err() {
             debug!(target: "sync", "HeadersProcess accept {:?} non_contextual", self.header.number());
             self.synchronizer
@@ -432,6 +462,7 @@ pub enum ValidationError {
     Verify(VerifyError),
     Version,
     InvalidParent,
+    Checkpoint,
 }
 
 #[derive(Debug, Default)]
@@ -1,6 +1,7 @@
 mod block_fetcher;
 mod block_pool;
 mod block_process;
+mod blocks_not_found_process;
 mod get_blocks_process;
 mod get_headers_process;
 mod headers_process;
@@ -8,11 +9,12 @@ mod headers_process;
 use self::block_fetcher::BlockFetcher;
 use self::block_pool::OrphanBlockPool;
 use self::block_process::BlockProcess;
+use self::blocks_not_found_process::BlocksNotFoundProcess;
 use self::get_blocks_process::GetBlocksProcess;
 use self::get_headers_process::GetHeadersProcess;
 use self::headers_process::HeadersProcess;
 use crate::config::Config;
-use crate::types::{HeaderView, Peers};
+use crate::types::{get_skip_height, HeaderView, Peers};
 use crate::{
     CHAIN_SYNC_TIMEOUT, EVICTION_HEADERS_RESPONSE_TIME, HEADERS_DOWNLOAD_TIMEOUT_BASE,
     HEADERS_DOWNLOAD_TIMEOUT_PER_HEADER, MAX_HEADERS_LEN,
@@ -151,6 +153,14 @@ impl<CS: ChainStore> Synchronizer<CS> {
             SyncPayload::Block => {
                 BlockProcess::new(&cast!(message.payload_as_block())?, self, peer, nc).execute()?;
             }
+            SyncPayload::BlocksNotFound => {
+                BlocksNotFoundProcess::new(
+                    &cast!(message.payload_as_blocks_not_found())?,
+                    self,
+                    peer,
+                )
+                .execute()?;
+            }
             SyncPayload::NONE => {
                 cast!(None)?;
             }
@@ -324,25 +334,42 @@ impl<CS: ChainStore> Synchronizer<CS> {
         self.shared.block(hash)
     }
 
+    pub fn is_pruned(&self, hash: &H256) -> bool {
+        self.shared.store().is_pruned(hash)
+    }
+
     pub fn get_ancestor(&self, base: &H256, number: BlockNumber) -> Option<Header> {
-        if let Some(header) = self.get_header(base) {
-            let mut n_number = header.number();
-            let mut index_walk = header;
-            if number > n_number {
-                return None;
+        let mut index_walk = self.get_header_view(base)?;
+        if number > index_walk.number() {
+            return None;
+        }
+
+        while index_walk.number() > number {
+            // the main chain is indexed by number
+            if self.shared.block_hash(index_walk.number()).as_ref() == Some(&index_walk.hash()) {
+                return self
+                    .shared
+                    .block_hash(number)
+                    .and_then(|hash| self.shared.block_header(&hash));
             }
 
-            while n_number > number {
-                if let Some(header) = self.get_header(&index_walk.parent_hash()) {
-                    index_walk = header;
-                    n_number -= 1;
-                } else {
-                    return None;
+            let skip_number = get_skip_height(index_walk.number());
+            let skip_prev_number = get_skip_height(index_walk.number() - 1);
+            let next_hash = match index_walk.skip_hash() {
+                // Only follow the skip hash if the parent's one doesn't get closer to the target
+                Some(skip_hash)
+                    if skip_number == number
+                        || (skip_number > number
+                            && !(skip_prev_number + 2 < skip_number
+                                && skip_prev_number >= number)) =>
+                {
+                    skip_hash.clone()
                 }
-            }
-            return Some(index_walk);
+                _ => index_walk.inner().parent_hash().clone(),
+            };
+            index_walk = self.get_header_view(&next_hash)?;
         }
-        None
+        Some(index_walk.into_inner())
     }
 
     pub fn get_locator_response(&self, block_number: BlockNumber, hash_stop: &H256) -> Vec<Header> {
@@ -363,10 +390,11 @@ impl<CS: ChainStore> Synchronizer<CS> {
             let total_difficulty = parent_view.total_difficulty() + header.difficulty();
             let total_uncles_count =
                 parent_view.total_uncles_count() + u64::from(header.uncles_count());
+            let mut header_view =
+                HeaderView::new(header.clone(), total_difficulty.clone(), total_uncles_count);
+            header_view.build_skip(|base, number| self.get_ancestor(base, number));
             let header_view = {
                 let mut best_known_header = self.best_known_header.write();
-                let header_view =
-                    HeaderView::new(header.clone(), total_difficulty.clone(), total_uncles_count);
 
                 if total_difficulty.gt(best_known_header.total_difficulty())
                     || (&total_difficulty == best_known_header.total_difficulty()
@@ -1017,6 +1045,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_ancestor_on_fork() {
+        let consensus = Consensus::default();
+        let (chain_controller, shared, _notify) = start_chain(Some(consensus), None);
+        for i in 1..100 {
+            insert_block(&chain_controller, &shared, i, i);
+        }
+
+        let synchronizer = gen_synchronizer(chain_controller.clone(), shared.clone());
+        // the fork headers are only known by the synchronizer
+        let mut fork = vec![];
+        let mut parent = shared
+            .block_header(&shared.block_hash(20).unwrap())
+            .unwrap();
+        for i in 0..500 {
+            let header = gen_block(&parent, parent.difficulty().clone(), i + 1000)
+                .header()
+                .clone();
+            synchronizer.insert_header_view(&header, 0.into());
+            parent = header.clone();
+            fork.push(header);
+        }
+
+        let tip = fork.last().unwrap().hash();
+        for header in fork.iter().step_by(7) {
+            let view = synchronizer.get_header_view(&header.hash()).unwrap();
+            if let Some(skip_hash) = view.skip_hash() {
+                let skip = synchronizer.get_header(skip_hash).unwrap();
+                assert_eq!(skip.number(), get_skip_height(header.number()));
+            }
+            assert_eq!(
+                synchronizer.get_ancestor(&tip, header.number()).as_ref(),
+                Some(header)
+            );
+        }
+        for number in 0..=20 {
+            assert_eq!(
+                synchronizer.get_ancestor(&tip, number),
+                shared
+                    .block_hash(number)
+                    .and_then(|hash| shared.block_header(&hash))
+            );
+        }
+        assert!(synchronizer.get_ancestor(&tip, 521).is_none());
+    }
+
     #[test]
     fn test_process_new_block() {
         let consensus = Consensus::default();
@@ -1128,6 +1202,8 @@ mod tests {
         }
         fn report_peer(&self, _peer_index: PeerIndex, _behaviour: Behaviour) {}
         fn ban_peer(&self, _peer_index: PeerIndex, _timeout: Duration) {}
+        fn new_block_received(&self, _peer_index: PeerIndex) {}
+        fn new_tx_received(&self, _peer_index: PeerIndex) {}
         // Other methods
         fn protocol_id(&self) -> ProtocolId {
             unimplemented!();
@@ -1251,6 +1327,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_blocks_not_found() {
+        let consensus = Consensus::default();
+        let notify = NotifyService::default().start::<&str>(None);
+        let (chain_controller1, shared1, _) =
+            start_chain(Some(consensus.clone()), Some(notify.clone()));
+        let (chain_controller2, shared2, _) =
+            start_chain(Some(consensus.clone()), Some(notify.clone()));
+        for i in 1..=4 {
+            insert_block(&chain_controller2, &shared2, i, i);
+        }
+        let synchronizer = gen_synchronizer(chain_controller1.clone(), shared1.clone());
+
+        let headers: Vec<Header> = (1..=4)
+            .map(|i| {
+                shared2
+                    .block_header(&shared2.block_hash(i).unwrap())
+                    .unwrap()
+            })
+            .collect();
+        let fbb = &mut FlatBufferBuilder::new();
+        let fbs_headers = FbsHeaders::build(fbb, &headers);
+        fbb.finish(fbs_headers, None);
+        let fbs_headers = get_root::<FbsHeaders>(fbb.finished_data());
+        let peer: PeerIndex = 1.into();
+        HeadersProcess::new(&fbs_headers, &synchronizer, peer, &mock_network_context(0))
+            .execute()
+            .unwrap();
+        let blocks_to_fetch = synchronizer.get_blocks_to_fetch(peer).unwrap();
+        assert_eq!(blocks_to_fetch.len(), 4);
+
+        // the peer does not have the first two blocks
+        let fbb = &mut FlatBufferBuilder::new();
+        let message = SyncMessage::build_blocks_not_found(fbb, &blocks_to_fetch[..2]);
+        fbb.finish(message, None);
+        synchronizer.process(
+            &mock_network_context(0),
+            peer,
+            get_root::<SyncMessage>(fbb.finished_data()),
+        );
+
+        let inflight = synchronizer.peers.blocks_inflight.read()[&peer].clone();
+        assert_eq!(inflight.len(), 2);
+        assert!(inflight.is_not_found(&blocks_to_fetch[0]));
+        assert!(inflight.is_not_found(&blocks_to_fetch[1]));
+        // they are left for the other peers
+        assert_eq!(synchronizer.get_blocks_to_fetch(peer), Some(Vec::new()));
+    }
+
     #[cfg(not(disable_faketime))]
     #[test]
     fn test_header_sync_timeout() {
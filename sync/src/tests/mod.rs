@@ -176,6 +176,8 @@ impl CKBProtocolContext for TestNetworkContext {
     }
     fn report_peer(&self, _peer_index: PeerIndex, _behaviour: Behaviour) {}
     fn ban_peer(&self, _peer_index: PeerIndex, _timeout: Duration) {}
+    fn new_block_received(&self, _peer_index: PeerIndex) {}
+    fn new_tx_received(&self, _peer_index: PeerIndex) {}
     // Other methods
     fn protocol_id(&self) -> ProtocolId {
         self.protocol
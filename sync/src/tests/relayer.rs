@@ -1,4 +1,4 @@
-use crate::relayer::TX_PROPOSAL_TOKEN;
+use crate::relayer::{TX_HASHES_TOKEN, TX_PROPOSAL_TOKEN, TX_REQUEST_TOKEN};
 use crate::tests::TestNode;
 use crate::{NetworkProtocol, Relayer};
 use ckb_chain::chain::{ChainBuilder, ChainController};
@@ -6,7 +6,9 @@ use ckb_chain_spec::consensus::Consensus;
 use ckb_core::block::BlockBuilder;
 use ckb_core::header::HeaderBuilder;
 use ckb_core::script::Script;
-use ckb_core::transaction::{CellInput, CellOutput, OutPoint, Transaction, TransactionBuilder};
+use ckb_core::transaction::{
+    CellInput, CellOutput, OutPoint, ProposalShortId, Transaction, TransactionBuilder,
+};
 use ckb_core::{capacity_bytes, Capacity};
 use ckb_db::memorydb::MemoryKeyValueDB;
 use ckb_notify::NotifyService;
@@ -319,6 +321,167 @@ fn relay_compact_block_with_missing_indexs() {
     assert_eq!(shared2.chain_state().lock().tip_number(), 5);
 }
 
+#[test]
+fn reconstruct_compact_block_from_pending_txs() {
+    let faketime_file = faketime::millis_tempfile(0).expect("create faketime file");
+    faketime::enable(&faketime_file);
+    let thread_name = format!("FAKETIME={}", faketime_file.display());
+
+    let (mut node1, shared1, chain_controller1) = setup_node(&thread_name, 3);
+    let (mut node2, shared2, _chain_controller2) = setup_node(&thread_name, 3);
+
+    node1.connect(&mut node2, NetworkProtocol::RELAY.into());
+
+    let last_block = shared1
+        .block(&shared1.chain_state().lock().tip_hash())
+        .unwrap();
+    let last_cellbase = last_block.transactions().first().unwrap();
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new(
+            OutPoint::new(last_cellbase.hash().clone(), 0),
+            0,
+            vec![],
+        ))
+        .output(CellOutput::new(
+            capacity_bytes!(50),
+            Vec::new(),
+            Script::default(),
+            None,
+        ))
+        .build();
+
+    // node2 has the transaction in its pending queue only, it was never proposed
+    {
+        let chain_state = shared2.chain_state().lock();
+        chain_state
+            .add_tx_to_pool(tx.clone(), shared2.consensus().max_block_cycles())
+            .expect("add tx to pool");
+    }
+
+    let (signal_tx1, _) = channel();
+    thread::Builder::new()
+        .name(thread_name)
+        .spawn(move || {
+            let block = {
+                let number = last_block.header().number() + 1;
+                let timestamp = last_block.header().timestamp() + 1;
+                let difficulty = shared1.calculate_difficulty(&last_block.header()).unwrap();
+                let cellbase = TransactionBuilder::default()
+                    .input(CellInput::new_cellbase_input(number))
+                    .output(CellOutput::default())
+                    .build();
+
+                let header_builder = HeaderBuilder::default()
+                    .parent_hash(last_block.header().hash().clone())
+                    .number(number)
+                    .timestamp(timestamp)
+                    .difficulty(difficulty);
+
+                BlockBuilder::default()
+                    .transaction(cellbase)
+                    .transaction(tx)
+                    .with_header_builder(header_builder)
+            };
+
+            chain_controller1
+                .process_block(Arc::new(block.clone()))
+                .expect("process block should be OK");
+
+            let fbb = &mut FlatBufferBuilder::new();
+            let message = RelayMessage::build_compact_block(fbb, &block, &HashSet::new());
+            fbb.finish(message, None);
+            node1.broadcast(NetworkProtocol::RELAY.into(), &fbb.finished_data().to_vec());
+
+            node1.start(&signal_tx1, |_| false);
+        })
+        .expect("thread spawn");
+
+    let (signal_tx2, signal_rx2) = channel();
+    thread::spawn(move || {
+        node2.start(&signal_tx2, |data| {
+            let msg = get_root::<RelayMessage>(data);
+            // terminate thread after processing the compact block
+            msg.payload_as_compact_block().is_some()
+        });
+    });
+
+    // Wait node2 receive the compact block from node1
+    let _ = signal_rx2.recv();
+
+    assert_eq!(shared2.chain_state().lock().tip_number(), 4);
+}
+
+#[test]
+fn relay_transaction_by_hash() {
+    let faketime_file = faketime::millis_tempfile(0).expect("create faketime file");
+    faketime::enable(&faketime_file);
+    let thread_name = format!("FAKETIME={}", faketime_file.display());
+
+    let (mut node1, shared1, _chain_controller1) = setup_node(&thread_name, 3);
+    let (mut node2, shared2, _chain_controller2) = setup_node(&thread_name, 3);
+
+    node1.connect(&mut node2, NetworkProtocol::RELAY.into());
+
+    let last_block = shared1
+        .block(&shared1.chain_state().lock().tip_hash())
+        .unwrap();
+    let last_cellbase = last_block.transactions().first().unwrap();
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new(
+            OutPoint::new(last_cellbase.hash().clone(), 0),
+            0,
+            vec![],
+        ))
+        .output(CellOutput::new(
+            capacity_bytes!(50),
+            Vec::new(),
+            Script::default(),
+            None,
+        ))
+        .build();
+    let tx_hash = tx.hash();
+
+    let (signal_tx1, _) = channel();
+    thread::Builder::new()
+        .name(thread_name)
+        .spawn(move || {
+            // node1 only announces the hash, node2 should request the full transaction
+            {
+                let hash = tx.hash();
+                let chain_state = shared1.chain_state().lock();
+                chain_state
+                    .add_tx_to_pool(tx, shared1.consensus().max_block_cycles())
+                    .expect("add tx to pool");
+                let fbb = &mut FlatBufferBuilder::new();
+                let message = RelayMessage::build_transaction_hashes(fbb, &[hash]);
+                fbb.finish(message, None);
+                node1.broadcast(NetworkProtocol::RELAY.into(), &fbb.finished_data().to_vec());
+            }
+
+            node1.start(&signal_tx1, |_| false);
+        })
+        .expect("thread spawn");
+
+    let (signal_tx2, signal_rx2) = channel();
+    thread::spawn(move || {
+        node2.start(&signal_tx2, |data| {
+            let msg = get_root::<RelayMessage>(data);
+            // terminate thread after processing the relayed transaction
+            msg.payload_as_relay_transactions().is_some()
+        });
+    });
+
+    // Wait node2 receive transaction from node1
+    let _ = signal_rx2.recv();
+
+    assert!(shared2
+        .chain_state()
+        .lock()
+        .tx_pool()
+        .get_tx(&ProposalShortId::from_tx_hash(&tx_hash))
+        .is_some());
+}
+
 fn setup_node(
     thread_name: &str,
     height: u64,
@@ -392,7 +555,7 @@ fn setup_node(
     node.add_protocol(
         NetworkProtocol::RELAY.into(),
         &protocol,
-        &[TX_PROPOSAL_TOKEN],
+        &[TX_PROPOSAL_TOKEN, TX_HASHES_TOKEN, TX_REQUEST_TOKEN],
     );
     (node, shared, chain_controller)
 }
@@ -77,6 +77,13 @@ impl KnownFilter {
             }
         }
     }
+
+    /// Returns `true` if the peer is known to have the value.
+    pub fn contains(&self, index: &PeerIndex, hash: &H256) -> bool {
+        self.inner
+            .get(index)
+            .map_or(false, |filter| filter.contains_key(hash))
+    }
 }
 
 #[derive(Default)]
@@ -94,12 +101,15 @@ pub struct Peers {
 pub struct BlocksInflight {
     pub timestamp: u64,
     pub blocks: FnvHashSet<H256>,
+    // blocks the peer replied it does not have, they are not requested from it again
+    pub not_found: FnvHashSet<H256>,
 }
 
 impl Default for BlocksInflight {
     fn default() -> Self {
         BlocksInflight {
             blocks: FnvHashSet::default(),
+            not_found: FnvHashSet::default(),
             timestamp: unix_time_as_millis(),
         }
     }
@@ -122,6 +132,15 @@ impl BlocksInflight {
         self.blocks.remove(hash)
     }
 
+    pub fn not_found(&mut self, hash: H256) {
+        self.blocks.remove(&hash);
+        self.not_found.insert(hash);
+    }
+
+    pub fn is_not_found(&self, hash: &H256) -> bool {
+        self.not_found.contains(hash)
+    }
+
     pub fn update_timestamp(&mut self) {
         self.timestamp = unix_time_as_millis();
     }
@@ -204,6 +223,17 @@ impl Peers {
         });
     }
 
+    pub fn blocks_not_found(&self, peer: PeerIndex, block_hashes: Vec<H256>) {
+        let mut blocks_inflight = self.blocks_inflight.write();
+        debug!(target: "sync", "blocks_not_found from peer {} {:?}", peer, block_hashes);
+        blocks_inflight.entry(peer).and_modify(|inflight| {
+            for hash in block_hashes {
+                inflight.not_found(hash);
+            }
+            inflight.update_timestamp();
+        });
+    }
+
     pub fn set_last_common_header(&self, peer: PeerIndex, header: &Header) {
         let mut last_common_headers = self.last_common_headers.write();
         last_common_headers
@@ -218,6 +248,8 @@ pub struct HeaderView {
     inner: Header,
     total_difficulty: U256,
     total_uncles_count: u64,
+    // the hash of the ancestor at `get_skip_height(number)`
+    skip_hash: Option<H256>,
 }
 
 impl HeaderView {
@@ -226,9 +258,26 @@ impl HeaderView {
             inner,
             total_difficulty,
             total_uncles_count,
+            skip_hash: None,
         }
     }
 
+    /// Point the skip hash at the ancestor found by `get_ancestor(parent_hash, number)`
+    pub fn build_skip<F>(&mut self, get_ancestor: F)
+    where
+        F: Fn(&H256, BlockNumber) -> Option<Header>,
+    {
+        if self.number() == 0 {
+            return;
+        }
+        self.skip_hash = get_ancestor(self.inner.parent_hash(), get_skip_height(self.number()))
+            .map(|header| header.hash());
+    }
+
+    pub fn skip_hash(&self) -> Option<&H256> {
+        self.skip_hash.as_ref()
+    }
+
     pub fn number(&self) -> BlockNumber {
         self.inner.number()
     }
@@ -253,3 +302,22 @@ impl HeaderView {
         self.inner
     }
 }
+
+// Turn the lowest '1' bit in the binary representation of a number into a '0'.
+fn invert_lowest_one(n: BlockNumber) -> BlockNumber {
+    n & n.wrapping_sub(1)
+}
+
+/// The number of the ancestor the skip hash points to, the same as `GetSkipHeight` in Bitcoin.
+pub fn get_skip_height(number: BlockNumber) -> BlockNumber {
+    if number < 2 {
+        return 0;
+    }
+    // Any number strictly lower than number is acceptable, but the following expression
+    // performs well in simulations (max 110 steps to go back up to 2**18 blocks).
+    if number & 1 != 0 {
+        invert_lowest_one(invert_lowest_one(number - 1)) + 1
+    } else {
+        invert_lowest_one(number)
+    }
+}
@@ -29,6 +29,7 @@ fn main() {
             "disconnect" => Box::new(Disconnect),
             "malformed_message" => Box::new(MalformedMessage),
             "depent_tx_in_same_block" => Box::new(DepentTxInSameBlock),
+            "chain_rpc_basic" => Box::new(ChainRpcBasic),
             _ => panic!("invalid spec"),
         };
         let net = spec.setup_net(&binary, start_port);
@@ -45,6 +46,7 @@ fn main() {
             Box::new(Disconnect),
             Box::new(MalformedMessage),
             Box::new(DepentTxInSameBlock),
+            Box::new(ChainRpcBasic),
         ];
 
         specs.iter().for_each(|spec| {
@@ -1,10 +1,16 @@
 use jsonrpc_client_core::{expand_params, jsonrpc_client};
-use jsonrpc_types::{Block, BlockTemplate, Header, Node, Transaction, TxTrace};
+use jsonrpc_types::{
+    BannedAddress, Block, BlockExt, BlockTemplate, Header, Node, RemoteNode, Transaction,
+    TransactionWithStatus, TxTrace, UncleBlock,
+};
 use numext_fixed_hash::H256;
 
 jsonrpc_client!(pub struct RpcClient {
     pub fn local_node_info(&mut self) -> RpcRequest<Node>;
-    pub fn get_peers(&mut self) -> RpcRequest<Vec<Node>>;
+    pub fn get_peers(&mut self) -> RpcRequest<Vec<RemoteNode>>;
+    pub fn set_ban(&mut self, address: String, ban_time: String, reason: Option<String>) -> RpcRequest<()>;
+    pub fn get_banned_addresses(&mut self) -> RpcRequest<Vec<BannedAddress>>;
+    pub fn clear_banned_addresses(&mut self) -> RpcRequest<()>;
 
     pub fn add_node(&mut self, peer_id: String, address: String) -> RpcRequest<()>;
 
@@ -17,7 +23,12 @@ jsonrpc_client!(pub struct RpcClient {
     pub fn get_pool_transaction(&mut self, hash: H256) -> RpcRequest<Option<Transaction>>;
 
     pub fn get_block(&mut self, hash: H256) -> RpcRequest<Option<Block>>;
-    pub fn get_transaction(&mut self, hash: H256) -> RpcRequest<Option<Transaction>>;
+    pub fn get_block_by_number(&mut self, number: String) -> RpcRequest<Option<Block>>;
+    pub fn get_header(&mut self, hash: H256) -> RpcRequest<Option<Header>>;
+    pub fn get_header_by_number(&mut self, number: String) -> RpcRequest<Option<Header>>;
+    pub fn get_block_ext(&mut self, hash: H256) -> RpcRequest<Option<BlockExt>>;
+    pub fn get_uncle_block(&mut self, hash: H256, index: String) -> RpcRequest<Option<UncleBlock>>;
+    pub fn get_transaction(&mut self, hash: H256) -> RpcRequest<Option<TransactionWithStatus>>;
     pub fn get_block_hash(&mut self, number: String) -> RpcRequest<Option<H256>>;
     pub fn get_tip_header(&mut self) -> RpcRequest<Header>;
     pub fn get_tip_block_number(&mut self) -> RpcRequest<String>;
@@ -0,0 +1,70 @@
+use crate::{Net, Spec};
+use log::info;
+
+pub struct ChainRpcBasic;
+
+impl Spec for ChainRpcBasic {
+    fn run(&self, net: Net) {
+        info!("Running ChainRpcBasic");
+        let node = &net.nodes[0];
+        let mut rpc = node.rpc_client();
+
+        info!("Generate 2 blocks");
+        node.generate_block();
+        let block_hash = node.generate_block();
+        let tip_number = rpc.get_tip_block_number().call().unwrap();
+        let block = rpc.get_block(block_hash.clone()).call().unwrap().unwrap();
+
+        info!("Block and header queried by number should be the same as by hash");
+        assert_eq!(
+            rpc.get_block_by_number(tip_number.clone()).call().unwrap(),
+            Some(block.clone())
+        );
+        assert_eq!(
+            rpc.get_header(block_hash.clone()).call().unwrap(),
+            Some(block.header.clone())
+        );
+        assert_eq!(
+            rpc.get_header_by_number(tip_number.clone()).call().unwrap(),
+            Some(block.header.clone())
+        );
+
+        info!("Block ext should accumulate the difficulty of the parent");
+        let block_ext = rpc
+            .get_block_ext(block_hash.clone())
+            .call()
+            .unwrap()
+            .unwrap();
+        let parent_ext = rpc
+            .get_block_ext(block.header.parent_hash.clone())
+            .call()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            block_ext.total_difficulty,
+            &parent_ext.total_difficulty + &block.header.difficulty
+        );
+
+        info!("Blocks beyond the tip and missing uncles should be none");
+        let next_number = (tip_number.parse::<u64>().unwrap() + 1).to_string();
+        assert!(rpc
+            .get_block_by_number(next_number.clone())
+            .call()
+            .unwrap()
+            .is_none());
+        assert!(rpc
+            .get_header_by_number(next_number)
+            .call()
+            .unwrap()
+            .is_none());
+        assert!(rpc
+            .get_uncle_block(block_hash, "0".to_owned())
+            .call()
+            .unwrap()
+            .is_none());
+    }
+
+    fn num_nodes(&self) -> usize {
+        1
+    }
+}
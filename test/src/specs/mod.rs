@@ -1,5 +1,6 @@
 mod block_relay;
 mod block_sync;
+mod chain_rpc;
 mod mining;
 mod p2p;
 mod pool;
@@ -9,6 +10,7 @@ mod transaction_relay;
 
 pub use block_relay::BlockRelayBasic;
 pub use block_sync::BlockSyncBasic;
+pub use chain_rpc::ChainRpcBasic;
 pub use mining::MiningBasic;
 pub use p2p::{Disconnect, Discovery};
 pub use pool::{PoolReconcile, PoolTrace};
@@ -1,5 +1,6 @@
 use crate::{BlockNumber, Bytes, Capacity, ProposalShortId};
 use ckb_core::block::{Block as CoreBlock, BlockBuilder};
+use ckb_core::extras::BlockExt as CoreBlockExt;
 use ckb_core::header::{Header as CoreHeader, HeaderBuilder, Seal as CoreSeal};
 use ckb_core::script::Script as CoreScript;
 use ckb_core::transaction::{
@@ -190,6 +191,66 @@ impl<'a> From<&'a CoreTransaction> for Transaction {
     }
 }
 
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Pending,
+    Proposed,
+    Committed,
+}
+
+/// The block fields are only set for committed transactions
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct TxStatus {
+    pub status: Status,
+    pub block_hash: Option<H256>,
+    pub block_number: Option<BlockNumber>,
+    /// `None` when the block body has been pruned
+    pub tx_index: Option<String>,
+    pub confirmations: Option<String>,
+}
+
+impl TxStatus {
+    pub fn pending() -> Self {
+        Self::in_pool(Status::Pending)
+    }
+
+    pub fn proposed() -> Self {
+        Self::in_pool(Status::Proposed)
+    }
+
+    fn in_pool(status: Status) -> Self {
+        TxStatus {
+            status,
+            block_hash: None,
+            block_number: None,
+            tx_index: None,
+            confirmations: None,
+        }
+    }
+
+    pub fn committed(
+        block_hash: H256,
+        block_number: CoreBlockNumber,
+        tx_index: Option<usize>,
+        tip_number: CoreBlockNumber,
+    ) -> Self {
+        TxStatus {
+            status: Status::Committed,
+            block_hash: Some(block_hash),
+            block_number: Some(block_number.to_string()),
+            tx_index: tx_index.map(|index| index.to_string()),
+            confirmations: Some((tip_number.saturating_sub(block_number) + 1).to_string()),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct TransactionWithStatus {
+    pub transaction: Transaction,
+    pub tx_status: TxStatus,
+}
+
 impl TryFrom<Transaction> for CoreTransaction {
     type Error = FailureError;
 
@@ -412,6 +473,83 @@ impl TryFrom<Block> for CoreBlock {
     }
 }
 
+/// The verbosity of a block returned by `get_block` and `get_block_by_number`
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockVerbosity {
+    Full,
+    /// The transactions and uncles are replaced with their hashes
+    Compact,
+}
+
+impl Default for BlockVerbosity {
+    fn default() -> Self {
+        BlockVerbosity::Full
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct BlockWithTxHashes {
+    pub header: Header,
+    /// The hashes of the uncle headers
+    pub uncles: Vec<H256>,
+    pub transactions: Vec<H256>,
+    pub proposals: Vec<ProposalShortId>,
+}
+
+impl<'a> From<&'a CoreBlock> for BlockWithTxHashes {
+    fn from(core: &CoreBlock) -> BlockWithTxHashes {
+        BlockWithTxHashes {
+            header: core.header().into(),
+            uncles: core
+                .uncles()
+                .iter()
+                .map(|uncle| uncle.header().hash())
+                .collect(),
+            transactions: core.transactions().iter().map(|tx| tx.hash()).collect(),
+            proposals: core.proposals().iter().cloned().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+#[serde(untagged)]
+pub enum BlockView {
+    Full(Block),
+    Compact(BlockWithTxHashes),
+}
+
+impl BlockView {
+    pub fn new(core: &CoreBlock, verbosity: BlockVerbosity) -> Self {
+        match verbosity {
+            BlockVerbosity::Full => BlockView::Full(core.into()),
+            BlockVerbosity::Compact => BlockView::Compact(core.into()),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct BlockExt {
+    /// The time the block was received in milliseconds
+    pub received_at: String,
+    pub total_difficulty: U256,
+    /// The number of uncles of the block and its ancestors
+    pub total_uncles_count: String,
+    /// `None` until the transactions are verified on the main chain
+    pub txs_verified: Option<bool>,
+}
+
+impl<'a> From<&'a CoreBlockExt> for BlockExt {
+    fn from(core: &CoreBlockExt) -> BlockExt {
+        BlockExt {
+            received_at: core.received_at.to_string(),
+            total_difficulty: core.total_difficulty.clone(),
+            total_uncles_count: core.total_uncles_count.to_string(),
+            txs_verified: core.txs_verified,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -470,6 +608,24 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_block_view_roundtrip() {
+        let block = mock_full_block(vec![1], vec![2]);
+        for verbosity in &[BlockVerbosity::Full, BlockVerbosity::Compact] {
+            let view = BlockView::new(&block, *verbosity);
+            let encoded = serde_json::to_string(&view).unwrap();
+            let decoded: BlockView = serde_json::from_str(&encoded).unwrap();
+            assert_eq!(decoded, view);
+        }
+        match BlockView::new(&block, BlockVerbosity::Compact) {
+            BlockView::Compact(compact) => {
+                assert_eq!(compact.transactions, vec![block.transactions()[0].hash()]);
+                assert_eq!(compact.uncles, vec![block.uncles()[0].header().hash()]);
+            }
+            BlockView::Full(_) => panic!("expect a compact block"),
+        }
+    }
+
     proptest! {
         #[test]
         fn test_block_convert(
@@ -1,8 +1,9 @@
-use crate::{Capacity, CellOutput, OutPoint, Script};
+use crate::{Bytes, Capacity, CellOutput, OutPoint, Script, Transaction};
 use ckb_core::cell::{CellStatus, LiveCell};
+use numext_fixed_hash::H256;
 use serde_derive::{Deserialize, Serialize};
 
-// This is used as return value of get_cells_by_type_hash RPC:
+// This is used as return value of get_cells_by_lock_hash RPC:
 // it contains both OutPoint data used for referencing a cell, as well as
 // cell's own data such as lock and capacity
 #[derive(Serialize, Deserialize)]
@@ -12,6 +13,39 @@ pub struct CellOutputWithOutPoint {
     pub lock: Script,
 }
 
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum IndexedCellStatus {
+    Live,
+    Dead,
+}
+
+// This is used as return value of get_cells_by_type_hash and get_cells_by_data_hash RPCs:
+// a cell without its data, along with the transaction and the block it is created in and
+// whether it is spent
+#[derive(Serialize, Deserialize)]
+pub struct IndexedCell {
+    pub out_point: OutPoint,
+    pub capacity: Capacity,
+    pub lock: Script,
+    #[serde(rename = "type")]
+    pub type_: Option<Script>,
+    pub data_hash: H256,
+    /// `None` when the block body has been pruned and all the outputs are spent
+    pub transaction: Option<Transaction>,
+    pub block_hash: H256,
+    pub block_number: String,
+    pub status: IndexedCellStatus,
+}
+
+// This is used as return value of get_cells_by_type_hash and get_cells_by_data_hash RPCs:
+// the cursor is passed back to get the next page, it is `None` when all the cells are returned
+#[derive(Serialize, Deserialize)]
+pub struct IndexedCellsPage {
+    pub cells: Vec<IndexedCell>,
+    pub cursor: Option<Bytes>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct CellWithStatus {
     pub cell: Option<CellOutput>,
@@ -0,0 +1,38 @@
+use crate::{BlockNumber, Capacity, Cycle};
+use numext_fixed_hash::H256;
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct ProposalWindow {
+    pub closest: BlockNumber,
+    pub farthest: BlockNumber,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct Checkpoint {
+    pub number: BlockNumber,
+    pub hash: H256,
+}
+
+/// The consensus parameters of the chain
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct Consensus {
+    pub id: String,
+    pub genesis_hash: H256,
+    pub initial_block_reward: Capacity,
+    pub max_uncles_age: String,
+    pub max_uncles_num: String,
+    pub orphan_rate_target: f32,
+    /// The time span of the difficulty adjustment in milliseconds
+    pub pow_time_span: String,
+    /// The expected block interval in milliseconds
+    pub pow_spacing: String,
+    pub tx_proposal_window: ProposalWindow,
+    pub pow: String,
+    pub cellbase_maturity: BlockNumber,
+    pub median_time_block_count: String,
+    pub max_block_cycles: Cycle,
+    pub max_block_bytes: String,
+    pub block_version: u32,
+    pub checkpoints: Vec<Checkpoint>,
+}
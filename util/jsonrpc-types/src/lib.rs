@@ -2,8 +2,11 @@ mod block_template;
 mod blockchain;
 mod bytes;
 mod cell;
+mod consensus;
 mod net;
+mod pool;
 mod proposal_short_id;
+mod stats;
 mod trace;
 
 pub type BlockNumber = String;
@@ -14,12 +17,19 @@ pub use self::block_template::{
     BlockTemplate, CellbaseTemplate, TransactionTemplate, UncleTemplate,
 };
 pub use self::blockchain::{
-    Block, CellInput, CellOutput, Header, OutPoint, Script, Seal, Transaction, UncleBlock, Witness,
+    Block, BlockExt, BlockVerbosity, BlockView, BlockWithTxHashes, CellInput, CellOutput, Header,
+    OutPoint, Script, Seal, Status, Transaction, TransactionWithStatus, TxStatus, UncleBlock,
+    Witness,
 };
 pub use self::bytes::Bytes;
-pub use self::cell::{CellOutputWithOutPoint, CellWithStatus};
-pub use self::net::{Node, NodeAddress};
+pub use self::cell::{
+    CellOutputWithOutPoint, CellWithStatus, IndexedCell, IndexedCellStatus, IndexedCellsPage,
+};
+pub use self::consensus::{Checkpoint, Consensus, ProposalWindow};
+pub use self::net::{BannedAddress, Node, NodeAddress, PeerDirection, PeerSyncState, RemoteNode};
+pub use self::pool::{FeeRate, PoolEntry, PoolSet, PoolSetInfo, RawTxPool, TxPoolInfo};
 pub use self::proposal_short_id::ProposalShortId;
+pub use self::stats::{BlockchainInfo, ColumnStatistics};
 pub use self::trace::{Action, TxTrace};
 pub use ckb_core::Version;
 pub use jsonrpc_core::types::{error, id, params, request, response, version};
@@ -1,3 +1,5 @@
+use crate::BlockNumber;
+use numext_fixed_hash::H256;
 use serde_derive::{Deserialize, Serialize};
 
 // TODO add more fields from PeerIdentifyInfo
@@ -13,3 +15,49 @@ pub struct NodeAddress {
     pub address: String,
     pub score: u8,
 }
+
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum PeerDirection {
+    Inbound,
+    Outbound,
+    /// A short-lived outbound connection to test whether an address is reachable
+    Feeler,
+}
+
+/// A connected peer
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct RemoteNode {
+    pub version: String,
+    pub node_id: String,
+    pub addresses: Vec<NodeAddress>,
+    pub direction: PeerDirection,
+    /// Milliseconds since the connection was established
+    pub connected_duration: String,
+    /// The round trip time of the last ping in milliseconds
+    pub last_ping_duration: Option<String>,
+    /// The score in the peer store
+    pub score: Option<i32>,
+    /// `None` when the peer has not opened the sync protocol
+    pub sync_state: Option<PeerSyncState>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct PeerSyncState {
+    pub best_known_header_hash: Option<H256>,
+    pub best_known_header_number: Option<BlockNumber>,
+    pub last_common_header_hash: Option<H256>,
+    pub last_common_header_number: Option<BlockNumber>,
+    /// The number of requested blocks not received yet
+    pub inflight_count: String,
+    /// Protected peers are not disconnected when their chain falls behind
+    pub is_protected: bool,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct BannedAddress {
+    pub address: String,
+    pub ban_until: String,
+    pub ban_reason: String,
+    pub created_at: String,
+}
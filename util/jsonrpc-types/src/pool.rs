@@ -0,0 +1,65 @@
+use crate::Cycle;
+use numext_fixed_hash::H256;
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct PoolSetInfo {
+    /// The number of transactions
+    pub count: String,
+    /// The serialized size of the transactions in bytes
+    pub bytes: String,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct TxPoolInfo {
+    pub pending: PoolSetInfo,
+    pub staging: PoolSetInfo,
+    pub orphan: PoolSetInfo,
+    pub conflict: PoolSetInfo,
+    /// The last time the transactions for the block template changed in milliseconds
+    pub last_txs_updated_at: String,
+}
+
+/// The hashes of the transactions in every set of the pool
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct RawTxPool {
+    pub pending: Vec<H256>,
+    pub staging: Vec<H256>,
+    pub orphan: Vec<H256>,
+    pub conflict: Vec<H256>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum PoolSet {
+    /// Waiting to be proposed
+    Pending,
+    /// Proposed and waiting to be committed
+    Staging,
+    /// Some inputs or deps are unknown
+    Orphan,
+    /// Evicted or conflicting, not in the pool anymore
+    Conflict,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct PoolEntry {
+    pub set: PoolSet,
+    /// `None` when the transaction has not been verified
+    pub cycles: Option<Cycle>,
+    /// The number of the inputs and deps referencing other staging transactions
+    pub refs_count: String,
+    pub size: String,
+    /// The staging transactions this one depends on, directly or indirectly
+    pub ancestors: Vec<H256>,
+    /// The staging transactions depending on this one, directly or indirectly
+    pub descendants: Vec<H256>,
+    /// The time the transaction entered the pool in milliseconds
+    pub timestamp: String,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct FeeRate {
+    /// The fee in shannons per 1000 bytes of the serialized transaction
+    pub fee_rate: String,
+}
@@ -0,0 +1,34 @@
+use crate::BlockNumber;
+use numext_fixed_hash::H256;
+use numext_fixed_uint::U256;
+use serde_derive::{Deserialize, Serialize};
+
+/// Statistics of a database column, the fields not reported by the database are null
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct ColumnStatistics {
+    pub name: String,
+    pub estimate_num_keys: Option<String>,
+    pub total_sst_files_size: Option<String>,
+    pub mem_tables_size: Option<String>,
+    pub table_readers_mem: Option<String>,
+    pub block_cache_usage: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct BlockchainInfo {
+    /// The chain id of the consensus
+    pub chain: String,
+    pub genesis_hash: H256,
+    pub tip_hash: H256,
+    pub tip_number: BlockNumber,
+    /// The best header received from the peers, the blocks up to it may not be downloaded yet
+    pub best_known_header_hash: H256,
+    pub best_known_header_number: BlockNumber,
+    /// The node is still catching up with the network when the tip is older than 1 hour
+    pub is_initial_block_download: bool,
+    /// The median timestamp of the latest blocks
+    pub median_time: String,
+    /// The difficulty of the tip block
+    pub difficulty: U256,
+    pub warnings: Vec<String>,
+}
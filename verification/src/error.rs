@@ -1,5 +1,5 @@
 use ckb_core::BlockNumber;
-use ckb_script::ScriptError;
+use ckb_script::TransactionScriptError;
 use failure::Fail;
 use numext_fixed_hash::H256;
 use numext_fixed_uint::U256;
@@ -142,7 +142,7 @@ pub enum TransactionError {
     /// Sum of all outputs capacity exceed sum of all inputs in the transaction
     OutputsSumOverflow,
     InvalidScript,
-    ScriptFailure(ScriptError),
+    ScriptFailure(TransactionScriptError),
     InvalidSignature,
     Conflict,
     Unknown,